@@ -0,0 +1,9 @@
+-- Declarative rules deciding which agent tool calls are approved, denied or
+-- sent to a human, one policy per project
+CREATE TABLE project_approval_policies (
+    project_id  BLOB PRIMARY KEY,
+    policy      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Durable record of tool approval requests, so pending questions and their
+-- outcome survive server restarts
+CREATE TABLE approvals (
+    id                              TEXT PRIMARY KEY,
+    execution_process_id            BLOB NOT NULL,
+    tool_name                       TEXT NOT NULL,
+    tool_input                      TEXT NOT NULL,
+    tool_call_metadata              TEXT NOT NULL,
+    status                          TEXT NOT NULL DEFAULT 'pending'
+                                       CHECK (status IN ('pending', 'approved', 'denied', 'timedout', 'interrupted')),
+    reason                          TEXT,
+    policy_rule                     TEXT,
+    resumed_execution_process_id    BLOB,
+    created_at                      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    timeout_at                      TEXT NOT NULL,
+    responded_at                    TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approvals_execution_process_id ON approvals(execution_process_id);
+CREATE INDEX idx_approvals_status ON approvals(status);
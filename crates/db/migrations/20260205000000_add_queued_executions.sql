@@ -0,0 +1,17 @@
+-- Optional per-project cap on concurrently running coding agents
+ALTER TABLE projects ADD COLUMN max_concurrent_coding_agents INTEGER;
+
+-- Coding agent starts waiting for a free slot, started oldest first
+CREATE TABLE queued_executions (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL,
+    session_id      BLOB NOT NULL,
+    executor_action TEXT NOT NULL,
+    run_reason      TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_executions_workspace_id ON queued_executions(workspace_id);
+CREATE INDEX idx_queued_executions_created_at ON queued_executions(created_at);
@@ -0,0 +1,21 @@
+-- "task_id is blocked by blocked_by_task_id"
+CREATE TABLE task_dependencies (
+    task_id            BLOB NOT NULL,
+    blocked_by_task_id BLOB NOT NULL,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, blocked_by_task_id),
+    CHECK (task_id != blocked_by_task_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (blocked_by_task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_dependencies_blocked_by_task_id ON task_dependencies(blocked_by_task_id);
+
+-- How to start a blocked task once all of its blockers are done
+CREATE TABLE task_auto_starts (
+    task_id             BLOB PRIMARY KEY,
+    executor_profile_id TEXT NOT NULL,
+    repos               TEXT NOT NULL,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
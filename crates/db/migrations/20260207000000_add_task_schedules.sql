@@ -0,0 +1,21 @@
+-- Cron schedules that start a fresh attempt of a task when they fire
+CREATE TABLE task_schedules (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    cron                TEXT NOT NULL,
+    executor_profile_id TEXT NOT NULL,
+    repos               TEXT NOT NULL,
+    -- Use the task as a template and run each firing as a new task
+    create_new_task     INTEGER NOT NULL DEFAULT 0,
+    enabled             INTEGER NOT NULL DEFAULT 1,
+    next_run_at         TEXT,
+    last_run_at         TEXT,
+    last_workspace_id   BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_task_id ON task_schedules(task_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(next_run_at);
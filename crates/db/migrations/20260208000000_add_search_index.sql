@@ -0,0 +1,95 @@
+-- Searchable text from tasks, coding agent turns and normalized log entries.
+-- source_key is the id of the row the text comes from (task, turn or
+-- execution process); entry_index tells log entries of a process apart.
+CREATE TABLE search_documents (
+    id                   INTEGER PRIMARY KEY,
+    source               TEXT NOT NULL
+                            CHECK (source IN ('task', 'prompt', 'summary', 'assistant_message', 'command')),
+    source_key           BLOB NOT NULL,
+    entry_index          INTEGER NOT NULL DEFAULT 0,
+    task_id              BLOB NOT NULL,
+    execution_process_id BLOB,
+    content              TEXT NOT NULL,
+    UNIQUE (source, source_key, entry_index),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_search_documents_task_id ON search_documents(task_id);
+CREATE INDEX idx_search_documents_execution_process_id ON search_documents(execution_process_id);
+
+CREATE VIRTUAL TABLE search_fts USING fts5(
+    content,
+    content = 'search_documents',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61'
+);
+
+-- Keep the FTS index in sync with search_documents
+CREATE TRIGGER search_documents_ai AFTER INSERT ON search_documents BEGIN
+    INSERT INTO search_fts (rowid, content) VALUES (NEW.id, NEW.content);
+END;
+
+CREATE TRIGGER search_documents_ad AFTER DELETE ON search_documents BEGIN
+    INSERT INTO search_fts (search_fts, rowid, content) VALUES ('delete', OLD.id, OLD.content);
+END;
+
+CREATE TRIGGER search_documents_au AFTER UPDATE OF content ON search_documents BEGIN
+    INSERT INTO search_fts (search_fts, rowid, content) VALUES ('delete', OLD.id, OLD.content);
+    INSERT INTO search_fts (rowid, content) VALUES (NEW.id, NEW.content);
+END;
+
+-- Tasks
+CREATE TRIGGER search_tasks_ai AFTER INSERT ON tasks BEGIN
+    INSERT INTO search_documents (source, source_key, task_id, content)
+    VALUES ('task', NEW.id, NEW.id, NEW.title || char(10) || COALESCE(NEW.description, ''));
+END;
+
+CREATE TRIGGER search_tasks_au AFTER UPDATE OF title, description ON tasks BEGIN
+    UPDATE search_documents
+    SET content = NEW.title || char(10) || COALESCE(NEW.description, '')
+    WHERE source = 'task' AND source_key = NEW.id;
+END;
+
+-- Coding agent turn prompts and summaries
+CREATE TRIGGER search_turns_prompt_ai AFTER INSERT ON coding_agent_turns
+WHEN NEW.prompt IS NOT NULL BEGIN
+    INSERT INTO search_documents (source, source_key, task_id, execution_process_id, content)
+    SELECT 'prompt', NEW.id, w.task_id, NEW.execution_process_id, NEW.prompt
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = NEW.execution_process_id;
+END;
+
+CREATE TRIGGER search_turns_summary_au AFTER UPDATE OF summary ON coding_agent_turns
+WHEN NEW.summary IS NOT NULL BEGIN
+    INSERT INTO search_documents (source, source_key, task_id, execution_process_id, content)
+    SELECT 'summary', NEW.id, w.task_id, NEW.execution_process_id, NEW.summary
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = NEW.execution_process_id
+    ON CONFLICT (source, source_key, entry_index) DO UPDATE SET content = excluded.content;
+END;
+
+-- Backfill existing rows
+INSERT INTO search_documents (source, source_key, task_id, content)
+SELECT 'task', id, id, title || char(10) || COALESCE(description, '')
+FROM tasks;
+
+INSERT INTO search_documents (source, source_key, task_id, execution_process_id, content)
+SELECT 'prompt', cat.id, w.task_id, cat.execution_process_id, cat.prompt
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id
+WHERE cat.prompt IS NOT NULL;
+
+INSERT INTO search_documents (source, source_key, task_id, execution_process_id, content)
+SELECT 'summary', cat.id, w.task_id, cat.execution_process_id, cat.summary
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id
+WHERE cat.summary IS NOT NULL;
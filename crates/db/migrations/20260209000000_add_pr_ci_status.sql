@@ -0,0 +1,3 @@
+-- Combined CI status and failing checks of a PR, refreshed by the PR monitor
+ALTER TABLE merges ADD COLUMN pr_ci_status TEXT;
+ALTER TABLE merges ADD COLUMN pr_failing_checks TEXT NOT NULL DEFAULT '[]';
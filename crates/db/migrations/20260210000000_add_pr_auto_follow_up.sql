@@ -0,0 +1,12 @@
+-- Opt-in per project: send new PR review comments and CI failures to the agent
+ALTER TABLE projects ADD COLUMN auto_follow_up_pr INTEGER NOT NULL DEFAULT 0;
+
+-- Review comments and failed checks of a PR already sent to the agent
+CREATE TABLE pr_follow_up_items (
+    id          BLOB PRIMARY KEY,
+    merge_id    BLOB NOT NULL,
+    item_key    TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    UNIQUE (merge_id, item_key)
+);
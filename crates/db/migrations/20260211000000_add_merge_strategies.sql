@@ -0,0 +1,10 @@
+-- How direct merges of a repo's task branches land on the base branch, and
+-- the commit message they are created with
+ALTER TABLE repos ADD COLUMN merge_strategy TEXT NOT NULL DEFAULT 'squash'
+    CHECK (merge_strategy IN ('squash', 'rebase', 'merge_commit'));
+ALTER TABLE repos ADD COLUMN merge_commit_template TEXT;
+
+-- Strategy a direct merge was made with; every earlier direct merge was a squash
+ALTER TABLE merges ADD COLUMN merge_strategy TEXT
+    CHECK (merge_strategy IN ('squash', 'rebase', 'merge_commit'));
+UPDATE merges SET merge_strategy = 'squash' WHERE merge_type = 'direct';
@@ -0,0 +1,2 @@
+-- Agent session a forked session resumes until it runs its own turns
+ALTER TABLE sessions ADD COLUMN forked_agent_session_id TEXT;
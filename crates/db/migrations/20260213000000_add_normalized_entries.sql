@@ -0,0 +1,14 @@
+-- Normalizer version that produced the stored entries of a finished process;
+-- NULL until the process has been normalized
+ALTER TABLE execution_processes ADD COLUMN normalizer_version INTEGER;
+
+-- Final normalized conversation entries of finished processes
+CREATE TABLE execution_process_normalized_entries (
+    execution_id  BLOB NOT NULL,
+    entry_index   INTEGER NOT NULL,
+    entry         TEXT NOT NULL,
+    PRIMARY KEY (execution_id, entry_index),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_processes_normalizer_version ON execution_processes(normalizer_version);
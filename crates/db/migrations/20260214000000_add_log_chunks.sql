@@ -0,0 +1,11 @@
+-- zstd-compressed JSONL logs of finished processes, replacing their rows in
+-- execution_process_logs
+CREATE TABLE execution_process_log_chunks (
+    execution_id     BLOB NOT NULL,
+    chunk_index      INTEGER NOT NULL,
+    data             BLOB NOT NULL,
+    byte_size        INTEGER NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, chunk_index),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- When the project last opted in to PR follow-ups. Feedback that was already
+-- on its open PRs by then is marked as handled instead of being sent.
+ALTER TABLE projects ADD COLUMN auto_follow_up_pr_since TEXT;
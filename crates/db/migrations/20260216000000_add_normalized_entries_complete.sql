@@ -0,0 +1,3 @@
+-- Whether the stored entries of a process come from a normalization that ran
+-- to the end of its logs. Raw logs are only dropped for complete entries.
+ALTER TABLE execution_processes ADD COLUMN normalized_entries_complete INTEGER NOT NULL DEFAULT 0;
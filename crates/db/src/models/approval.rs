@@ -0,0 +1,364 @@
+use chrono::{DateTime, Utc};
+use executors::approvals::ToolCallMetadata;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_status", rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRecordStatus {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+    /// The server stopped while the approval was pending
+    Interrupted,
+}
+
+impl From<&ApprovalStatus> for ApprovalRecordStatus {
+    fn from(status: &ApprovalStatus) -> Self {
+        match status {
+            ApprovalStatus::Pending => Self::Pending,
+            ApprovalStatus::Approved => Self::Approved,
+            ApprovalStatus::Denied { .. } => Self::Denied,
+            ApprovalStatus::TimedOut => Self::TimedOut,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct Approval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    #[ts(type = "JsonValue")]
+    pub tool_input: Json<serde_json::Value>,
+    #[ts(type = "{ tool_call_id: string }")]
+    pub tool_call_metadata: Json<ToolCallMetadata>,
+    pub status: ApprovalRecordStatus,
+    /// Denial reason given by the user or the approval policy
+    pub reason: Option<String>,
+    /// Approval policy rule that decided the request without asking
+    pub policy_rule: Option<String>,
+    /// Follow-up process started to re-ask after an interruption
+    pub resumed_execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl Approval {
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT
+                id as "id!",
+                execution_process_id as "execution_process_id!: Uuid",
+                tool_name,
+                tool_input as "tool_input!: Json<serde_json::Value>",
+                tool_call_metadata as "tool_call_metadata!: Json<ToolCallMetadata>",
+                status as "status!: ApprovalRecordStatus",
+                reason,
+                policy_rule,
+                resumed_execution_process_id as "resumed_execution_process_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                timeout_at as "timeout_at!: DateTime<Utc>",
+                responded_at as "responded_at?: DateTime<Utc>"
+               FROM approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Approval history of an execution process, oldest first
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT
+                id as "id!",
+                execution_process_id as "execution_process_id!: Uuid",
+                tool_name,
+                tool_input as "tool_input!: Json<serde_json::Value>",
+                tool_call_metadata as "tool_call_metadata!: Json<ToolCallMetadata>",
+                status as "status!: ApprovalRecordStatus",
+                reason,
+                policy_rule,
+                resumed_execution_process_id as "resumed_execution_process_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                timeout_at as "timeout_at!: DateTime<Utc>",
+                responded_at as "responded_at?: DateTime<Utc>"
+               FROM approvals
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record a new approval request. `status` is `Pending` unless an approval
+    /// policy rule already decided it.
+    pub async fn create(
+        pool: &SqlitePool,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        policy_rule: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let tool_input = Json(&request.tool_input);
+        let tool_call_metadata = Json(ToolCallMetadata {
+            tool_call_id: request.tool_call_id.clone(),
+        });
+        let record_status = ApprovalRecordStatus::from(status);
+        let reason = denial_reason(status);
+        let responded_at = (record_status != ApprovalRecordStatus::Pending).then(Utc::now);
+
+        sqlx::query!(
+            r#"INSERT INTO approvals (
+                id, execution_process_id, tool_name, tool_input, tool_call_metadata,
+                status, reason, policy_rule, created_at, timeout_at, responded_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+            request.id,
+            request.execution_process_id,
+            request.tool_name,
+            tool_input,
+            tool_call_metadata,
+            record_status,
+            reason,
+            policy_rule,
+            request.created_at,
+            request.timeout_at,
+            responded_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the outcome of a pending approval
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+    ) -> Result<(), sqlx::Error> {
+        let record_status = ApprovalRecordStatus::from(status);
+        let reason = denial_reason(status);
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE approvals
+               SET status = $1, reason = $2, responded_at = $3
+               WHERE id = $4 AND status = 'pending'"#,
+            record_status,
+            reason,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark the pending approvals of a process that is no longer running as
+    /// interrupted. Returns the number of approvals affected.
+    pub async fn mark_interrupted(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET status = 'interrupted', responded_at = $1
+               WHERE execution_process_id = $2 AND status = 'pending'"#,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn set_resumed_execution_process_id(
+        pool: &SqlitePool,
+        id: &str,
+        resumed_execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE approvals
+               SET resumed_execution_process_id = $1
+               WHERE id = $2"#,
+            resumed_execution_process_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn denial_reason(status: &ApprovalStatus) -> Option<String> {
+    match status {
+        ApprovalStatus::Denied { reason } => reason.clone(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::approvals::CreateApprovalRequest;
+
+    use super::*;
+    use crate::models::{
+        execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+        test_utils::{process, session},
+    };
+
+    fn request(execution_process_id: Uuid, tool_call_id: &str) -> ApprovalRequest {
+        ApprovalRequest::from_create(
+            CreateApprovalRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: serde_json::json!({ "command": "cargo test" }),
+                tool_call_id: tool_call_id.to_string(),
+            },
+            execution_process_id,
+            60,
+        )
+    }
+
+    async fn status(pool: &SqlitePool, id: &str) -> ApprovalRecordStatus {
+        Approval::find_by_id(pool, id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status
+    }
+
+    #[sqlx::test]
+    async fn records_responses_only_for_pending_approvals(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let id = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+
+        let asked = request(id, "asked");
+        Approval::create(&pool, &asked, &ApprovalStatus::Pending, None)
+            .await
+            .unwrap();
+        let stored = Approval::find_by_id(&pool, &asked.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalRecordStatus::Pending);
+        assert_eq!(stored.tool_call_metadata.0.tool_call_id, "asked");
+        assert!(stored.responded_at.is_none());
+
+        let denied = ApprovalStatus::Denied {
+            reason: Some("Use nextest".to_string()),
+        };
+        Approval::update_status(&pool, &asked.id, &denied)
+            .await
+            .unwrap();
+        // A late timeout does not overwrite the answer
+        Approval::update_status(&pool, &asked.id, &ApprovalStatus::TimedOut)
+            .await
+            .unwrap();
+        let stored = Approval::find_by_id(&pool, &asked.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalRecordStatus::Denied);
+        assert_eq!(stored.reason.as_deref(), Some("Use nextest"));
+        assert!(stored.responded_at.is_some());
+
+        let by_rule = request(id, "by-rule");
+        Approval::create(&pool, &by_rule, &ApprovalStatus::Approved, Some("cargo"))
+            .await
+            .unwrap();
+        let stored = Approval::find_by_id(&pool, &by_rule.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalRecordStatus::Approved);
+        assert_eq!(stored.policy_rule.as_deref(), Some("cargo"));
+        assert!(stored.responded_at.is_some());
+    }
+
+    #[sqlx::test]
+    async fn interrupts_pending_approvals_of_a_process(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let id = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Failed,
+        )
+        .await;
+        let other = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+
+        let answered = request(id, "answered");
+        let pending = request(id, "pending");
+        let elsewhere = request(other, "elsewhere");
+        for approval in [&answered, &pending, &elsewhere] {
+            Approval::create(&pool, approval, &ApprovalStatus::Pending, None)
+                .await
+                .unwrap();
+        }
+        Approval::update_status(&pool, &answered.id, &ApprovalStatus::Approved)
+            .await
+            .unwrap();
+
+        assert_eq!(Approval::mark_interrupted(&pool, id).await.unwrap(), 1);
+        assert_eq!(
+            status(&pool, &answered.id).await,
+            ApprovalRecordStatus::Approved
+        );
+        assert_eq!(
+            status(&pool, &pending.id).await,
+            ApprovalRecordStatus::Interrupted
+        );
+        assert_eq!(
+            status(&pool, &elsewhere.id).await,
+            ApprovalRecordStatus::Pending
+        );
+
+        let resumed = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+        Approval::set_resumed_execution_process_id(&pool, &pending.id, resumed)
+            .await
+            .unwrap();
+        let history = Approval::find_by_execution_process_id(&pool, id)
+            .await
+            .unwrap();
+        assert_eq!(
+            history.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(),
+            vec![answered.id.as_str(), pending.id.as_str()]
+        );
+        assert_eq!(history[1].resumed_execution_process_id, Some(resumed));
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use utils::approvals::APPROVAL_TIMEOUT_SECONDS;
+use uuid::Uuid;
+
+/// What happens to a tool call matched by an approval rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleAction {
+    Approve,
+    Deny,
+    #[default]
+    Ask,
+}
+
+/// Matches on the normalized action of a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalRuleMatcher {
+    /// Glob over the edited path relative to the workspace, e.g. `src/**`
+    FileEdit { path_glob: String },
+    /// Regex searched in the command line, e.g. `^cargo (test|check)\b`
+    CommandRun { command_regex: String },
+    /// Host of the fetched URL; `*.example.com` also matches subdomains
+    WebFetch { host: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    /// Shown in the conversation log when the rule decides a tool call
+    pub name: String,
+    pub action: ApprovalRuleAction,
+    /// Exact tool name reported by the agent; any tool when unset
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    /// Any action when unset
+    #[serde(default)]
+    pub matcher: Option<ApprovalRuleMatcher>,
+}
+
+/// When several rules match a tool call, the most restrictive action wins:
+/// deny over ask over approve. Approve rules on commands only match when every
+/// command of a chained command line matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ApprovalPolicy {
+    #[serde(default)]
+    pub rules: Vec<ApprovalRule>,
+    /// Applied to tool calls no rule matches
+    #[serde(default)]
+    pub default_action: ApprovalRuleAction,
+    /// How long to wait for a human before the request times out
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: i64,
+}
+
+fn default_timeout_seconds() -> i64 {
+    APPROVAL_TIMEOUT_SECONDS
+}
+
+impl Default for ApprovalPolicy {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_action: ApprovalRuleAction::Ask,
+            timeout_seconds: default_timeout_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ProjectApprovalPolicy {
+    pub project_id: Uuid,
+    #[ts(type = "ApprovalPolicy")]
+    pub policy: Json<ApprovalPolicy>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectApprovalPolicy {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectApprovalPolicy,
+            r#"SELECT
+                project_id as "project_id!: Uuid",
+                policy as "policy!: Json<ApprovalPolicy>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_approval_policies
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The project's policy, or the default policy that asks for every tool call
+    pub async fn policy_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<ApprovalPolicy, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .map(|row| row.policy.0)
+            .unwrap_or_default())
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        policy: &ApprovalPolicy,
+    ) -> Result<Self, sqlx::Error> {
+        let policy = Json(policy);
+        sqlx::query_as!(
+            ProjectApprovalPolicy,
+            r#"INSERT INTO project_approval_policies (project_id, policy)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   policy = excluded.policy,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING
+                project_id as "project_id!: Uuid",
+                policy as "policy!: Json<ApprovalPolicy>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            policy
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_approval_policies WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use executors::logs::TurnTokenUsage;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
@@ -12,6 +13,11 @@ pub struct CodingAgentTurn {
     pub prompt: Option<String>,           // The prompt sent to the executor
     pub summary: Option<String>,          // Final assistant message/summary
     pub seen: bool,                       // Whether user has viewed this turn
+    pub model: Option<String>,            // Model that served the turn, when reported
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cache_read_tokens: Option<i64>,
+    pub cache_write_tokens: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -22,6 +28,24 @@ pub struct CreateCodingAgentTurn {
     pub prompt: Option<String>,
 }
 
+/// Token usage of a single turn together with the entities it belongs to,
+/// used to build usage aggregates.
+#[derive(Debug, Clone, FromRow)]
+pub struct CodingAgentTurnUsage {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub executor: Option<String>,
+    pub variant: Option<String>,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub created_at: DateTime<Utc>,
+}
+
 impl CodingAgentTurn {
     /// Find coding agent turn by execution process ID
     pub async fn find_by_execution_process_id(
@@ -37,6 +61,11 @@ impl CodingAgentTurn {
                 prompt,
                 summary,
                 seen as "seen!: bool",
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -60,6 +89,11 @@ impl CodingAgentTurn {
                 prompt,
                 summary,
                 seen as "seen!: bool",
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -100,6 +134,11 @@ impl CodingAgentTurn {
                 prompt,
                 summary,
                 seen as "seen!: bool",
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -157,6 +196,83 @@ impl CodingAgentTurn {
         Ok(())
     }
 
+    /// Record the token usage reported by the coding agent for this turn
+    pub async fn update_token_usage(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TurnTokenUsage,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_read_tokens = usage.cache_read_tokens as i64;
+        let cache_write_tokens = usage.cache_write_tokens as i64;
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET model = $1, input_tokens = $2, output_tokens = $3,
+                   cache_read_tokens = $4, cache_write_tokens = $5, updated_at = $6
+               WHERE execution_process_id = $7"#,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find token usage of all turns that reported it, optionally narrowed to a
+    /// project, task or workspace and to turns created within `[from, to)`.
+    pub async fn find_usage(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        task_id: Option<Uuid>,
+        workspace_id: Option<Uuid>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<CodingAgentTurnUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentTurnUsage,
+            r#"SELECT
+                cat.execution_process_id as "execution_process_id!: Uuid",
+                s.workspace_id as "workspace_id!: Uuid",
+                w.task_id as "task_id!: Uuid",
+                t.project_id as "project_id!: Uuid",
+                json_extract(ep.executor_action, '$.typ.executor_profile_id.executor') as "executor?: String",
+                json_extract(ep.executor_action, '$.typ.executor_profile_id.variant') as "variant?: String",
+                cat.model,
+                COALESCE(cat.input_tokens, 0) as "input_tokens!: i64",
+                COALESCE(cat.output_tokens, 0) as "output_tokens!: i64",
+                COALESCE(cat.cache_read_tokens, 0) as "cache_read_tokens!: i64",
+                COALESCE(cat.cache_write_tokens, 0) as "cache_write_tokens!: i64",
+                cat.created_at as "created_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON cat.execution_process_id = ep.id
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE cat.input_tokens IS NOT NULL
+                 AND ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR w.task_id = $2)
+                 AND ($3 IS NULL OR s.workspace_id = $3)
+                 AND ($4 IS NULL OR cat.created_at >= $4)
+                 AND ($5 IS NULL OR cat.created_at < $5)
+               ORDER BY cat.created_at ASC"#,
+            project_id,
+            task_id,
+            workspace_id,
+            from,
+            to
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Mark all coding agent turns for a workspace as seen
     pub async fn mark_seen_by_workspace_id(
         pool: &SqlitePool,
@@ -55,6 +55,7 @@ pub enum ExecutionProcessStatus {
 pub enum ExecutionProcessRunReason {
     SetupScript,
     CleanupScript,
+    VerificationScript,
     CodingAgent,
     DevServer,
 }
@@ -113,6 +114,15 @@ pub struct LatestProcessInfo {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// First start and last completion of the non-dev-server processes of a workspace
+#[derive(Debug, Clone, FromRow)]
+pub struct WorkspaceRunSpan {
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Number of those processes that are still running
+    pub running: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ExecutorActionField {
@@ -305,17 +315,20 @@ impl ExecutionProcess {
     }
 
     /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// or coding agent runs queued for a free slot
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
     ) -> Result<bool, sqlx::Error> {
         let count: i64 = sqlx::query_scalar!(
-            r#"SELECT COUNT(*) as "count!: i64"
-               FROM execution_processes ep
-               JOIN sessions s ON ep.session_id = s.id
-               WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
-                 AND ep.run_reason != 'devserver'"#,
+            r#"SELECT (SELECT COUNT(*)
+                       FROM execution_processes ep
+                       JOIN sessions s ON ep.session_id = s.id
+                       WHERE s.workspace_id = $1
+                         AND ep.status = 'running'
+                         AND ep.run_reason != 'devserver')
+                    + (SELECT COUNT(*) FROM queued_executions WHERE workspace_id = $1)
+                    AS "count!: i64""#,
             workspace_id
         )
         .fetch_one(pool)
@@ -365,16 +378,21 @@ impl ExecutionProcess {
             "Finding latest coding agent turn session id for session {}",
             session_id
         );
+        // A forked session resumes the agent session it was forked from
+        // until it has turns of its own
         let row = sqlx::query!(
-            r#"SELECT cat.agent_session_id
-               FROM execution_processes ep
-               JOIN coding_agent_turns cat ON ep.id = cat.execution_process_id
-               WHERE ep.session_id = $1
-                 AND ep.run_reason = 'codingagent'
-                 AND ep.dropped = FALSE
-                 AND cat.agent_session_id IS NOT NULL
-               ORDER BY ep.created_at DESC
-               LIMIT 1"#,
+            r#"SELECT COALESCE(
+                   (SELECT cat.agent_session_id
+                    FROM execution_processes ep
+                    JOIN coding_agent_turns cat ON ep.id = cat.execution_process_id
+                    WHERE ep.session_id = $1
+                      AND ep.run_reason = 'codingagent'
+                      AND ep.dropped = FALSE
+                      AND cat.agent_session_id IS NOT NULL
+                    ORDER BY ep.created_at DESC
+                    LIMIT 1),
+                   (SELECT forked_agent_session_id FROM sessions WHERE id = $1)
+               ) AS "agent_session_id?: String""#,
             session_id
         )
         .fetch_optional(pool)
@@ -715,14 +733,14 @@ impl ExecutionProcess {
             JOIN sessions s ON ep.session_id = s.id
             JOIN workspaces w ON s.workspace_id = w.id
             WHERE w.archived = $1
-              AND ep.run_reason IN ('codingagent', 'setupscript', 'cleanupscript')
+              AND ep.run_reason IN ('codingagent', 'setupscript', 'cleanupscript', 'verificationscript')
               AND ep.dropped = FALSE
               AND ep.created_at = (
                   SELECT MAX(ep2.created_at)
                   FROM execution_processes ep2
                   JOIN sessions s2 ON ep2.session_id = s2.id
                   WHERE s2.workspace_id = s.workspace_id
-                    AND ep2.run_reason IN ('codingagent', 'setupscript', 'cleanupscript')
+                    AND ep2.run_reason IN ('codingagent', 'setupscript', 'cleanupscript', 'verificationscript')
                     AND ep2.dropped = FALSE
               )
             "#,
@@ -762,4 +780,106 @@ impl ExecutionProcess {
 
         Ok(rows.into_iter().collect())
     }
+
+    /// Time span covered by the agent and script processes of a workspace
+    pub async fn find_run_span_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<WorkspaceRunSpan, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRunSpan,
+            r#"
+            SELECT
+                MIN(ep.started_at) as "started_at?: DateTime<Utc>",
+                MAX(ep.completed_at) as "completed_at?: DateTime<Utc>",
+                COUNT(CASE WHEN ep.status = 'running' THEN 1 END) as "running!: i64"
+            FROM execution_processes ep
+            JOIN sessions s ON ep.session_id = s.id
+            WHERE s.workspace_id = $1
+              AND ep.run_reason != 'devserver'
+              AND ep.dropped = FALSE
+            "#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Number of running coding agents, across all projects or within one
+    pub async fn count_running_coding_agents(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE ep.status = 'running'
+                 AND ep.run_reason = 'codingagent'
+                 AND ($1 IS NULL OR t.project_id = $1)"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_utils::{process, session};
+
+    #[sqlx::test]
+    async fn counts_running_coding_agents_per_project(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let other_session_id = session(&pool).await;
+        for (session_id, run_reason, status) in [
+            (
+                session_id,
+                ExecutionProcessRunReason::CodingAgent,
+                ExecutionProcessStatus::Running,
+            ),
+            (
+                session_id,
+                ExecutionProcessRunReason::CodingAgent,
+                ExecutionProcessStatus::Completed,
+            ),
+            (
+                session_id,
+                ExecutionProcessRunReason::DevServer,
+                ExecutionProcessStatus::Running,
+            ),
+            (
+                other_session_id,
+                ExecutionProcessRunReason::CodingAgent,
+                ExecutionProcessStatus::Running,
+            ),
+        ] {
+            process(&pool, session_id, run_reason, status).await;
+        }
+        let any_process = ExecutionProcess::find_by_session_id(&pool, session_id, false)
+            .await
+            .unwrap()[0]
+            .id;
+        let project_id = ExecutionProcess::load_context(&pool, any_process)
+            .await
+            .unwrap()
+            .project
+            .id;
+
+        assert_eq!(
+            ExecutionProcess::count_running_coding_agents(&pool, None)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            ExecutionProcess::count_running_coding_agents(&pool, Some(project_id))
+                .await
+                .unwrap(),
+            1
+        );
+    }
 }
@@ -5,6 +5,10 @@ use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+/// Uncompressed bytes of JSONL logs per compressed chunk
+const LOG_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+const LOG_COMPRESSION_LEVEL: i32 = 3;
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -14,25 +18,52 @@ pub struct ExecutionProcessLogs {
 }
 
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find logs by execution process ID, compressed chunks first
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
-            ExecutionProcessLogs,
-            r#"SELECT 
-                execution_id as "execution_id!: Uuid",
-                logs,
+        let chunks = sqlx::query!(
+            r#"SELECT
+                data,
                 byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>"
-               FROM execution_process_logs 
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_log_chunks
                WHERE execution_id = $1
-               ORDER BY inserted_at ASC"#,
+               ORDER BY chunk_index ASC"#,
             execution_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+        let mut records = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let logs = zstd::decode_all(chunk.data.as_slice())
+                .map_err(|e| sqlx::Error::Decode(e.into()))?;
+            records.push(ExecutionProcessLogs {
+                execution_id,
+                logs: String::from_utf8(logs).map_err(|e| sqlx::Error::Decode(e.into()))?,
+                byte_size: chunk.byte_size,
+                inserted_at: chunk.created_at,
+            });
+        }
+
+        records.extend(
+            sqlx::query_as!(
+                ExecutionProcessLogs,
+                r#"SELECT 
+                    execution_id as "execution_id!: Uuid",
+                    logs,
+                    byte_size,
+                    inserted_at as "inserted_at!: DateTime<Utc>"
+                   FROM execution_process_logs 
+                   WHERE execution_id = $1
+                   ORDER BY inserted_at ASC"#,
+                execution_id
+            )
+            .fetch_all(pool)
+            .await?,
+        );
+        Ok(records)
     }
 
     /// Parse JSONL logs back into Vec<LogMsg>
@@ -66,4 +97,220 @@ impl ExecutionProcessLogs {
 
         Ok(())
     }
+
+    /// Move the uncompressed log rows of a process into zstd-compressed
+    /// chunks. Returns whether there was anything to compact.
+    pub async fn compact(pool: &SqlitePool, execution_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let lines = sqlx::query_scalar!(
+            r#"SELECT logs FROM execution_process_logs
+               WHERE execution_id = $1
+               ORDER BY inserted_at ASC"#,
+            execution_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if lines.is_empty() {
+            return Ok(false);
+        }
+
+        let mut next_index = sqlx::query_scalar!(
+            r#"SELECT COALESCE(MAX(chunk_index) + 1, 0) AS "next!: i64"
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut chunk = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            chunk.push_str(line);
+            if chunk.len() < LOG_CHUNK_SIZE && i + 1 < lines.len() {
+                continue;
+            }
+            let data = zstd::encode_all(chunk.as_bytes(), LOG_COMPRESSION_LEVEL)
+                .map_err(|e| sqlx::Error::Encode(e.into()))?;
+            let byte_size = chunk.len() as i64;
+            sqlx::query!(
+                r#"INSERT INTO execution_process_log_chunks (execution_id, chunk_index, data, byte_size)
+                   VALUES ($1, $2, $3, $4)"#,
+                execution_id,
+                next_index,
+                data,
+                byte_size
+            )
+            .execute(&mut *tx)
+            .await?;
+            next_index += 1;
+            chunk.clear();
+        }
+
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Finished processes that still have uncompressed log rows
+    pub async fn find_uncompacted_execution_ids(
+        pool: &SqlitePool,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT l.execution_id AS "execution_id!: Uuid"
+               FROM execution_process_logs l
+               JOIN execution_processes ep ON ep.id = l.execution_id
+               WHERE ep.status != 'running'
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Drop the raw logs of processes that completed before `cutoff`. Coding
+    /// agent logs are only dropped once entries from a complete normalization
+    /// of them are stored, so the whole conversation stays viewable.
+    pub async fn delete_completed_before(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let rows = sqlx::query!(
+            r#"DELETE FROM execution_process_logs
+               WHERE execution_id IN (
+                   SELECT id FROM execution_processes
+                   WHERE completed_at < $1
+                     AND (run_reason != 'codingagent' OR normalized_entries_complete = 1)
+               )"#,
+            cutoff
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        let chunks = sqlx::query!(
+            r#"DELETE FROM execution_process_log_chunks
+               WHERE execution_id IN (
+                   SELECT id FROM execution_processes
+                   WHERE completed_at < $1
+                     AND (run_reason != 'codingagent' OR normalized_entries_complete = 1)
+               )"#,
+            cutoff
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(rows + chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::models::{
+        execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+        execution_process_normalized_entries::ExecutionProcessNormalizedEntries,
+        test_utils::{process, session},
+    };
+
+    async fn append(pool: &SqlitePool, execution_id: Uuid, msg: LogMsg) {
+        let line = serde_json::to_string(&msg).unwrap();
+        ExecutionProcessLogs::append_log_line(pool, execution_id, &format!("{line}\n"))
+            .await
+            .unwrap();
+    }
+
+    async fn parsed_logs(pool: &SqlitePool, execution_id: Uuid) -> Vec<String> {
+        let records = ExecutionProcessLogs::find_by_execution_id(pool, execution_id)
+            .await
+            .unwrap();
+        ExecutionProcessLogs::parse_logs(&records)
+            .unwrap()
+            .iter()
+            .map(|msg| serde_json::to_string(msg).unwrap())
+            .collect()
+    }
+
+    #[sqlx::test]
+    async fn compacted_logs_read_back_unchanged(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let id = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        append(&pool, id, LogMsg::Stdout("first".to_string())).await;
+        append(&pool, id, LogMsg::Stderr("second".to_string())).await;
+        append(&pool, id, LogMsg::SessionId("session".to_string())).await;
+        let before = parsed_logs(&pool, id).await;
+
+        assert!(ExecutionProcessLogs::compact(&pool, id).await.unwrap());
+        assert!(!ExecutionProcessLogs::compact(&pool, id).await.unwrap());
+        assert_eq!(parsed_logs(&pool, id).await, before);
+
+        // Lines logged after compaction follow the compressed ones
+        append(&pool, id, LogMsg::Finished).await;
+        let mut expected = before;
+        expected.push(serde_json::to_string(&LogMsg::Finished).unwrap());
+        assert_eq!(parsed_logs(&pool, id).await, expected);
+    }
+
+    #[sqlx::test]
+    async fn keeps_logs_of_agent_turns_without_complete_entries(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let script = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::SetupScript,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        let complete = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, complete, 1, &[], true)
+            .await
+            .unwrap();
+        let incomplete = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, incomplete, 1, &[], false)
+            .await
+            .unwrap();
+        for id in [script, complete, incomplete] {
+            append(&pool, id, LogMsg::Stdout("output".to_string())).await;
+        }
+        ExecutionProcessLogs::compact(&pool, complete)
+            .await
+            .unwrap();
+
+        let cutoff = Utc::now() + Duration::minutes(1);
+        assert_eq!(
+            ExecutionProcessLogs::delete_completed_before(&pool, cutoff)
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(parsed_logs(&pool, script).await.is_empty());
+        assert!(parsed_logs(&pool, complete).await.is_empty());
+        assert_eq!(parsed_logs(&pool, incomplete).await.len(), 1);
+    }
 }
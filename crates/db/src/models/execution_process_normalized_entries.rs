@@ -0,0 +1,500 @@
+use executors::logs::NormalizedEntry;
+use serde::Serialize;
+use sqlx::{SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Which entries of a conversation to return. Without a cursor the page is
+/// the end of the conversation, so clients can show the latest entries first
+/// and page back from there.
+#[derive(Debug, Clone, Default)]
+pub struct NormalizedEntriesQuery {
+    /// Only entries before this entry index, the latest of them first
+    pub before: Option<usize>,
+    /// Only entries after this entry index, the earliest of them first
+    pub after: Option<usize>,
+    pub limit: usize,
+    /// `type` tags of `NormalizedEntryType` to keep, all when empty
+    pub entry_types: Vec<String>,
+    /// `action` tags of the `ActionType` of tool uses to keep, all when empty
+    pub action_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct IndexedNormalizedEntry {
+    pub entry_index: usize,
+    pub entry: NormalizedEntry,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct NormalizedEntriesPage {
+    /// In conversation order
+    pub entries: Vec<IndexedNormalizedEntry>,
+    /// Whether more entries match past this page, in the direction it was
+    /// read: earlier ones unless the query had an `after` cursor
+    pub has_more: bool,
+}
+
+impl NormalizedEntriesQuery {
+    /// Same checks as the SQL filter of `find_page`, for entries that are not
+    /// stored yet
+    fn matches(&self, entry: &NormalizedEntry) -> bool {
+        let Ok(entry_type) = serde_json::to_value(&entry.entry_type) else {
+            return false;
+        };
+        let tag_in = |tag: Option<&serde_json::Value>, tags: &[String]| {
+            tags.is_empty()
+                || tag
+                    .and_then(|tag| tag.as_str())
+                    .is_some_and(|tag| tags.iter().any(|t| t == tag))
+        };
+        tag_in(entry_type.get("type"), &self.entry_types)
+            && tag_in(
+                entry_type
+                    .get("action_type")
+                    .and_then(|action| action.get("action")),
+                &self.action_types,
+            )
+    }
+
+    /// Page through entries held in memory, e.g. of a running process
+    pub fn paginate(&self, entries: Vec<(usize, NormalizedEntry)>) -> NormalizedEntriesPage {
+        let mut matching: Vec<IndexedNormalizedEntry> = entries
+            .into_iter()
+            .filter(|(index, entry)| {
+                self.before.is_none_or(|before| *index < before)
+                    && self.after.is_none_or(|after| *index > after)
+                    && self.matches(entry)
+            })
+            .map(|(entry_index, entry)| IndexedNormalizedEntry { entry_index, entry })
+            .collect();
+        let has_more = matching.len() > self.limit;
+        if self.after.is_some() {
+            matching.truncate(self.limit);
+        } else {
+            matching.drain(..matching.len().saturating_sub(self.limit));
+        }
+        NormalizedEntriesPage {
+            entries: matching,
+            has_more,
+        }
+    }
+}
+
+/// Final normalized conversation entries of a finished execution process, so
+/// opening it doesn't replay its raw logs through the executor's normalizer.
+/// Entries are only valid for the normalizer version recorded on the process.
+pub struct ExecutionProcessNormalizedEntries;
+
+impl ExecutionProcessNormalizedEntries {
+    /// Whether the stored entries of a process are the output of
+    /// `normalizer_version`
+    pub async fn is_current(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        normalizer_version: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let stored_version = sqlx::query_scalar!(
+            r#"SELECT normalizer_version FROM execution_processes WHERE id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+        Ok(stored_version == Some(normalizer_version))
+    }
+
+    /// Entries of a process with their entry indices, or `None` when it has
+    /// not been normalized by `normalizer_version`
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        normalizer_version: i64,
+    ) -> Result<Option<Vec<(usize, NormalizedEntry)>>, sqlx::Error> {
+        if !Self::is_current(pool, execution_id, normalizer_version).await? {
+            return Ok(None);
+        }
+
+        let rows = sqlx::query!(
+            r#"SELECT entry_index, entry AS "entry!: Json<NormalizedEntry>"
+               FROM execution_process_normalized_entries
+               WHERE execution_id = $1
+               ORDER BY entry_index ASC"#,
+            execution_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Some(
+            rows.into_iter()
+                .map(|row| (row.entry_index as usize, row.entry.0))
+                .collect(),
+        ))
+    }
+
+    /// One page of the stored entries of a process. Callers check the entries
+    /// are current with `is_current` first.
+    pub async fn find_page(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        query: &NormalizedEntriesQuery,
+    ) -> Result<NormalizedEntriesPage, sqlx::Error> {
+        let before = query.before.map(|index| index as i64);
+        let after = query.after.map(|index| index as i64);
+        let forward = query.after.is_some();
+        // Filters are passed as JSON arrays and expanded with json_each
+        let tags = |tags: &[String]| {
+            (!tags.is_empty()).then(|| serde_json::to_string(tags).unwrap_or_default())
+        };
+        let entry_types = tags(&query.entry_types);
+        let action_types = tags(&query.action_types);
+        // One more than asked for tells whether there is another page
+        let limit = query.limit as i64 + 1;
+
+        let rows = sqlx::query!(
+            r#"SELECT entry_index, entry AS "entry!: Json<NormalizedEntry>"
+               FROM execution_process_normalized_entries
+               WHERE execution_id = $1
+                 AND ($2 IS NULL OR entry_index < $2)
+                 AND ($3 IS NULL OR entry_index > $3)
+                 AND ($4 IS NULL OR json_extract(entry, '$.entry_type.type')
+                     IN (SELECT value FROM json_each($4)))
+                 AND ($5 IS NULL OR json_extract(entry, '$.entry_type.action_type.action')
+                     IN (SELECT value FROM json_each($5)))
+               ORDER BY CASE WHEN $6 THEN entry_index ELSE -entry_index END
+               LIMIT $7"#,
+            execution_id,
+            before,
+            after,
+            entry_types,
+            action_types,
+            forward,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let has_more = rows.len() > query.limit;
+        let mut entries: Vec<IndexedNormalizedEntry> = rows
+            .into_iter()
+            .take(query.limit)
+            .map(|row| IndexedNormalizedEntry {
+                entry_index: row.entry_index as usize,
+                entry: row.entry.0,
+            })
+            .collect();
+        if !forward {
+            entries.reverse();
+        }
+        Ok(NormalizedEntriesPage { entries, has_more })
+    }
+
+    /// Replace the stored entries of a process with the output of
+    /// `normalizer_version`. Entries keep the indices they had in the
+    /// conversation, as served while the process ran, so they can have holes
+    /// where raw output was. `complete` tells whether the normalization ran to
+    /// the end of the logs.
+    pub async fn replace(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        normalizer_version: i64,
+        entries: &[(usize, NormalizedEntry)],
+        complete: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_normalized_entries WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (index, entry) in entries {
+            let index = *index as i64;
+            let entry = Json(entry);
+            sqlx::query!(
+                r#"INSERT INTO execution_process_normalized_entries (execution_id, entry_index, entry)
+                   VALUES ($1, $2, $3)"#,
+                execution_id,
+                index,
+                entry
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET normalizer_version = $1, normalized_entries_complete = $2
+               WHERE id = $3"#,
+            normalizer_version,
+            complete,
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Record `normalizer_version` on a process while keeping its stored
+    /// entries, for processes whose raw logs are gone
+    pub async fn set_normalizer_version(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        normalizer_version: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET normalizer_version = $1 WHERE id = $2",
+            normalizer_version,
+            execution_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Finished coding agent processes whose entries are missing, were
+    /// produced by another normalizer version, or are not known to be complete
+    /// while their raw logs are still around, oldest first
+    pub async fn find_stale_execution_ids(
+        pool: &SqlitePool,
+        normalizer_version: i64,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id AS "id!: Uuid"
+               FROM execution_processes
+               WHERE run_reason = 'codingagent'
+                 AND status != 'running'
+                 AND (normalizer_version IS NULL
+                      OR normalizer_version != $1
+                      OR (normalized_entries_complete = 0
+                          AND (EXISTS (SELECT 1 FROM execution_process_logs l
+                                       WHERE l.execution_id = execution_processes.id)
+                               OR EXISTS (SELECT 1 FROM execution_process_log_chunks c
+                                          WHERE c.execution_id = execution_processes.id))))
+               ORDER BY created_at ASC
+               LIMIT $2"#,
+            normalizer_version,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{ActionType, NormalizedEntryType, ToolStatus};
+
+    use super::*;
+    use crate::models::{
+        execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus},
+        execution_process_logs::ExecutionProcessLogs,
+        test_utils::{process, session},
+    };
+
+    fn entry(entry_type: NormalizedEntryType) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    fn command() -> NormalizedEntry {
+        entry(NormalizedEntryType::ToolUse {
+            tool_name: "bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: "ls".to_string(),
+                result: None,
+            },
+            status: ToolStatus::Success,
+        })
+    }
+
+    fn indices(page: &NormalizedEntriesPage) -> Vec<usize> {
+        page.entries.iter().map(|e| e.entry_index).collect()
+    }
+
+    #[test]
+    fn paginates_from_the_end_and_filters_by_tags() {
+        let entries = vec![
+            (0, entry(NormalizedEntryType::UserMessage)),
+            (1, command()),
+            (2, entry(NormalizedEntryType::AssistantMessage)),
+            (3, command()),
+            (5, command()),
+        ];
+
+        let tail = NormalizedEntriesQuery {
+            limit: 2,
+            ..Default::default()
+        };
+        let page = tail.paginate(entries.clone());
+        assert_eq!(indices(&page), vec![3, 5]);
+        assert!(page.has_more);
+
+        let older = NormalizedEntriesQuery {
+            before: Some(3),
+            ..tail.clone()
+        };
+        assert_eq!(indices(&older.paginate(entries.clone())), vec![1, 2]);
+
+        let commands = NormalizedEntriesQuery {
+            after: Some(1),
+            limit: 10,
+            entry_types: vec!["tool_use".to_string()],
+            action_types: vec!["command_run".to_string()],
+            ..Default::default()
+        };
+        let page = commands.paginate(entries);
+        assert_eq!(indices(&page), vec![3, 5]);
+        assert!(!page.has_more);
+    }
+
+    #[sqlx::test]
+    async fn replace_stores_entries_at_their_conversation_indices(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let id = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        assert!(
+            ExecutionProcessNormalizedEntries::find_by_execution_id(&pool, id, 1)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Raw output at index 2 is not stored
+        let entries = vec![
+            (0, entry(NormalizedEntryType::UserMessage)),
+            (1, command()),
+            (3, entry(NormalizedEntryType::AssistantMessage)),
+        ];
+        ExecutionProcessNormalizedEntries::replace(&pool, id, 1, &entries, true)
+            .await
+            .unwrap();
+        let stored = ExecutionProcessNormalizedEntries::find_by_execution_id(&pool, id, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+        assert!(
+            ExecutionProcessNormalizedEntries::find_by_execution_id(&pool, id, 2)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // A later normalization replaces all entries and the version
+        ExecutionProcessNormalizedEntries::replace(&pool, id, 2, &entries[1..], true)
+            .await
+            .unwrap();
+        assert!(
+            !ExecutionProcessNormalizedEntries::is_current(&pool, id, 1)
+                .await
+                .unwrap()
+        );
+        let query = NormalizedEntriesQuery {
+            limit: 10,
+            ..Default::default()
+        };
+        let page = ExecutionProcessNormalizedEntries::find_page(&pool, id, &query)
+            .await
+            .unwrap();
+        assert_eq!(indices(&page), vec![1, 3]);
+        assert!(matches!(
+            page.entries[1].entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+    }
+
+    #[sqlx::test]
+    async fn finds_finished_coding_agent_processes_with_stale_entries(pool: SqlitePool) {
+        let session_id = session(&pool).await;
+        let outdated = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, outdated, 1, &[], true)
+            .await
+            .unwrap();
+        let current = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, current, 2, &[], true)
+            .await
+            .unwrap();
+        let never_normalized = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Failed,
+        )
+        .await;
+        process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+        process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::SetupScript,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        // Incomplete entries only count while there are logs to redo them from
+        let incomplete = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, incomplete, 2, &[], false)
+            .await
+            .unwrap();
+        ExecutionProcessLogs::append_log_line(&pool, incomplete, "{}\n")
+            .await
+            .unwrap();
+        let incomplete_without_logs = process(
+            &pool,
+            session_id,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        ExecutionProcessNormalizedEntries::replace(&pool, incomplete_without_logs, 2, &[], false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ExecutionProcessNormalizedEntries::find_stale_execution_ids(&pool, 2, 10)
+                .await
+                .unwrap(),
+            vec![outdated, never_normalized, incomplete]
+        );
+        assert_eq!(
+            ExecutionProcessNormalizedEntries::find_stale_execution_ids(&pool, 2, 1)
+                .await
+                .unwrap(),
+            vec![outdated]
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -30,9 +30,24 @@ pub struct DirectMerge {
     pub repo_id: Uuid,
     pub merge_commit: String,
     pub target_branch_name: String,
+    pub merge_strategy: MergeStrategy,
     pub created_at: DateTime<Utc>,
 }
 
+/// How a task branch lands on its base branch in a direct merge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// A single commit with all changes of the task branch
+    #[default]
+    Squash,
+    /// The task branch's commits, with the base branch fast-forwarded to them
+    Rebase,
+    /// A merge commit joining the task branch into the base branch
+    MergeCommit,
+}
+
 /// PR merge - represents a pull request merge
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct PrMerge {
@@ -51,6 +66,26 @@ pub struct PullRequestInfo {
     pub status: MergeStatus,
     pub merged_at: Option<chrono::DateTime<chrono::Utc>>,
     pub merge_commit_sha: Option<String>,
+    /// Combined status of the CI checks on the PR head, if any are configured
+    pub ci_status: Option<CiStatus>,
+    /// Checks that failed on the PR head
+    pub failing_checks: Vec<PrCheck>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    Pending,
+    Success,
+    Failure,
+}
+
+/// A CI check (check run, status context or pipeline job) reported by the git host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct PrCheck {
+    pub name: String,
+    pub url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -73,6 +108,9 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_ci_status: Option<CiStatus>,
+    pr_failing_checks: Json<Vec<PrCheck>>,
+    merge_strategy: Option<MergeStrategy>,
     created_at: DateTime<Utc>,
 }
 
@@ -91,6 +129,7 @@ impl Merge {
         repo_id: Uuid,
         target_branch_name: &str,
         merge_commit: &str,
+        merge_strategy: MergeStrategy,
     ) -> Result<DirectMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -98,8 +137,9 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, workspace_id, repo_id, merge_type, merge_commit, created_at, target_branch_name
-            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6)
+                id, workspace_id, repo_id, merge_type, merge_commit, created_at, target_branch_name,
+                merge_strategy
+            ) VALUES ($1, $2, $3, 'direct', $4, $5, $6, $7)
             RETURNING
                 id as "id!: Uuid",
                 workspace_id as "workspace_id!: Uuid",
@@ -111,6 +151,9 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_failing_checks as "pr_failing_checks!: Json<Vec<PrCheck>>",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -119,7 +162,8 @@ impl Merge {
             repo_id,
             merge_commit,
             now,
-            target_branch_name
+            target_branch_name,
+            merge_strategy
         )
         .fetch_one(pool)
         .await
@@ -153,6 +197,9 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_failing_checks as "pr_failing_checks!: Json<Vec<PrCheck>>",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -184,6 +231,9 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_failing_checks as "pr_failing_checks!: Json<Vec<PrCheck>>",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -225,6 +275,47 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Update the CI status of a PR, returning whether anything changed
+    pub async fn update_ci_status(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        ci_status: Option<CiStatus>,
+        failing_checks: &[PrCheck],
+    ) -> Result<bool, sqlx::Error> {
+        let failing_checks = Json(failing_checks);
+        let result = sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_status = $1,
+                pr_failing_checks = $2
+            WHERE id = $3
+              AND (pr_ci_status IS NOT $1 OR pr_failing_checks IS NOT $2)"#,
+            ci_status,
+            failing_checks,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the new base branch of a PR that was retargeted
+    pub async fn update_target_branch(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET target_branch_name = $1 WHERE id = $2",
+            target_branch_name,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -244,6 +335,9 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_failing_checks as "pr_failing_checks!: Json<Vec<PrCheck>>",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -277,6 +371,9 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_failing_checks as "pr_failing_checks!: Json<Vec<PrCheck>>",
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -342,6 +439,7 @@ impl From<MergeRow> for DirectMerge {
                 .merge_commit
                 .expect("direct merge must have merge_commit"),
             target_branch_name: row.target_branch_name,
+            merge_strategy: row.merge_strategy.unwrap_or_default(),
             created_at: row.created_at,
         }
     }
@@ -360,6 +458,8 @@ impl From<MergeRow> for PrMerge {
                 status: row.pr_status.expect("pr merge must have status"),
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
+                ci_status: row.pr_ci_status,
+                failing_checks: row.pr_failing_checks.0,
             },
             created_at: row.created_at,
         }
@@ -1,15 +1,26 @@
+pub mod approval;
+pub mod approval_policy;
 pub mod coding_agent_turn;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_normalized_entries;
 pub mod execution_process_repo_state;
 pub mod image;
 pub mod merge;
+pub mod pr_follow_up_item;
 pub mod project;
 pub mod project_repo;
+pub mod queued_execution;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod session;
+pub mod storage;
 pub mod tag;
 pub mod task;
+pub mod task_dependency;
+pub mod task_schedule;
+#[cfg(test)]
+mod test_utils;
 pub mod workspace;
 pub mod workspace_repo;
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Review comments and failed CI checks of a PR that were already sent to
+/// the agent, identified by a provider specific key
+pub struct PrFollowUpItem;
+
+impl PrFollowUpItem {
+    pub async fn find_keys_for_merge(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<HashSet<String>, sqlx::Error> {
+        let keys = sqlx::query_scalar!(
+            r#"SELECT item_key AS "item_key!" FROM pr_follow_up_items WHERE merge_id = $1"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Mark items as sent; keys recorded before are ignored
+    pub async fn record(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        keys: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for key in keys {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO pr_follow_up_items (id, merge_id, item_key)
+                   VALUES ($1, $2, $3)"#,
+                id,
+                merge_id,
+                key
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
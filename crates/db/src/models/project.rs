@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
@@ -23,6 +24,11 @@ pub struct Project {
     pub name: String,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    /// Coding agents of this project allowed to run at once, unlimited when unset
+    #[ts(type = "number | null")]
+    pub max_concurrent_coding_agents: Option<i64>,
+    /// Send new PR review comments and CI failures back to the agent
+    pub auto_follow_up_pr: bool,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -38,6 +44,15 @@ pub struct CreateProject {
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub max_concurrent_coding_agents: Option<Option<i64>>,
+    #[ts(optional)]
+    pub auto_follow_up_pr: Option<bool>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -71,6 +86,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      max_concurrent_coding_agents,
+                      auto_follow_up_pr as "auto_follow_up_pr!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -88,6 +105,8 @@ impl Project {
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
                    p.remote_project_id as "remote_project_id: Uuid",
+                   p.max_concurrent_coding_agents,
+                   p.auto_follow_up_pr as "auto_follow_up_pr!: bool",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -111,6 +130,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      max_concurrent_coding_agents,
+                      auto_follow_up_pr as "auto_follow_up_pr!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -128,6 +149,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      max_concurrent_coding_agents,
+                      auto_follow_up_pr as "auto_follow_up_pr!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -148,6 +171,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      max_concurrent_coding_agents,
+                      auto_follow_up_pr as "auto_follow_up_pr!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -176,6 +201,8 @@ impl Project {
                           name,
                           default_agent_working_dir,
                           remote_project_id as "remote_project_id: Uuid",
+                          max_concurrent_coding_agents,
+                          auto_follow_up_pr as "auto_follow_up_pr!: bool",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -195,25 +222,59 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        let max_concurrent_coding_agents = match payload.max_concurrent_coding_agents {
+            None => existing.max_concurrent_coding_agents,
+            Some(v) => v,
+        };
+        let auto_follow_up_pr = payload
+            .auto_follow_up_pr
+            .unwrap_or(existing.auto_follow_up_pr);
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2,
+                   max_concurrent_coding_agents = $3,
+                   auto_follow_up_pr = $4,
+                   auto_follow_up_pr_since = CASE
+                       WHEN $4 AND NOT auto_follow_up_pr THEN datetime('now', 'subsec')
+                       ELSE auto_follow_up_pr_since
+                   END
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
                          remote_project_id as "remote_project_id: Uuid",
+                         max_concurrent_coding_agents,
+                         auto_follow_up_pr as "auto_follow_up_pr!: bool",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            max_concurrent_coding_agents,
+            auto_follow_up_pr,
         )
         .fetch_one(pool)
         .await
     }
 
+    /// When the project last opted in to PR follow-ups, `None` if it opted in
+    /// before this was recorded or never did
+    pub async fn find_auto_follow_up_pr_since(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        let since = sqlx::query_scalar!(
+            r#"SELECT auto_follow_up_pr_since as "auto_follow_up_pr_since: DateTime<Utc>"
+               FROM projects
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(since.flatten())
+    }
+
     pub async fn set_remote_project_id(
         pool: &SqlitePool,
         id: Uuid,
@@ -7,7 +7,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{merge::MergeStrategy, repo::Repo};
 
 #[derive(Debug, Error)]
 pub enum ProjectRepoError {
@@ -83,6 +83,9 @@ impl ProjectRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.verification_script,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.merge_commit_template,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
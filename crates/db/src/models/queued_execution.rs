@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use executors::actions::ExecutorAction;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessRunReason;
+
+/// An execution waiting for a free coding agent slot
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct QueuedExecution {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    #[ts(type = "ExecutorAction")]
+    pub executor_action: Json<ExecutorAction>,
+    pub run_reason: ExecutionProcessRunReason,
+    pub created_at: DateTime<Utc>,
+}
+
+impl QueuedExecution {
+    /// All queued executions, oldest first
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedExecution,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                session_id as "session_id!: Uuid",
+                executor_action as "executor_action!: Json<ExecutorAction>",
+                run_reason as "run_reason!: ExecutionProcessRunReason",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM queued_executions
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedExecution,
+            r#"SELECT
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                session_id as "session_id!: Uuid",
+                executor_action as "executor_action!: Json<ExecutorAction>",
+                run_reason as "run_reason!: ExecutionProcessRunReason",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM queued_executions
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        session_id: Uuid,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let executor_action = Json(executor_action);
+        sqlx::query_as!(
+            QueuedExecution,
+            r#"INSERT INTO queued_executions (id, workspace_id, session_id, executor_action, run_reason)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING
+                id as "id!: Uuid",
+                workspace_id as "workspace_id!: Uuid",
+                session_id as "session_id!: Uuid",
+                executor_action as "executor_action!: Json<ExecutorAction>",
+                run_reason as "run_reason!: ExecutionProcessRunReason",
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            session_id,
+            executor_action,
+            run_reason
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM queued_executions WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_executions WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
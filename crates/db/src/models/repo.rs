@@ -8,6 +8,8 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::merge::MergeStrategy;
+
 #[derive(Debug, Error)]
 pub enum RepoError {
     #[error(transparent)]
@@ -28,6 +30,13 @@ pub struct Repo {
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
     pub default_target_branch: Option<String>,
+    /// Run after every coding agent turn; failures are fed back to the agent
+    pub verification_script: Option<String>,
+    /// How direct merges land task branches on the base branch
+    pub merge_strategy: MergeStrategy,
+    /// Commit message of direct merges, with `{title}`, `{description}`,
+    /// `{task_id}`, `{short_id}` and `{branch}` placeholders
+    pub merge_commit_template: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -92,6 +101,30 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_target_branch: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub verification_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "MergeStrategy | null")]
+    pub merge_strategy: Option<Option<MergeStrategy>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub merge_commit_template: Option<Option<String>>,
 }
 
 impl Repo {
@@ -110,6 +143,9 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      verification_script,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      merge_commit_template,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -149,6 +185,9 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      verification_script,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      merge_commit_template,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -205,6 +244,9 @@ impl Repo {
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
+                         verification_script,
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         merge_commit_template,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -240,6 +282,9 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      verification_script,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      merge_commit_template,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -289,6 +334,18 @@ impl Repo {
             None => existing.default_target_branch,
             Some(v) => v.clone(),
         };
+        let verification_script = match &payload.verification_script {
+            None => existing.verification_script,
+            Some(v) => v.clone(),
+        };
+        let merge_strategy = match &payload.merge_strategy {
+            None => existing.merge_strategy,
+            Some(v) => v.unwrap_or_default(),
+        };
+        let merge_commit_template = match &payload.merge_commit_template {
+            None => existing.merge_commit_template,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -300,8 +357,11 @@ impl Repo {
                    parallel_setup_script = $5,
                    dev_server_script = $6,
                    default_target_branch = $7,
+                   verification_script = $8,
+                   merge_strategy = $9,
+                   merge_commit_template = $10,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $8
+               WHERE id = $11
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -312,6 +372,9 @@ impl Repo {
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
+                         verification_script,
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         merge_commit_template,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -321,6 +384,9 @@ impl Repo {
             parallel_setup_script,
             dev_server_script,
             default_target_branch,
+            verification_script,
+            merge_strategy,
+            merge_commit_template,
             id
         )
         .fetch_one(pool)
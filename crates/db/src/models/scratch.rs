@@ -61,6 +61,41 @@ pub struct DraftWorkspaceRepo {
     pub target_branch: String,
 }
 
+/// Data for the review comments left on a workspace diff
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewCommentsData {
+    pub comments: Vec<DiffReviewComment>,
+}
+
+/// A comment anchored to a line range of a file in a workspace diff
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DiffReviewComment {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// First commented line (1-based) in the workspace's version of the file
+    pub start_line: u32,
+    /// Last commented line, inclusive
+    pub end_line: u32,
+    pub text: String,
+    #[serde(default)]
+    pub status: ReviewCommentStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ReviewCommentStatus {
+    /// Not sent to the agent yet
+    #[default]
+    Pending,
+    /// Sent to the agent, which has not changed the lines yet
+    Sent,
+    /// The agent changed the commented lines after the comment was sent
+    Resolved,
+}
+
 /// The payload of a scratch, tagged by type. The type is part of the composite primary key.
 /// Data is stored as markdown string.
 #[derive(Debug, Clone, Serialize, Deserialize, TS, EnumDiscriminants)]
@@ -76,6 +111,7 @@ pub enum ScratchPayload {
     DraftWorkspace(DraftWorkspaceData),
     PreviewSettings(PreviewSettingsData),
     WorkspaceNotes(WorkspaceNotesData),
+    ReviewComments(ReviewCommentsData),
 }
 
 impl ScratchPayload {
@@ -272,6 +308,62 @@ impl Scratch {
         Scratch::try_from(row)
     }
 
+    /// Move the given review comments of a workspace from one status to
+    /// another. The payload is re-read and only swapped in if it did not change
+    /// meanwhile, so comments edited concurrently are never overwritten.
+    /// Returns how many comments changed status.
+    pub async fn transition_review_comments(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        comment_ids: &[Uuid],
+        from: ReviewCommentStatus,
+        to: ReviewCommentStatus,
+    ) -> Result<usize, ScratchError> {
+        let scratch_type_str = ScratchType::ReviewComments.to_string();
+        loop {
+            let Some(current) = sqlx::query_scalar!(
+                "SELECT payload FROM scratch WHERE id = $1 AND scratch_type = $2",
+                workspace_id,
+                scratch_type_str
+            )
+            .fetch_optional(pool)
+            .await?
+            else {
+                return Ok(0);
+            };
+            let ScratchPayload::ReviewComments(mut data) = serde_json::from_str(&current)? else {
+                return Ok(0);
+            };
+
+            let mut changed = 0;
+            for comment in &mut data.comments {
+                if comment.status == from && comment_ids.contains(&comment.id) {
+                    comment.status = to;
+                    changed += 1;
+                }
+            }
+            if changed == 0 {
+                return Ok(0);
+            }
+
+            let payload_str = serde_json::to_string(&ScratchPayload::ReviewComments(data))?;
+            let result = sqlx::query!(
+                r#"UPDATE scratch
+                   SET payload = $3, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1 AND scratch_type = $2 AND payload = $4"#,
+                workspace_id,
+                scratch_type_str,
+                payload_str,
+                current
+            )
+            .execute(pool)
+            .await?;
+            if result.rows_affected() > 0 {
+                return Ok(changed);
+            }
+        }
+    }
+
     pub async fn delete(
         pool: &SqlitePool,
         id: Uuid,
@@ -313,3 +405,85 @@ impl Scratch {
         Ok(scratch)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(status: ReviewCommentStatus) -> DiffReviewComment {
+        DiffReviewComment {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            file_path: "src/lib.rs".to_string(),
+            start_line: 1,
+            end_line: 2,
+            text: "Rename this".to_string(),
+            status,
+        }
+    }
+
+    async fn save(pool: &SqlitePool, workspace_id: Uuid, comments: &[DiffReviewComment]) {
+        Scratch::update(
+            pool,
+            workspace_id,
+            &ScratchType::ReviewComments,
+            &UpdateScratch {
+                payload: ScratchPayload::ReviewComments(ReviewCommentsData {
+                    comments: comments.to_vec(),
+                }),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn statuses(pool: &SqlitePool, workspace_id: Uuid) -> Vec<(Uuid, ReviewCommentStatus)> {
+        match Scratch::find_by_id(pool, workspace_id, &ScratchType::ReviewComments)
+            .await
+            .unwrap()
+            .map(|scratch| scratch.payload)
+        {
+            Some(ScratchPayload::ReviewComments(data)) => {
+                data.comments.iter().map(|c| (c.id, c.status)).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    #[sqlx::test]
+    async fn transition_keeps_comments_changed_since_the_ids_were_picked(pool: SqlitePool) {
+        let workspace_id = Uuid::new_v4();
+        let sent = comment(ReviewCommentStatus::Sent);
+        let pending = comment(ReviewCommentStatus::Pending);
+        save(&pool, workspace_id, &[sent.clone(), pending.clone()]).await;
+
+        // The user adds a comment after the caller picked the ids to resolve
+        let added = comment(ReviewCommentStatus::Pending);
+        save(
+            &pool,
+            workspace_id,
+            &[sent.clone(), pending.clone(), added.clone()],
+        )
+        .await;
+
+        let changed = Scratch::transition_review_comments(
+            &pool,
+            workspace_id,
+            &[sent.id, pending.id],
+            ReviewCommentStatus::Sent,
+            ReviewCommentStatus::Resolved,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(changed, 1);
+        assert_eq!(
+            statuses(&pool, workspace_id).await,
+            vec![
+                (sent.id, ReviewCommentStatus::Resolved),
+                (pending.id, ReviewCommentStatus::Pending),
+                (added.id, ReviewCommentStatus::Pending),
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where the text of a search hit comes from
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "search_source", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    /// Task title and description
+    Task,
+    /// Prompt of a coding agent turn
+    Prompt,
+    /// Final assistant message of a coding agent turn
+    Summary,
+    /// Assistant message in an execution log
+    AssistantMessage,
+    /// Command run by the coding agent
+    Command,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SearchHit {
+    pub source: SearchSource,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    /// `None` for task hits
+    pub workspace_id: Option<Uuid>,
+    /// `None` for task hits
+    pub execution_process_id: Option<Uuid>,
+    /// Matching text with matches wrapped in `<mark>` tags; the text itself is
+    /// not escaped
+    pub snippet: String,
+    /// Lower is better
+    pub rank: f64,
+}
+
+pub struct SearchDocument;
+
+impl SearchDocument {
+    /// Run an FTS5 match expression against the index, best hits first
+    pub async fn search(
+        pool: &SqlitePool,
+        match_expression: &str,
+        project_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>, sqlx::Error> {
+        sqlx::query_as!(
+            SearchHit,
+            r#"SELECT
+                d.source as "source!: SearchSource",
+                d.task_id as "task_id!: Uuid",
+                t.title as "task_title!",
+                t.project_id as "project_id!: Uuid",
+                s.workspace_id as "workspace_id?: Uuid",
+                d.execution_process_id as "execution_process_id?: Uuid",
+                snippet(search_fts, 0, '<mark>', '</mark>', '…', 16) as "snippet!: String",
+                bm25(search_fts) as "rank!: f64"
+               FROM search_fts
+               JOIN search_documents d ON d.id = search_fts.rowid
+               JOIN tasks t ON t.id = d.task_id
+               LEFT JOIN execution_processes ep ON ep.id = d.execution_process_id
+               LEFT JOIN sessions s ON s.id = ep.session_id
+               WHERE search_fts MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+               ORDER BY rank
+               LIMIT $3"#,
+            match_expression,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Index or re-index log entries of a running execution process, given as
+    /// `(entry_index, source, content)`
+    pub async fn upsert_log_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entries: &[(i64, SearchSource, &str)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (entry_index, source, content) in entries {
+            sqlx::query!(
+                r#"INSERT INTO search_documents (source, source_key, entry_index, task_id, execution_process_id, content)
+                   SELECT $1, ep.id, $3, w.task_id, ep.id, $4
+                   FROM execution_processes ep
+                   JOIN sessions s ON s.id = ep.session_id
+                   JOIN workspaces w ON w.id = s.workspace_id
+                   WHERE ep.id = $2
+                   ON CONFLICT (source, source_key, entry_index) DO UPDATE
+                       SET content = excluded.content
+                       WHERE content != excluded.content"#,
+                source,
+                execution_process_id,
+                entry_index,
+                content
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Replace the indexed log entries of an execution process with its
+    /// final normalized entries, given as `(entry_index, source, content)`
+    pub async fn replace_log_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entries: &[(i64, SearchSource, &str)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"DELETE FROM search_documents
+               WHERE source_key = $1 AND source IN ('assistant_message', 'command')"#,
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (entry_index, source, content) in entries {
+            sqlx::query!(
+                r#"INSERT INTO search_documents (source, source_key, entry_index, task_id, execution_process_id, content)
+                   SELECT $1, ep.id, $3, w.task_id, ep.id, $4
+                   FROM execution_processes ep
+                   JOIN sessions s ON s.id = ep.session_id
+                   JOIN workspaces w ON w.id = s.workspace_id
+                   WHERE ep.id = $2"#,
+                source,
+                execution_process_id,
+                entry_index,
+                content
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -130,6 +130,23 @@ impl Session {
         .await?)
     }
 
+    /// Make the session's first follow-up resume the given agent session, so
+    /// it continues a conversation that started in another workspace
+    pub async fn set_forked_agent_session_id(
+        pool: &SqlitePool,
+        id: Uuid,
+        agent_session_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE sessions SET forked_agent_session_id = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2"#,
+            agent_session_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_executor(
         pool: &SqlitePool,
         id: Uuid,
@@ -0,0 +1,131 @@
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Space a table takes in the database file, its indexes included
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TableSize {
+    pub name: String,
+    pub bytes: i64,
+}
+
+/// Stored log payload of a project's execution processes
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ProjectStorageSize {
+    pub project_id: Uuid,
+    pub project_name: String,
+    /// Raw logs, counting compressed chunks at their compressed size
+    pub log_bytes: i64,
+    pub normalized_entry_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseSize {
+    /// Size of the database file
+    pub total_bytes: i64,
+    /// Unused pages in the file, reused before the file grows
+    pub free_bytes: i64,
+    /// Largest first. Empty when SQLite was built without the dbstat table,
+    /// which leaves only the totals.
+    pub tables: Vec<TableSize>,
+    /// Largest first
+    pub projects: Vec<ProjectStorageSize>,
+}
+
+impl DatabaseSize {
+    pub async fn measure(pool: &SqlitePool) -> Result<Self, sqlx::Error> {
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+            .fetch_one(pool)
+            .await?;
+        let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+            .fetch_one(pool)
+            .await?;
+        let freelist_count: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(pool)
+            .await?;
+
+        // dbstat lists every table and index by its own name
+        let tables = match sqlx::query_as::<_, TableSize>(
+            r#"SELECT m.tbl_name AS name, SUM(s.pgsize) AS bytes
+               FROM dbstat s
+               JOIN sqlite_master m ON m.name = s.name
+               GROUP BY m.tbl_name
+               ORDER BY bytes DESC"#,
+        )
+        .fetch_all(pool)
+        .await
+        {
+            Ok(tables) => tables,
+            // Not every SQLite build has dbstat; the page counts above still
+            // give the totals
+            Err(sqlx::Error::Database(e)) => {
+                tracing::debug!("Table sizes unavailable: {}", e);
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        let projects = sqlx::query_as::<_, ProjectStorageSize>(
+            r#"WITH sized AS (
+                   SELECT execution_id, byte_size AS log_bytes, 0 AS entry_bytes
+                   FROM execution_process_logs
+                   UNION ALL
+                   SELECT execution_id, LENGTH(data), 0
+                   FROM execution_process_log_chunks
+                   UNION ALL
+                   SELECT execution_id, 0, LENGTH(CAST(entry AS BLOB))
+                   FROM execution_process_normalized_entries
+               )
+               SELECT p.id AS project_id,
+                      p.name AS project_name,
+                      SUM(sized.log_bytes) AS log_bytes,
+                      SUM(sized.entry_bytes) AS normalized_entry_bytes
+               FROM sized
+               JOIN execution_processes ep ON ep.id = sized.execution_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               GROUP BY p.id, p.name
+               ORDER BY SUM(sized.log_bytes) + SUM(sized.entry_bytes) DESC"#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            total_bytes: page_size * page_count,
+            free_bytes: page_size * freelist_count,
+            tables,
+            projects,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn measure_falls_back_to_page_totals_without_dbstat(pool: SqlitePool) {
+        // A regular table of the same name hides the dbstat virtual table
+        sqlx::query("CREATE TABLE dbstat (id INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let size = DatabaseSize::measure(&pool).await.unwrap();
+
+        assert_eq!(size.total_bytes, page_size * page_count);
+        assert!(size.tables.is_empty());
+        assert!(size.projects.is_empty());
+    }
+}
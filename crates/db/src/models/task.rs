@@ -5,7 +5,7 @@ use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{project::Project, workspace::Workspace, workspace_repo::WorkspaceRepo};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -40,6 +40,8 @@ pub struct TaskWithAttemptStatus {
     #[ts(flatten)]
     pub task: Task,
     pub has_in_progress_attempt: bool,
+    /// An attempt is waiting for a free coding agent slot
+    pub has_queued_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
 }
@@ -62,6 +64,18 @@ pub struct TaskRelationships {
     pub parent_task: Option<Task>, // The task that owns the parent workspace
     pub current_workspace: Workspace, // The workspace we're viewing
     pub children: Vec<Task>,       // Tasks created from this workspace
+    /// Workspaces stacked with the current one, bottom first; empty when it isn't stacked
+    pub stack: Vec<StackedWorkspace>,
+}
+
+/// A workspace whose branch is based on the branch of the workspace below it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct StackedWorkspace {
+    pub task: Task,
+    pub workspace_id: Uuid,
+    pub branch: String,
+    /// 0 for the bottom of the stack, which is based on a regular branch
+    pub depth: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -135,17 +149,25 @@ impl Task {
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM workspaces w
+      JOIN queued_executions qe ON qe.workspace_id = w.id
+     WHERE w.task_id       = t.id
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_queued_attempt!: i64",
+
   CASE WHEN (
     SELECT ep.status
       FROM workspaces w
       JOIN sessions s ON s.workspace_id = w.id
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
      ORDER BY ep.created_at DESC
      LIMIT 1
   ) IN ('failed','killed') THEN 1 ELSE 0 END
@@ -181,6 +203,7 @@ ORDER BY t.created_at DESC"#,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: rec.has_queued_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -364,10 +387,85 @@ ORDER BY t.created_at DESC"#,
         // 3. Get children tasks (created from this workspace)
         let children = Self::find_children_by_workspace_id(pool, workspace.id).await?;
 
+        // 4. Get the stack of branches the workspace is part of
+        let stack = Self::find_stack_for_workspace(pool, workspace, &current_task).await?;
+
         Ok(TaskRelationships {
             parent_task,
             current_workspace: workspace.clone(),
             children,
+            stack,
         })
     }
+
+    /// Stacked ancestors of the workspace, the workspace itself and its
+    /// stacked descendants in depth-first order
+    async fn find_stack_for_workspace(
+        pool: &SqlitePool,
+        workspace: &Workspace,
+        current_task: &Task,
+    ) -> Result<Vec<StackedWorkspace>, sqlx::Error> {
+        // Walk down while the workspace targets the branch of its parent task's workspace
+        let mut below: Vec<(Task, Workspace)> = Vec::new();
+        let mut upper = workspace.clone();
+        let mut upper_task = current_task.clone();
+        while let Some(parent_workspace_id) = upper_task.parent_workspace_id {
+            let Some(parent_workspace) = Workspace::find_by_id(pool, parent_workspace_id).await?
+            else {
+                break;
+            };
+            let is_stacked = WorkspaceRepo::find_by_workspace_id(pool, upper.id)
+                .await?
+                .iter()
+                .any(|wr| wr.target_branch == parent_workspace.branch);
+            // Guard against cycles in hand-edited relationships
+            let seen = parent_workspace.id == workspace.id
+                || below.iter().any(|(_, w)| w.id == parent_workspace.id);
+            if !is_stacked || seen {
+                break;
+            }
+            let Some(parent_task) = Self::find_by_id(pool, parent_workspace.task_id).await? else {
+                break;
+            };
+            below.push((parent_task.clone(), parent_workspace.clone()));
+            upper = parent_workspace;
+            upper_task = parent_task;
+        }
+
+        let mut stack: Vec<StackedWorkspace> = below
+            .into_iter()
+            .rev()
+            .enumerate()
+            .map(|(depth, (task, workspace))| StackedWorkspace {
+                task,
+                workspace_id: workspace.id,
+                branch: workspace.branch,
+                depth: depth as u32,
+            })
+            .collect();
+
+        let mut pending = vec![(current_task.clone(), workspace.clone(), stack.len() as u32)];
+        while let Some((task, workspace, depth)) = pending.pop() {
+            if stack.iter().any(|entry| entry.workspace_id == workspace.id) {
+                continue;
+            }
+            let children = Workspace::find_stacked_children(pool, &workspace, None).await?;
+            for child in children.into_iter().rev() {
+                if let Some(child_task) = Self::find_by_id(pool, child.task_id).await? {
+                    pending.push((child_task, child, depth + 1));
+                }
+            }
+            stack.push(StackedWorkspace {
+                task,
+                workspace_id: workspace.id,
+                branch: workspace.branch,
+                depth,
+            });
+        }
+
+        if stack.len() < 2 {
+            stack.clear();
+        }
+        Ok(stack)
+    }
 }
@@ -0,0 +1,357 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    task::{Task, TaskStatus},
+    workspace_repo::CreateWorkspaceRepo,
+};
+
+/// `task_id` cannot start until `blocked_by_task_id` is done
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskDependency {
+    /// Tasks that block `task_id`
+    pub async fn find_blockers(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.blocked_by_task_id
+               WHERE d.task_id = $1
+               ORDER BY d.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks blocked by `task_id`
+    pub async fn find_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.task_id
+               WHERE d.blocked_by_task_id = $1
+               ORDER BY d.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Number of blockers of `task_id` that are not done yet
+    pub async fn count_unfinished_blockers(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.blocked_by_task_id
+               WHERE d.task_id = $1 AND t.status != 'done'"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Whether making `task_id` wait for `blocked_by_task_id` would close a
+    /// cycle, i.e. `blocked_by_task_id` already waits for `task_id`, directly
+    /// or transitively.
+    pub async fn would_create_cycle(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        blocked_by_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        if task_id == blocked_by_task_id {
+            return Ok(true);
+        }
+        let found = sqlx::query_scalar!(
+            r#"WITH RECURSIVE blockers(id) AS (
+                   SELECT blocked_by_task_id FROM task_dependencies WHERE task_id = $1
+                   UNION
+                   SELECT d.blocked_by_task_id
+                   FROM task_dependencies d
+                   JOIN blockers b ON d.task_id = b.id
+               )
+               SELECT EXISTS(SELECT 1 FROM blockers WHERE id = $2) as "found!: bool""#,
+            blocked_by_task_id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(found)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        blocked_by_task_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_dependencies (task_id, blocked_by_task_id)
+               VALUES ($1, $2)
+               ON CONFLICT(task_id, blocked_by_task_id) DO NOTHING"#,
+            task_id,
+            blocked_by_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        blocked_by_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND blocked_by_task_id = $2",
+            task_id,
+            blocked_by_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Attempt to start automatically once every blocker of the task is done
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskAutoStart {
+    pub task_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: Json<Vec<CreateWorkspaceRepo>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskAutoStart {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"SELECT
+                task_id as "task_id!: Uuid",
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                created_at as "created_at!: DateTime<Utc>"
+               FROM task_auto_starts
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Auto start of `task_id` if the task is still to do and has no
+    /// unfinished blockers left
+    pub async fn find_ready(pool: &SqlitePool, task_id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"SELECT
+                a.task_id as "task_id!: Uuid",
+                a.executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                a.repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                a.created_at as "created_at!: DateTime<Utc>"
+               FROM task_auto_starts a
+               JOIN tasks t ON t.id = a.task_id
+               WHERE a.task_id = $1
+                 AND t.status = 'todo'
+                 AND NOT EXISTS (
+                     SELECT 1
+                     FROM task_dependencies d
+                     JOIN tasks b ON b.id = d.blocked_by_task_id
+                     WHERE d.task_id = a.task_id AND b.status != 'done'
+                 )"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Auto starts of tasks blocked by `blocked_by_task_id` that are still to
+    /// do and have no unfinished blockers left
+    pub async fn find_ready_after(
+        pool: &SqlitePool,
+        blocked_by_task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"SELECT
+                a.task_id as "task_id!: Uuid",
+                a.executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                a.repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                a.created_at as "created_at!: DateTime<Utc>"
+               FROM task_auto_starts a
+               JOIN task_dependencies d ON d.task_id = a.task_id
+               JOIN tasks t ON t.id = a.task_id
+               WHERE d.blocked_by_task_id = $1
+                 AND t.status = 'todo'
+                 AND NOT EXISTS (
+                     SELECT 1
+                     FROM task_dependencies d2
+                     JOIN tasks b ON b.id = d2.blocked_by_task_id
+                     WHERE d2.task_id = a.task_id AND b.status != 'done'
+                 )
+               ORDER BY a.created_at ASC"#,
+            blocked_by_task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(executor_profile_id);
+        let repos = Json(repos);
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"INSERT INTO task_auto_starts (task_id, executor_profile_id, repos)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   executor_profile_id = excluded.executor_profile_id,
+                   repos = excluded.repos
+               RETURNING
+                task_id as "task_id!: Uuid",
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            task_id,
+            executor_profile_id,
+            repos
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_auto_starts WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::models::test_utils::{project, task};
+
+    #[sqlx::test]
+    async fn detects_direct_and_transitive_cycles(pool: SqlitePool) {
+        let project_id = project(&pool).await;
+        let schema = task(&pool, project_id, "Schema").await;
+        let api = task(&pool, project_id, "API").await;
+        let ui = task(&pool, project_id, "UI").await;
+        let docs = task(&pool, project_id, "Docs").await;
+        TaskDependency::create(&pool, api, schema).await.unwrap();
+        TaskDependency::create(&pool, ui, api).await.unwrap();
+
+        for (task_id, blocked_by_task_id, cycle) in [
+            (schema, schema, true),
+            (schema, api, true),
+            (schema, ui, true),
+            (ui, schema, false),
+            (docs, ui, false),
+        ] {
+            assert_eq!(
+                TaskDependency::would_create_cycle(&pool, task_id, blocked_by_task_id)
+                    .await
+                    .unwrap(),
+                cycle,
+                "{task_id} blocked by {blocked_by_task_id}"
+            );
+        }
+    }
+
+    #[sqlx::test]
+    async fn auto_start_is_ready_once_every_blocker_is_done(pool: SqlitePool) {
+        let project_id = project(&pool).await;
+        let schema = task(&pool, project_id, "Schema").await;
+        let api = task(&pool, project_id, "API").await;
+        let ui = task(&pool, project_id, "UI").await;
+        TaskDependency::create(&pool, ui, schema).await.unwrap();
+        TaskDependency::create(&pool, ui, api).await.unwrap();
+        let profile = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        TaskAutoStart::upsert(&pool, ui, &profile, &[])
+            .await
+            .unwrap();
+
+        Task::update_status(&pool, schema, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert_eq!(
+            TaskDependency::count_unfinished_blockers(&pool, ui)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(
+            TaskAutoStart::find_ready(&pool, ui)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            TaskAutoStart::find_ready_after(&pool, schema)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        Task::update_status(&pool, api, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert_eq!(
+            TaskDependency::count_unfinished_blockers(&pool, ui)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(
+            TaskAutoStart::find_ready(&pool, ui)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        let ready = TaskAutoStart::find_ready_after(&pool, api).await.unwrap();
+        assert_eq!(
+            ready.iter().map(|a| a.task_id).collect::<Vec<_>>(),
+            vec![ui]
+        );
+
+        // Started by hand in the meantime
+        Task::update_status(&pool, ui, TaskStatus::InProgress)
+            .await
+            .unwrap();
+        assert!(
+            TaskAutoStart::find_ready(&pool, ui)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::workspace_repo::CreateWorkspaceRepo;
+
+/// Cron schedule that starts a fresh attempt of a task whenever it fires
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// Five field cron expression evaluated in the server's local time
+    pub cron: String,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: Json<Vec<CreateWorkspaceRepo>>,
+    /// Treat the task as a template and run each firing as a new task
+    pub create_new_task: bool,
+    pub enabled: bool,
+    /// `None` while disabled
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Attempt started by the last run
+    pub last_workspace_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub task_id: Uuid,
+    pub cron: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+    #[serde(default)]
+    pub create_new_task: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub cron: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+    pub create_new_task: bool,
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl TaskSchedule {
+    pub async fn find_all(
+        pool: &SqlitePool,
+        task_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                cron,
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                create_new_task as "create_new_task!: bool",
+                enabled as "enabled!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                last_workspace_id as "last_workspace_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE ($1 IS NULL OR task_id = $1)
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                cron,
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                create_new_task as "create_new_task!: bool",
+                enabled as "enabled!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                last_workspace_id as "last_workspace_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is at or before `now`, most overdue first
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                cron,
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                create_new_task as "create_new_task!: bool",
+                enabled as "enabled!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                last_workspace_id as "last_workspace_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= $1
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (
+                id, task_id, cron, executor_profile_id, repos, create_new_task, enabled, next_run_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                cron,
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                create_new_task as "create_new_task!: bool",
+                enabled as "enabled!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                last_workspace_id as "last_workspace_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.task_id,
+            data.cron,
+            executor_profile_id,
+            repos,
+            data.create_new_task,
+            data.enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET cron = $2, executor_profile_id = $3, repos = $4, create_new_task = $5,
+                   enabled = $6, next_run_at = $7, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING
+                id as "id!: Uuid",
+                task_id as "task_id!: Uuid",
+                cron,
+                executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                repos as "repos!: Json<Vec<CreateWorkspaceRepo>>",
+                create_new_task as "create_new_task!: bool",
+                enabled as "enabled!: bool",
+                next_run_at as "next_run_at?: DateTime<Utc>",
+                last_run_at as "last_run_at?: DateTime<Utc>",
+                last_workspace_id as "last_workspace_id?: Uuid",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.cron,
+            executor_profile_id,
+            repos,
+            data.create_new_task,
+            data.enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Move to the next run without recording a run, e.g. when one was skipped
+    pub async fn set_next_run_at(
+        pool: &SqlitePool,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_schedules SET next_run_at = $2 WHERE id = $1",
+            id,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        run_at: DateTime<Utc>,
+        workspace_id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2, last_workspace_id = $3, next_run_at = $4
+               WHERE id = $1"#,
+            id,
+            run_at,
+            workspace_id,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
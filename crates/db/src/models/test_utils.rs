@@ -0,0 +1,92 @@
+//! Rows the model tests build on
+
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::{
+    execution_process::{
+        CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    project::{CreateProject, Project},
+    session::{CreateSession, Session},
+    task::{CreateTask, Task},
+    workspace::{CreateWorkspace, Workspace},
+};
+
+/// A project without repos
+pub async fn project(pool: &SqlitePool) -> Uuid {
+    let project_id = Uuid::new_v4();
+    let project = CreateProject {
+        name: "app".to_string(),
+        repositories: Vec::new(),
+    };
+    Project::create(pool, &project, project_id).await.unwrap();
+    project_id
+}
+
+/// A task of the project that is still to do
+pub async fn task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Uuid {
+    let task_id = Uuid::new_v4();
+    let task = CreateTask::from_title_description(project_id, title.to_string(), None);
+    Task::create(pool, &task, task_id).await.unwrap();
+    task_id
+}
+
+/// A session to run processes in
+pub async fn session(pool: &SqlitePool) -> Uuid {
+    let project_id = project(pool).await;
+    let task_id = task(pool, project_id, "Fix").await;
+    let workspace_id = Uuid::new_v4();
+    let workspace = CreateWorkspace {
+        branch: "fix".to_string(),
+        agent_working_dir: None,
+    };
+    Workspace::create(pool, &workspace, workspace_id, task_id)
+        .await
+        .unwrap();
+    let session_id = Uuid::new_v4();
+    Session::create(
+        pool,
+        &CreateSession { executor: None },
+        session_id,
+        workspace_id,
+    )
+    .await
+    .unwrap();
+    session_id
+}
+
+/// A process of the session that ended, or still runs, with `status`
+pub async fn process(
+    pool: &SqlitePool,
+    session_id: Uuid,
+    run_reason: ExecutionProcessRunReason,
+    status: ExecutionProcessStatus,
+) -> Uuid {
+    let action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: "true".to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::SetupScript,
+            working_dir: None,
+        }),
+        None,
+    );
+    let data = CreateExecutionProcess {
+        session_id,
+        executor_action: action,
+        run_reason,
+    };
+    let id = Uuid::new_v4();
+    ExecutionProcess::create(pool, &data, id, &[])
+        .await
+        .unwrap();
+    ExecutionProcess::update_completion(pool, id, status, Some(0))
+        .await
+        .unwrap();
+    id
+}
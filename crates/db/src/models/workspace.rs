@@ -9,6 +9,7 @@ use uuid::Uuid;
 const WORKSPACE_NAME_MAX_LEN: usize = 60;
 
 use super::{
+    merge::CiStatus,
     project::Project,
     task::Task,
     workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
@@ -57,6 +58,8 @@ pub struct WorkspaceWithStatus {
     pub workspace: Workspace,
     pub is_running: bool,
     pub is_errored: bool,
+    /// CI status of the workspace's latest PR
+    pub pr_ci_status: Option<CiStatus>,
 }
 
 impl std::ops::Deref for WorkspaceWithStatus {
@@ -298,6 +301,45 @@ impl Workspace {
         .await
     }
 
+    /// Active workspaces of child tasks whose branch is stacked on `parent`,
+    /// i.e. that target the parent's branch in `repo_id` (or in any repo)
+    pub async fn find_stacked_children(
+        pool: &SqlitePool,
+        parent: &Workspace,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  w.id                AS "id!: Uuid",
+                       w.task_id           AS "task_id!: Uuid",
+                       w.container_ref,
+                       w.branch,
+                       w.agent_working_dir,
+                       w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       w.created_at        AS "created_at!: DateTime<Utc>",
+                       w.updated_at        AS "updated_at!: DateTime<Utc>",
+                       w.archived          AS "archived!: bool",
+                       w.pinned            AS "pinned!: bool",
+                       w.name
+               FROM    workspaces w
+               JOIN    tasks t ON t.id = w.task_id
+               WHERE   t.parent_workspace_id = $1
+                 AND   w.archived = 0
+                 AND   EXISTS (
+                           SELECT 1 FROM workspace_repos wr
+                           WHERE wr.workspace_id = w.id
+                             AND wr.target_branch = $2
+                             AND ($3 IS NULL OR wr.repo_id = $3)
+                       )
+               ORDER BY w.created_at ASC"#,
+            parent.id,
+            parent.branch,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn container_ref_exists(
         pool: &SqlitePool,
         container_ref: &str,
@@ -562,7 +604,7 @@ impl Workspace {
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
                       AND ep.status = 'running'
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
 
@@ -571,10 +613,19 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64",
+
+                (
+                    SELECT m.pr_ci_status
+                    FROM merges m
+                    WHERE m.workspace_id = w.id
+                      AND m.merge_type = 'pr'
+                    ORDER BY m.created_at DESC
+                    LIMIT 1
+                ) AS "pr_ci_status?: CiStatus"
 
             FROM workspaces w
             ORDER BY w.updated_at DESC"#
@@ -600,6 +651,7 @@ impl Workspace {
                 },
                 is_running: rec.is_running != 0,
                 is_errored: rec.is_errored != 0,
+                pr_ci_status: rec.pr_ci_status,
             })
             // Apply archived filter if provided
             .filter(|ws| archived.is_none_or(|a| ws.workspace.archived == a))
@@ -663,7 +715,7 @@ impl Workspace {
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
                       AND ep.status = 'running'
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
 
@@ -672,10 +724,19 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+                      AND ep.run_reason IN ('setupscript','cleanupscript','verificationscript','codingagent')
                     ORDER BY ep.created_at DESC
                     LIMIT 1
-                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64"
+                ) IN ('failed','killed') THEN 1 ELSE 0 END AS "is_errored!: i64",
+
+                (
+                    SELECT m.pr_ci_status
+                    FROM merges m
+                    WHERE m.workspace_id = w.id
+                      AND m.merge_type = 'pr'
+                    ORDER BY m.created_at DESC
+                    LIMIT 1
+                ) AS "pr_ci_status?: CiStatus"
 
             FROM workspaces w
             WHERE w.id = $1"#,
@@ -704,6 +765,7 @@ impl Workspace {
             },
             is_running: rec.is_running != 0,
             is_errored: rec.is_errored != 0,
+            pr_ci_status: rec.pr_ci_status,
         };
 
         if ws.workspace.name.is_none()
@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{merge::MergeStrategy, repo::Repo};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -20,7 +20,7 @@ pub struct WorkspaceRepo {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CreateWorkspaceRepo {
     pub repo_id: Uuid,
     pub target_branch: String,
@@ -121,6 +121,9 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.verification_script,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.merge_commit_template,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -148,6 +151,9 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.verification_script,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.merge_commit_template,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -174,6 +180,9 @@ impl WorkspaceRepo {
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
                     default_target_branch: row.default_target_branch,
+                    verification_script: row.verification_script,
+                    merge_strategy: row.merge_strategy,
+                    merge_commit_template: row.merge_commit_template,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -261,6 +270,9 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.verification_script,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.merge_commit_template,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    sync::{Arc, LazyLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::Error as AnyhowError;
 use async_trait::async_trait;
@@ -12,7 +15,7 @@ use db::{
     },
 };
 use executors::executors::ExecutorError;
-use futures::{StreamExt, TryStreamExt};
+use futures::StreamExt;
 use git2::Error as Git2Error;
 use serde_json::Value;
 use services::services::{
@@ -31,12 +34,29 @@ use services::services::{
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    task_schedule::TaskScheduleService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::sync::RwLock;
-use utils::sentry as sentry_utils;
+use utils::{log_msg::EV_RESYNC, msg_store::StreamLagged, sentry as sentry_utils};
+
+/// Tells this server process apart from earlier ones, as a clock reading in
+/// milliseconds. Event ids carry it, since the events store numbers its
+/// messages from 1 again after a restart.
+static BOOT_ID: LazyLock<u128> = LazyLock::new(|| {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default()
+});
+
+/// Asks an events client to start over. The empty id clears the id the
+/// browser resumes after, so its reconnect gets the whole history.
+fn resync_event() -> Event {
+    Event::default().event(EV_RESYNC).data("").id("")
+}
 
 #[derive(Debug, Clone, Copy, Error)]
 #[error("Remote client not configured")]
@@ -88,7 +108,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn analytics(&self) -> &Option<AnalyticsService>;
 
-    fn container(&self) -> &impl ContainerService;
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static);
 
     fn git(&self) -> &GitService;
 
@@ -129,7 +149,18 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
-        PrMonitorService::spawn(db, analytics).await
+        PrMonitorService::spawn(
+            db,
+            self.config().clone(),
+            self.container().clone(),
+            self.events().clone(),
+            analytics,
+        )
+        .await
+    }
+
+    async fn spawn_task_schedule_service(&self) -> tokio::task::JoinHandle<()> {
+        TaskScheduleService::spawn(self.db().clone(), self.container().clone()).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -206,13 +237,41 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         }
     }
 
+    /// Events as SSE, each with `<boot id>:<sequence number>` as id. A
+    /// reconnecting client passes the last id it saw and gets only what
+    /// followed. When that can't be done, because the id is no longer in
+    /// history, is from before a restart, or the client fell behind the live
+    /// events, the stream is a final `resync` event instead: the client drops
+    /// what it has, and its next connection starts from the whole history.
     async fn stream_events(
         &self,
+        last_event_id: Option<&str>,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
-        self.events()
-            .msg_store()
-            .history_plus_stream()
-            .map_ok(|m| m.to_sse_event())
+        let msg_store = self.events().msg_store();
+        let boot_id = *BOOT_ID;
+        let stream = match last_event_id.map(str::trim).filter(|id| !id.is_empty()) {
+            None => msg_store.sequenced_history_plus_stream(),
+            Some(id) => match id
+                .split_once(':')
+                .filter(|(boot, _)| boot.parse::<u128>().ok() == Some(boot_id))
+                .and_then(|(_, seq)| seq.parse::<u64>().ok())
+                .and_then(|after| msg_store.stream_since(after))
+            {
+                Some(stream) => stream,
+                None => {
+                    return futures::stream::once(async {
+                        Ok::<_, std::io::Error>(resync_event())
+                    })
+                    .boxed();
+                }
+            },
+        };
+        stream
+            .map(move |item| match item {
+                Ok((seq, m)) => Ok(m.to_sse_event().id(format!("{boot_id}:{seq}"))),
+                Err(e) if StreamLagged::is(&e) => Ok(resync_event()),
+                Err(e) => Err(e),
+            })
             .boxed()
     }
 }
@@ -26,6 +26,19 @@ pub struct CodingAgentFollowUpRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Why the follow-up was started without the user asking for it; `None`
+    /// for prompts the user sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub trigger: Option<FollowUpTrigger>,
+}
+
+/// What started an automatic follow-up
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowUpTrigger {
+    /// A verification script failed after the agent's turn
+    VerificationFailure,
 }
 
 impl CodingAgentFollowUpRequest {
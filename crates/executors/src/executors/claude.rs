@@ -38,7 +38,7 @@ use crate::{
     },
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        TodoItem, ToolStatus, TurnTokenUsage,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -399,6 +399,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Cumulative usage for the run, taken from the final result message
+    turn_usage: Option<TurnTokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -418,6 +420,7 @@ impl ClaudeLogProcessor {
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            turn_usage: None,
         }
     }
 
@@ -1267,18 +1270,32 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
                 subtype,
                 result,
                 ..
             } => {
+                if let Some(usage) = usage {
+                    self.turn_usage = Some(TurnTokenUsage {
+                        model: self.main_model_name.clone(),
+                        input_tokens: usage.input_tokens.unwrap_or(0),
+                        output_tokens: usage.output_tokens.unwrap_or(0),
+                        cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                        cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    });
+                }
+
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
+                }
+                if context_window.is_some() || usage.is_some() {
                     patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
@@ -1471,6 +1488,7 @@ impl ClaudeLogProcessor {
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                turn_usage: self.turn_usage.clone(),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
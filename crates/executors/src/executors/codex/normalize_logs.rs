@@ -36,7 +36,7 @@ use crate::{
     logs::{
         ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
         NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        ToolStatus, TurnTokenUsage,
         stderr_processor::normalize_stderr_logs,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -215,6 +215,8 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    model: Option<String>,
+    token_usage_baseline: Option<TurnTokenUsage>,
 }
 
 enum StreamingTextKind {
@@ -232,6 +234,33 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            token_usage_baseline: None,
+        }
+    }
+
+    /// Codex reports token usage accumulated over the whole thread, so the first
+    /// report seen by this process marks where the current turn started.
+    fn turn_token_usage(&mut self, total: TurnTokenUsage, last: TurnTokenUsage) -> TurnTokenUsage {
+        let baseline = self
+            .token_usage_baseline
+            .get_or_insert_with(|| TurnTokenUsage {
+                model: None,
+                input_tokens: total.input_tokens.saturating_sub(last.input_tokens),
+                output_tokens: total.output_tokens.saturating_sub(last.output_tokens),
+                cache_read_tokens: total
+                    .cache_read_tokens
+                    .saturating_sub(last.cache_read_tokens),
+                cache_write_tokens: 0,
+            });
+        TurnTokenUsage {
+            model: self.model.clone(),
+            input_tokens: total.input_tokens.saturating_sub(baseline.input_tokens),
+            output_tokens: total.output_tokens.saturating_sub(baseline.output_tokens),
+            cache_read_tokens: total
+                .cache_read_tokens
+                .saturating_sub(baseline.cache_read_tokens),
+            cache_write_tokens: 0,
         }
     }
 
@@ -387,6 +416,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -425,6 +455,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -999,6 +1030,20 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let total = &info.total_token_usage;
+                        let last = &info.last_token_usage;
+                        let turn_usage = state.turn_token_usage(
+                            codex_token_usage(
+                                total.input_tokens,
+                                total.cached_input_tokens,
+                                total.output_tokens,
+                            ),
+                            codex_token_usage(
+                                last.input_tokens,
+                                last.cached_input_tokens,
+                                last.output_tokens,
+                            ),
+                        );
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -1011,6 +1056,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        turn_usage: Some(turn_usage),
                                     },
                                 ),
                                 content: format!(
@@ -1100,6 +1146,21 @@ fn handle_jsonrpc_response(
     );
 }
 
+/// Codex counts cached tokens as part of `input_tokens`; split them out.
+fn codex_token_usage(
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    output_tokens: i64,
+) -> TurnTokenUsage {
+    TurnTokenUsage {
+        model: None,
+        input_tokens: (input_tokens - cached_input_tokens).max(0) as u64,
+        output_tokens: output_tokens.max(0) as u64,
+        cache_read_tokens: cached_input_tokens.max(0) as u64,
+        cache_write_tokens: 0,
+    }
+}
+
 fn handle_model_params(
     model: String,
     reasoning_effort: Option<ReasoningEffort>,
@@ -0,0 +1,211 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+};
+
+const CUSTOM_ACP_SESSION_NAMESPACE: &str = "custom_acp_sessions";
+
+/// Any agent that speaks the Agent Client Protocol over stdio, launched from a
+/// user-provided command.
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomAcp {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default)]
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent in ACP mode (e.g. `npx -y @zed-industries/claude-code-acp`)"
+    )]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Arguments",
+        description = "Arguments passed to the command as-is, without shell splitting"
+    )]
+    pub args: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model requested through ACP `session/set_model`, if the agent supports it"
+    )]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Mode",
+        description = "Session mode requested through ACP `session/set_mode`, if the agent supports it"
+    )]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Auto Approve",
+        description = "Approve every permission request from the agent without asking"
+    )]
+    pub auto_approve: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "MCP Config Path",
+        description = "Path to the agent's MCP configuration file, if it has one"
+    )]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomAcp {
+    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        let mut builder = CommandBuilder::new(self.command.trim());
+
+        if let Some(args) = &self.args {
+            builder = builder.extend_params(args.iter().cloned());
+        }
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    fn harness(&self) -> AcpAgentHarness {
+        let mut harness = AcpAgentHarness::with_session_namespace(CUSTOM_ACP_SESSION_NAMESPACE);
+        if let Some(model) = &self.model {
+            harness = harness.with_model(model);
+        }
+        if let Some(mode) = &self.mode {
+            harness = harness.with_mode(mode);
+        }
+        harness
+    }
+
+    fn approvals(&self) -> Option<Arc<dyn ExecutorApprovalService>> {
+        if self.auto_approve.unwrap_or(false) {
+            None
+        } else {
+            self.approvals.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAcp {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder()?.build_initial()?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_with_command(
+                current_dir,
+                combined_prompt,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder()?.build_follow_up(&[])?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness()
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        crate::executors::acp::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        self.mcp_config_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(std::path::PathBuf::from)
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder()
+            .and_then(|builder| builder.build_initial());
+
+        match program {
+            Ok(parts) if resolve_executable_path_blocking(parts.program()).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_acp(command: &str) -> CustomAcp {
+        serde_json::from_value(serde_json::json!({ "command": command })).unwrap()
+    }
+
+    #[test]
+    fn test_args_are_appended_verbatim() {
+        let mut executor = custom_acp("my-agent --acp");
+        executor.args = Some(vec!["--name".to_string(), "two words".to_string()]);
+
+        let builder = executor.build_command_builder().unwrap();
+        assert_eq!(builder.base, "my-agent --acp");
+        assert_eq!(
+            builder.params,
+            Some(vec!["--name".to_string(), "two words".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_empty_command_is_not_available() {
+        let executor = custom_acp("   ");
+        assert!(
+            executor
+                .build_command_builder()
+                .unwrap()
+                .build_initial()
+                .is_err()
+        );
+        assert!(!executor.get_availability_info().is_available());
+    }
+}
@@ -5,9 +5,12 @@ use std::{
 
 use serde_json::Value;
 
-use crate::executors::opencode::{
-    sdk::EventStreamContext,
-    types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+use crate::{
+    executors::opencode::{
+        sdk::EventStreamContext,
+        types::{MessageRole, OpencodeExecutorEvent, ProviderListResponse, SdkEvent},
+    },
+    logs::TurnTokenUsage,
 };
 
 type ProviderId = String;
@@ -146,11 +149,21 @@ pub(super) async fn maybe_emit_token_usage(context: &EventStreamContext<'_>, eve
         return;
     }
 
+    let message_usage = TurnTokenUsage {
+        model: model_id.map(str::to_string),
+        input_tokens: tokens.input as u64,
+        output_tokens: tokens.output as u64,
+        cache_read_tokens: tokens.cache.as_ref().map(|c| c.read).unwrap_or(0) as u64,
+        cache_write_tokens: tokens.cache.as_ref().map(|c| c.write).unwrap_or(0) as u64,
+    };
+
     let _ = context
         .log_writer
         .log_event(&OpencodeExecutorEvent::TokenUsage {
             total_tokens,
             model_context_window,
+            message_id: Some(message.id.clone()),
+            message_usage: Some(message_usage),
         })
         .await;
 }
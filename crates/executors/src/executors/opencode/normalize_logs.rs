@@ -14,7 +14,7 @@ use crate::{
     logs::{
         ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
         NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult,
-        ToolStatus,
+        ToolStatus, TurnTokenUsage,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -71,7 +71,12 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 OpencodeExecutorEvent::TokenUsage {
                     total_tokens,
                     model_context_window,
+                    message_id,
+                    message_usage,
                 } => {
+                    if let (Some(message_id), Some(message_usage)) = (message_id, message_usage) {
+                        state.message_usage.insert(message_id, message_usage);
+                    }
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
@@ -80,6 +85,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                turn_usage: state.turn_token_usage(),
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -177,6 +183,8 @@ struct LogState {
     todo_update_entry: Option<usize>,
     todo_update_fingerprint: Option<String>,
     retry_status_fingerprint: Option<String>,
+    // Latest token counts per assistant message, summed into the turn usage
+    message_usage: HashMap<String, TurnTokenUsage>,
 }
 
 impl LogState {
@@ -193,7 +201,25 @@ impl LogState {
             todo_update_entry: None,
             todo_update_fingerprint: None,
             retry_status_fingerprint: None,
+            message_usage: HashMap::new(),
+        }
+    }
+
+    fn turn_token_usage(&self) -> Option<TurnTokenUsage> {
+        if self.message_usage.is_empty() {
+            return None;
+        }
+        let mut turn = TurnTokenUsage::default();
+        for usage in self.message_usage.values() {
+            if turn.model.is_none() {
+                turn.model = usage.model.clone();
+            }
+            turn.input_tokens += usage.input_tokens;
+            turn.output_tokens += usage.output_tokens;
+            turn.cache_read_tokens += usage.cache_read_tokens;
+            turn.cache_write_tokens += usage.cache_write_tokens;
         }
+        Some(turn)
     }
 
     fn handle_sdk_event(&mut self, raw: &Value, worktree_path: &Path, msg_store: &Arc<MsgStore>) {
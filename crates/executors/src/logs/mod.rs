@@ -7,6 +7,11 @@ pub mod plain_text_processor;
 pub mod stderr_processor;
 pub mod utils;
 
+/// Version of the executors' log normalization output. Bump it when a change
+/// to a `normalize_logs` implementation changes the entries produced for the
+/// same raw logs, so stored entries get normalized again.
+pub const NORMALIZER_VERSION: i64 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(export)]
@@ -102,6 +107,21 @@ pub enum NormalizedEntryType {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Tokens consumed so far by the whole turn, for agents that report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub turn_usage: Option<TurnTokenUsage>,
+}
+
+/// Cumulative token counts for a single coding agent turn.
+/// `input_tokens` excludes tokens served from or written to the prompt cache.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct TurnTokenUsage {
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
@@ -145,6 +145,93 @@ pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, Norm
     })
 }
 
+/// Replay conversation patches into the final normalized entries, in entry
+/// order. Raw stdout/stderr and diff entries are left out.
+pub fn normalized_entries_from_patches<'a>(
+    patches: impl IntoIterator<Item = &'a Patch>,
+) -> Vec<(usize, NormalizedEntry)> {
+    let mut entries = BTreeMap::new();
+    for patch in patches {
+        let Ok(value) = to_value(patch) else {
+            continue;
+        };
+        for op in value.as_array().into_iter().flatten() {
+            let Some(entry_index) = op
+                .get("path")
+                .and_then(|path| path.as_str())
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            match op.get("op").and_then(|op| op.as_str()) {
+                Some("add" | "replace") => {
+                    let entry = op
+                        .get("value")
+                        .filter(|value| {
+                            value.get("type").and_then(|t| t.as_str()) == Some("NORMALIZED_ENTRY")
+                        })
+                        .and_then(|value| value.get("content"))
+                        .and_then(|content| from_value::<NormalizedEntry>(content.clone()).ok());
+                    match entry {
+                        Some(entry) => entries.insert(entry_index, entry),
+                        None => entries.remove(&entry_index),
+                    };
+                }
+                Some("remove") => {
+                    entries.remove(&entry_index);
+                }
+                _ => {}
+            }
+        }
+    }
+    entries.into_iter().collect()
+}
+
+/// Apply conversation patches to an empty conversation and turn the result
+/// into one patch that replaces a client's entries, for clients that resume
+/// after the messages they missed are gone. Returns the patch and the number
+/// of entries in it.
+pub fn conversation_snapshot<'a>(patches: impl IntoIterator<Item = &'a Patch>) -> (Patch, usize) {
+    let mut conversation = json!({ "entries": [] });
+    for patch in patches {
+        // Patches that do not apply cleanly are skipped, as clients would
+        if let Err(err) = json_patch::patch(&mut conversation, patch) {
+            tracing::debug!("Skipping conversation patch for snapshot: {err}");
+        }
+    }
+    let entries = conversation["entries"].take();
+    let len = entries.as_array().map_or(0, Vec::len);
+    let snapshot = from_value(json!([{
+        "op": PatchOperation::Replace,
+        "path": "/entries",
+        "value": entries,
+    }]))
+    .unwrap();
+    (snapshot, len)
+}
+
+/// Rewrite a live patch for a client that holds a snapshot of `snapshot_len`
+/// entries: entries the snapshot already has are replaced rather than
+/// inserted again.
+pub fn rebase_patch_onto_snapshot(patch: Patch, snapshot_len: usize) -> Patch {
+    let Ok(mut value) = to_value(&patch) else {
+        return patch;
+    };
+    for op in value.as_array_mut().into_iter().flatten() {
+        let in_snapshot = op
+            .get("path")
+            .and_then(|path| path.as_str())
+            .and_then(|path| path.strip_prefix("/entries/"))
+            .and_then(|index| index.parse::<usize>().ok())
+            .is_some_and(|index| index < snapshot_len);
+        if in_snapshot && op.get("op").and_then(|op| op.as_str()) == Some("add") {
+            op["op"] = json!(PatchOperation::Replace);
+        }
+    }
+    from_value(value).unwrap_or(patch)
+}
+
 pub fn upsert_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index: usize,
@@ -191,3 +278,70 @@ pub fn slash_commands(
     ]))
     .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::NormalizedEntryType;
+
+    fn message(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn replays_patches_into_final_entries() {
+        let patches = [
+            ConversationPatch::add_normalized_entry(0, message("first")),
+            ConversationPatch::add_stdout(1, "raw".to_string()),
+            ConversationPatch::add_normalized_entry(2, message("draft")),
+            ConversationPatch::replace(2, message("final")),
+            ConversationPatch::add_normalized_entry(3, message("gone")),
+            ConversationPatch::remove(3),
+        ];
+
+        let entries: Vec<_> = normalized_entries_from_patches(&patches)
+            .into_iter()
+            .map(|(index, entry)| (index, entry.content))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![(0, "first".to_string()), (2, "final".to_string())]
+        );
+    }
+
+    #[test]
+    fn snapshot_replaces_entries_and_live_adds_are_rebased() {
+        let patches = [
+            ConversationPatch::add_normalized_entry(0, message("first")),
+            ConversationPatch::add_stdout(1, "raw".to_string()),
+            ConversationPatch::replace(0, message("edited")),
+        ];
+        let (snapshot, len) = conversation_snapshot(&patches);
+        assert_eq!(len, 2);
+
+        let mut client = json!({ "entries": [{ "stale": true }] });
+        json_patch::patch(&mut client, &snapshot).unwrap();
+        assert_eq!(client["entries"][0]["content"]["content"], "edited");
+        assert_eq!(client["entries"][1]["content"], "raw");
+
+        // The live normalizer re-adds an entry the snapshot already has
+        let patch = rebase_patch_onto_snapshot(
+            ConversationPatch::add_normalized_entry(1, message("second")),
+            len,
+        );
+        json_patch::patch(&mut client, &patch).unwrap();
+        let patch = rebase_patch_onto_snapshot(
+            ConversationPatch::add_normalized_entry(2, message("third")),
+            len,
+        );
+        json_patch::patch(&mut client, &patch).unwrap();
+        assert_eq!(client["entries"].as_array().unwrap().len(), 3);
+        assert_eq!(client["entries"][1]["content"]["content"], "second");
+        assert_eq!(client["entries"][2]["content"]["content"], "third");
+    }
+}
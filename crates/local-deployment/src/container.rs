@@ -3,7 +3,7 @@ use std::{
     io,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
@@ -16,9 +16,11 @@ use db::{
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::ExecutionProcessRepoState,
+        merge::{Merge, MergeStatus},
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
+        scratch::{DraftFollowUpData, ReviewCommentStatus, Scratch, ScratchPayload, ScratchType},
         session::{Session, SessionError},
         task::{Task, TaskStatus},
         workspace::Workspace,
@@ -29,13 +31,16 @@ use deployment::DeploymentError;
 use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
-        coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_follow_up::{CodingAgentFollowUpRequest, FollowUpTrigger},
         coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest},
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    logs::{
+        NormalizedEntryType, TurnTokenUsage, utils::patch::extract_normalized_entry_from_patch,
+    },
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
@@ -43,15 +48,21 @@ use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
     config::Config,
-    container::{ContainerError, ContainerRef, ContainerService},
+    container::{
+        CONFLICT_RESOLUTION_PREFIX, ContainerError, ContainerRef, ContainerService, ExecutionStart,
+    },
     diff_stream::{self, DiffStreamHandle},
-    git::{GitCli, GitService},
+    git::{GitCli, GitService, GitServiceError},
     image::ImageService,
     notification::NotificationService,
+    pr_monitor::PR_FOLLOW_UP_PREFIX,
     queued_message::QueuedMessageService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
@@ -79,6 +90,8 @@ pub struct LocalContainerService {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    coding_agent_slots: Arc<Mutex<()>>,
+    log_maintenance: Arc<Mutex<Option<Instant>>>,
 }
 
 impl LocalContainerService {
@@ -113,9 +126,12 @@ impl LocalContainerService {
             approvals,
             queued_message_service,
             notification_service,
+            coding_agent_slots: Arc::new(Mutex::new(())),
+            log_maintenance: Arc::new(Mutex::new(None)),
         };
 
         container.spawn_workspace_cleanup();
+        container.spawn_log_maintenance();
 
         container
     }
@@ -242,6 +258,23 @@ impl LocalContainerService {
         });
     }
 
+    /// Maintain the logs at startup and every hour after, which also stores
+    /// the entries of processes the current normalizer hasn't seen
+    pub fn spawn_log_maintenance(&self) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut maintenance_interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(3600)); // 1 hour
+            loop {
+                maintenance_interval.tick().await;
+                let retention_days = container.config.read().await.log_retention_days;
+                if let Err(e) = container.maintain_logs(retention_days).await {
+                    tracing::error!("Failed to maintain execution process logs: {}", e);
+                }
+            }
+        });
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -321,6 +354,17 @@ impl LocalContainerService {
         for repo in repos {
             let worktree_path = workspace_root.join(&repo.name);
 
+            // Changes made while a rebase or merge is stopped on conflicts
+            // resolve it, so they belong to that operation rather than to a
+            // commit of their own
+            if matches!(self.git.detect_conflict_op(&worktree_path), Ok(Some(_))) {
+                tracing::debug!(
+                    "Skipping auto-commit in repo '{}' with a git operation in progress",
+                    repo.name
+                );
+                continue;
+            }
+
             match git.has_changes(&worktree_path) {
                 Ok(true) => {
                     repos_with_changes.push((repo.clone(), worktree_path));
@@ -475,6 +519,13 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if let Err(e) = container
+                    .update_executor_session_token_usage(&exec_id)
+                    .await
+                {
+                    tracing::warn!("Failed to update executor session token usage: {}", e);
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -488,6 +539,10 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
+                // Set when another process picks up this session, which is then
+                // finalized once that process exits instead
+                let mut continued = false;
+
                 if success || cleanup_done {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
@@ -499,6 +554,13 @@ impl LocalContainerService {
                         }
                     };
 
+                    if matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                    ) {
+                        container.resolve_review_comments(&ctx).await;
+                    }
+
                     let should_start_next = if matches!(
                         ctx.execution_process.run_reason,
                         ExecutionProcessRunReason::CodingAgent
@@ -514,8 +576,22 @@ impl LocalContainerService {
                     };
 
                     if should_start_next {
+                        // Verify coding agent changes before moving on to the next action
+                        if matches!(
+                            ctx.execution_process.run_reason,
+                            ExecutionProcessRunReason::CodingAgent
+                        ) {
+                            continued = container
+                                .try_start_verification(&ctx)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    tracing::error!("Failed to start verification scripts: {}", e);
+                                    false
+                                });
+                        }
+
                         // If the process exited successfully, start the next action
-                        if let Err(e) = container.try_start_next_action(&ctx).await {
+                        if !continued && let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
                         }
                     } else {
@@ -529,7 +605,44 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                // Hand failed verification output back to the coding agent
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::VerificationScript
+                ) && matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed)
+                {
+                    continued = container
+                        .try_start_verification_follow_up(&ctx)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::error!("Failed to start verification follow-up: {}", e);
+                            false
+                        });
+                }
+
+                // Pick up git operations the agent was resolving conflicts for
+                if !continued
+                    && container.should_finalize(&ctx)
+                    && matches!(
+                        ctx.execution_process.status,
+                        ExecutionProcessStatus::Completed
+                    )
+                {
+                    continued = container.continue_conflict_resolution(&ctx).await;
+                }
+
+                // Publish the agent's answer to PR feedback once its turn is done
+                if !continued
+                    && container.should_finalize(&ctx)
+                    && matches!(
+                        ctx.execution_process.status,
+                        ExecutionProcessStatus::Completed
+                    )
+                {
+                    container.push_pr_follow_up_result(&ctx).await;
+                }
+
+                if !continued && container.should_finalize(&ctx) {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -562,7 +675,11 @@ impl LocalContainerService {
 
                             // Execute the queued follow-up
                             if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
+                                .start_queued_follow_up(
+                                    &ctx.workspace,
+                                    &ctx.session,
+                                    &queued_msg.data,
+                                )
                                 .await
                             {
                                 tracing::error!("Failed to start queued follow-up: {}", e);
@@ -614,6 +731,17 @@ impl LocalContainerService {
             if let Some(handle) = db_stream_handle {
                 let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
             }
+            // Store the final conversation now that all raw logs are, so
+            // opening the process later doesn't replay them
+            if let Ok(Some(process)) = ExecutionProcess::find_by_id(&db.pool, exec_id).await
+                && matches!(process.run_reason, ExecutionProcessRunReason::CodingAgent)
+                && let Err(e) = container.persist_normalized_entries(&exec_id).await
+            {
+                tracing::warn!("Failed to store normalized entries of {}: {}", exec_id, e);
+            }
+            if let Err(e) = ExecutionProcessLogs::compact(&db.pool, exec_id).await {
+                tracing::warn!("Failed to compress logs of {}: {}", exec_id, e);
+            }
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
@@ -728,6 +856,35 @@ impl LocalContainerService {
         None
     }
 
+    /// Extract the latest turn token usage reported in the MsgStore history
+    fn extract_turn_token_usage(&self, exec_id: &Uuid) -> Option<TurnTokenUsage> {
+        let msg_stores = self.msg_stores.try_read().ok()?;
+        let msg_store = msg_stores.get(exec_id)?;
+
+        // Usage is cumulative, so the last report covers the whole turn
+        msg_store.get_history().iter().rev().find_map(|msg| {
+            let LogMsg::JsonPatch(patch) = msg else {
+                return None;
+            };
+            match extract_normalized_entry_from_patch(patch)?.1.entry_type {
+                NormalizedEntryType::TokenUsageInfo(info) => info.turn_usage,
+                _ => None,
+            }
+        })
+    }
+
+    /// Persist the token usage reported by the coding agent for this turn
+    async fn update_executor_session_token_usage(
+        &self,
+        exec_id: &Uuid,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(usage) = self.extract_turn_token_usage(exec_id) {
+            CodingAgentTurn::update_token_usage(&self.db.pool, *exec_id, &usage).await?;
+        }
+
+        Ok(())
+    }
+
     /// Update the coding agent turn summary with the final assistant message
     async fn update_executor_session_summary(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
         // Check if there's a coding agent turn for this execution process
@@ -844,48 +1001,82 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Start a follow-up execution from a queued message
-    async fn start_queued_follow_up(
-        &self,
-        ctx: &ExecutionContext,
-        queued_data: &DraftFollowUpData,
-    ) -> Result<ExecutionProcess, ContainerError> {
-        let executor_profile_id = queued_data.executor_profile_id.clone();
+    /// Start the verification scripts of the workspace repos after a coding agent
+    /// turn, continuing with the turn's own next action once they all pass.
+    /// Returns `false` if no repo has a verification script.
+    async fn try_start_verification(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let Some(verification_action) =
+            self.verification_actions_for_repos(&ctx.repos, action.next_action().cloned())
+        else {
+            return Ok(false);
+        };
 
-        // Validate executor matches session if session has prior executions
-        let expected_executor: Option<String> =
-            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
-                .await?
-                .map(|profile| profile.executor.to_string())
-                .or_else(|| ctx.session.executor.clone());
+        self.start_execution(
+            &ctx.workspace,
+            &ctx.session,
+            &verification_action,
+            &ExecutionProcessRunReason::VerificationScript,
+        )
+        .await?;
 
-        if let Some(expected) = expected_executor {
-            let actual = executor_profile_id.executor.to_string();
-            if expected != actual {
-                return Err(SessionError::ExecutorMismatch { expected, actual }.into());
-            }
-        }
+        Ok(true)
+    }
 
-        if ctx.session.executor.is_none() {
-            Session::update_executor(
-                &self.db.pool,
-                ctx.session.id,
-                &executor_profile_id.executor.to_string(),
-            )
-            .await?;
+    /// Ask the coding agent to fix a failed verification script, passing it the
+    /// tail of the script output. Returns `false` once the configured number of
+    /// iterations has been used up.
+    async fn try_start_verification_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let max_iterations = self.config.read().await.verification_max_iterations;
+        let failures = self.count_verification_failures(ctx.session.id).await?;
+        if failures > max_iterations {
+            tracing::info!(
+                "Verification still failing after {} iterations for workspace {}",
+                max_iterations,
+                ctx.workspace.id
+            );
+            return Ok(false);
         }
 
-        // Get latest agent session ID for session continuity (from coding agent turns)
-        let latest_agent_session_id = ExecutionProcess::find_latest_coding_agent_turn_session_id(
+        let Some(agent_session_id) = ExecutionProcess::find_latest_coding_agent_turn_session_id(
             &self.db.pool,
             ctx.session.id,
         )
-        .await?;
+        .await?
+        else {
+            return Ok(false);
+        };
+        let Some(executor_profile_id) =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await?
+        else {
+            return Ok(false);
+        };
 
-        let repos =
-            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let action = ctx.execution_process.executor_action()?;
+        let ExecutorActionType::ScriptRequest(script) = action.typ() else {
+            return Ok(false);
+        };
+
+        // Continue after the remaining verification scripts, which run again
+        // once the agent is done
+        let mut next_action = action.next_action();
+        while let Some(next) = next_action
+            && matches!(
+                next.typ(),
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerificationScript,
+                    ..
+                })
+            )
+        {
+            next_action = next.next_action();
+        }
 
+        let output = self.output_tail(&ctx.execution_process.id).await;
         let working_dir = ctx
             .workspace
             .agent_working_dir
@@ -893,88 +1084,633 @@ impl LocalContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
-        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+        let follow_up = ExecutorAction::new(
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
+                prompt: verification_follow_up_prompt(
+                    script,
+                    ctx.execution_process.exit_code,
+                    &output,
+                ),
                 session_id: agent_session_id,
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir: working_dir.clone(),
-            })
-        } else {
-            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
-                executor_profile_id: executor_profile_id.clone(),
+                executor_profile_id,
                 working_dir,
-            })
-        };
-
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+                trigger: Some(FollowUpTrigger::VerificationFailure),
+            }),
+            next_action.cloned().map(Box::new),
+        );
 
         self.start_execution(
             &ctx.workspace,
             &ctx.session,
-            &action,
+            &follow_up,
             &ExecutionProcessRunReason::CodingAgent,
         )
-        .await
-    }
-}
+        .await?;
 
-fn failure_exit_status() -> std::process::ExitStatus {
-    #[cfg(unix)]
-    {
-        use std::os::unix::process::ExitStatusExt;
-        ExitStatusExt::from_raw(256) // Exit code 1 (shifted by 8 bits)
-    }
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::ExitStatusExt;
-        ExitStatusExt::from_raw(1)
+        Ok(true)
     }
-}
 
-#[async_trait]
-impl ContainerService for LocalContainerService {
-    fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
-        &self.msg_stores
-    }
+    /// Count the failed verification runs since the user last prompted the agent
+    async fn count_verification_failures(&self, session_id: Uuid) -> Result<u32, ContainerError> {
+        let processes =
+            ExecutionProcess::find_by_session_id(&self.db.pool, session_id, false).await?;
 
-    fn db(&self) -> &DBService {
-        &self.db
-    }
+        let mut failures = 0;
+        for process in processes.iter().rev() {
+            match process.run_reason {
+                ExecutionProcessRunReason::VerificationScript
+                    if matches!(process.status, ExecutionProcessStatus::Failed) =>
+                {
+                    failures += 1;
+                }
+                ExecutionProcessRunReason::CodingAgent => {
+                    let is_verification_follow_up = matches!(
+                        process.executor_action().map(|action| action.typ()),
+                        Ok(ExecutorActionType::CodingAgentFollowUpRequest(
+                            CodingAgentFollowUpRequest {
+                                trigger: Some(FollowUpTrigger::VerificationFailure),
+                                ..
+                            }
+                        ))
+                    );
+                    if !is_verification_follow_up {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
 
-    fn git(&self) -> &GitService {
-        &self.git
+        Ok(failures)
     }
 
-    fn notification_service(&self) -> &NotificationService {
-        &self.notification_service
-    }
+    /// Last lines of the stdout and stderr of a process, read from its stored
+    /// logs once the process is no longer in memory
+    async fn output_tail(&self, exec_id: &Uuid) -> String {
+        let messages = match self.get_msg_store_by_id(exec_id).await {
+            Some(store) => store.get_history(),
+            None => ExecutionProcessLogs::find_by_execution_id(&self.db.pool, *exec_id)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|records| {
+                    ExecutionProcessLogs::parse_logs(&records).map_err(|e| e.to_string())
+                })
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read logs of execution {}: {}", exec_id, e);
+                    Vec::new()
+                }),
+        };
 
-    async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
-        self.add_db_stream_handle(id, handle).await;
+        let output: String = messages
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content),
+                _ => None,
+            })
+            .collect();
+        let lines: Vec<&str> = output.lines().collect();
+        lines[lines.len().saturating_sub(VERIFICATION_OUTPUT_TAIL_LINES)..].join("\n")
     }
 
-    async fn take_db_stream_handle(&self, id: &Uuid) -> Option<JoinHandle<()>> {
-        LocalContainerService::take_db_stream_handle(self, id).await
+    /// Whether the session's latest coding agent turn was started with a
+    /// prompt beginning with `prefix`
+    async fn last_agent_prompt_starts_with(&self, ctx: &ExecutionContext, prefix: &str) -> bool {
+        let processes = match ExecutionProcess::find_by_session_id(
+            &self.db.pool,
+            ctx.session.id,
+            false,
+        )
+        .await
+        {
+            Ok(processes) => processes,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load processes of session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return false;
+            }
+        };
+        processes
+            .iter()
+            .rev()
+            .find(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+            .and_then(|p| p.executor_action().ok())
+            .is_some_and(|action| match action.typ() {
+                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                    request.prompt.starts_with(prefix)
+                }
+                ExecutorActionType::CodingAgentInitialRequest(request) => {
+                    request.prompt.starts_with(prefix)
+                }
+                _ => false,
+            })
     }
 
-    async fn git_branch_prefix(&self) -> String {
-        self.config.read().await.git_branch_prefix.clone()
-    }
+    /// Continue the git operations whose conflicts the agent turn that just
+    /// finished was resolving. Conflicts on a later rebase step go back to the
+    /// agent; returns true when that follow-up was started.
+    async fn continue_conflict_resolution(&self, ctx: &ExecutionContext) -> bool {
+        if !self
+            .last_agent_prompt_starts_with(ctx, CONFLICT_RESOLUTION_PREFIX)
+            .await
+        {
+            return false;
+        }
+        let Some(container_ref) = &ctx.workspace.container_ref else {
+            return false;
+        };
+        let repos =
+            match WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await {
+                Ok(repos) => repos,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load repos of workspace {}: {}",
+                        ctx.workspace.id,
+                        e
+                    );
+                    return false;
+                }
+            };
 
-    fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
-        PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
+        for repo in repos {
+            let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+            if !matches!(self.git.detect_conflict_op(&worktree_path), Ok(Some(_))) {
+                continue;
+            }
+            // Only an agent that left no markers behind gets another round
+            match self.git.find_conflict_markers(&worktree_path) {
+                Ok(files) if files.is_empty() => {}
+                Ok(files) => {
+                    tracing::warn!(
+                        "Conflict markers remain in {} of workspace {}: {}",
+                        repo.name,
+                        ctx.workspace.id,
+                        files.join(", ")
+                    );
+                    self.push_conversation_error(
+                        ctx.execution_process.id,
+                        format!(
+                            "Conflict markers remain in {}: {}. The git operation was left \
+                             in progress; remove them and continue it, or ask the agent again.",
+                            repo.name,
+                            files.join(", ")
+                        ),
+                    )
+                    .await;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to check conflict markers in {}: {}", repo.name, e);
+                    continue;
+                }
+            }
+
+            match self.git.continue_conflicts(&worktree_path) {
+                Ok(()) => tracing::info!(
+                    "Continued resolved git operation in {} of workspace {}",
+                    repo.name,
+                    ctx.workspace.id
+                ),
+                Err(GitServiceError::MergeConflicts { .. }) => {
+                    match self
+                        .resolve_conflicts_with_agent(&ctx.workspace, &repo)
+                        .await
+                    {
+                        Ok(true) => return true,
+                        Ok(false) => {}
+                        Err(e) => tracing::error!(
+                            "Failed to hand new conflicts in {} to the agent: {}",
+                            repo.name,
+                            e
+                        ),
+                    }
+                }
+                Err(e) => tracing::error!(
+                    "Failed to continue git operation in {} of workspace {}: {}",
+                    repo.name,
+                    ctx.workspace.id,
+                    e
+                ),
+            }
+        }
+        false
     }
 
-    async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
-        let task = workspace
-            .parent_task(&self.db.pool)
-            .await?
-            .ok_or(sqlx::Error::RowNotFound)?;
+    /// Show an error at the end of a process's conversation. It goes through
+    /// stderr so the normalizer numbers it along with the agent's entries.
+    async fn push_conversation_error(&self, exec_id: Uuid, message: String) {
+        if let Some(store) = self.get_msg_store_by_id(&exec_id).await {
+            store.push_stderr(format!("{message}\n"));
+            return;
+        }
+        let line = match serde_json::to_string(&LogMsg::Stderr(format!("{message}\n"))) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize message for {}: {}", exec_id, e);
+                return;
+            }
+        };
+        if let Err(e) =
+            ExecutionProcessLogs::append_log_line(&self.db.pool, exec_id, &format!("{line}\n"))
+                .await
+        {
+            tracing::warn!("Failed to store message for {}: {}", exec_id, e);
+        }
+    }
 
-        let workspace_dir_name =
-            LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
+    /// Mark the sent review comments whose lines the agent turn that just
+    /// finished changed as resolved
+    async fn resolve_review_comments(&self, ctx: &ExecutionContext) {
+        let pool = &self.db.pool;
+        let data =
+            match Scratch::find_by_id(pool, ctx.workspace.id, &ScratchType::ReviewComments).await {
+                Ok(Some(Scratch {
+                    payload: ScratchPayload::ReviewComments(data),
+                    ..
+                })) => data,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load review comments of workspace {}: {}",
+                        ctx.workspace.id,
+                        e
+                    );
+                    return;
+                }
+            };
+        if !data
+            .comments
+            .iter()
+            .any(|c| c.status == ReviewCommentStatus::Sent)
+        {
+            return;
+        }
+        let Ok(repo_states) =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, ctx.execution_process.id)
+                .await
+        else {
+            return;
+        };
+        let workspace_root = self.workspace_to_current_dir(&ctx.workspace);
+
+        let mut changed_lines: HashMap<(Uuid, String), Vec<(u32, u32)>> = HashMap::new();
+        let mut resolved_ids = Vec::new();
+        for comment in data
+            .comments
+            .iter()
+            .filter(|c| c.status == ReviewCommentStatus::Sent)
+        {
+            let key = (comment.repo_id, comment.file_path.clone());
+            if !changed_lines.contains_key(&key) {
+                let ranges = ctx
+                    .repos
+                    .iter()
+                    .find(|repo| repo.id == comment.repo_id)
+                    .zip(
+                        repo_states
+                            .iter()
+                            .find(|state| state.repo_id == comment.repo_id)
+                            .and_then(|state| state.before_head_commit.clone()),
+                    )
+                    .and_then(|(repo, before)| {
+                        let worktree_path = workspace_root.join(&repo.name);
+                        let head = self.git.get_head_info(&worktree_path).ok()?;
+                        self.git
+                            .get_changed_line_ranges(
+                                &worktree_path,
+                                &before,
+                                &head.oid,
+                                &comment.file_path,
+                            )
+                            .ok()
+                    })
+                    .unwrap_or_default();
+                changed_lines.insert(key.clone(), ranges);
+            }
+            if changed_lines[&key]
+                .iter()
+                .any(|&(start, end)| start <= comment.end_line && comment.start_line <= end)
+            {
+                resolved_ids.push(comment.id);
+            }
+        }
+        if resolved_ids.is_empty() {
+            return;
+        }
+
+        // The diff checks above take a while; only flip statuses by id so
+        // comments the user changed meanwhile are kept
+        let resolved = match Scratch::transition_review_comments(
+            pool,
+            ctx.workspace.id,
+            &resolved_ids,
+            ReviewCommentStatus::Sent,
+            ReviewCommentStatus::Resolved,
+        )
+        .await
+        {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to save resolved review comments of workspace {}: {}",
+                    ctx.workspace.id,
+                    e
+                );
+                return;
+            }
+        };
+        tracing::info!(
+            "Resolved {} review comments of workspace {}",
+            resolved,
+            ctx.workspace.id
+        );
+    }
+
+    /// Push the workspace branch to its open PRs when the agent turn that just
+    /// finished was answering PR feedback
+    async fn push_pr_follow_up_result(&self, ctx: &ExecutionContext) {
+        let pool = &self.db.pool;
+        if !self
+            .last_agent_prompt_starts_with(ctx, PR_FOLLOW_UP_PREFIX)
+            .await
+        {
+            return;
+        }
+
+        let Some(container_ref) = &ctx.workspace.container_ref else {
+            return;
+        };
+        let (merges, repos) = match tokio::try_join!(
+            Merge::find_by_workspace_id(pool, ctx.workspace.id),
+            WorkspaceRepo::find_repos_for_workspace(pool, ctx.workspace.id)
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load PRs of workspace {}: {}",
+                    ctx.workspace.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        for repo in repos {
+            let has_open_pr = merges.iter().any(|merge| {
+                matches!(merge, Merge::Pr(pr)
+                    if pr.repo_id == repo.id && matches!(pr.pr_info.status, MergeStatus::Open))
+            });
+            if !has_open_pr {
+                continue;
+            }
+
+            let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+            match self
+                .git
+                .push_to_remote(&worktree_path, &ctx.workspace.branch, false)
+            {
+                Ok(()) => tracing::info!(
+                    "Pushed PR follow-up of workspace {} for repo {}",
+                    ctx.workspace.id,
+                    repo.name
+                ),
+                Err(e) => tracing::error!(
+                    "Failed to push PR follow-up of workspace {} for repo {}: {}",
+                    ctx.workspace.id,
+                    repo.name,
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Send a prompt to the agent of the workspace's latest session, queued
+    /// behind a running process when `queue_if_running` is set
+    async fn send_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+        queue_if_running: bool,
+    ) -> Result<bool, ContainerError> {
+        let Some(session) =
+            Session::find_latest_by_workspace_id(&self.db.pool, workspace.id).await?
+        else {
+            return Ok(false);
+        };
+        let Some(executor_profile_id) =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, session.id)
+                .await?
+        else {
+            return Ok(false);
+        };
+        // Never replace a message the user queued
+        if self.queued_message_service.has_queued(session.id) {
+            return Ok(false);
+        }
+
+        let data = DraftFollowUpData {
+            message: prompt,
+            executor_profile_id,
+        };
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            &self.db.pool,
+            workspace.id,
+        )
+        .await?
+        {
+            if !queue_if_running {
+                return Ok(false);
+            }
+            self.queued_message_service.queue_message(session.id, data);
+            return Ok(true);
+        }
+
+        self.ensure_container_exists(workspace).await?;
+        self.start_queued_follow_up(workspace, &session, &data)
+            .await?;
+        Ok(true)
+    }
+
+    /// Start a follow-up execution from a queued message
+    async fn start_queued_follow_up(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        queued_data: &DraftFollowUpData,
+    ) -> Result<ExecutionStart, ContainerError> {
+        let executor_profile_id = queued_data.executor_profile_id.clone();
+
+        // Validate executor matches session if session has prior executions
+        let expected_executor: Option<String> =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, session.id)
+                .await?
+                .map(|profile| profile.executor.to_string())
+                .or_else(|| session.executor.clone());
+
+        if let Some(expected) = expected_executor {
+            let actual = executor_profile_id.executor.to_string();
+            if expected != actual {
+                return Err(SessionError::ExecutorMismatch { expected, actual }.into());
+            }
+        }
+
+        if session.executor.is_none() {
+            Session::update_executor(
+                &self.db.pool,
+                session.id,
+                &executor_profile_id.executor.to_string(),
+            )
+            .await?;
+        }
+
+        // Get latest agent session ID for session continuity (from coding agent turns)
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(&self.db.pool, session.id)
+                .await?;
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: queued_data.message.clone(),
+                session_id: agent_session_id,
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir: working_dir.clone(),
+                trigger: None,
+            })
+        } else {
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: queued_data.message.clone(),
+                executor_profile_id: executor_profile_id.clone(),
+                working_dir,
+            })
+        };
+
+        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+
+        self.start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+}
+
+const VERIFICATION_OUTPUT_TAIL_LINES: usize = 100;
+
+fn verification_follow_up_prompt(
+    script: &ScriptRequest,
+    exit_code: Option<i64>,
+    output: &str,
+) -> String {
+    let location = script
+        .working_dir
+        .as_deref()
+        .map(|dir| format!(" in `{dir}`"))
+        .unwrap_or_default();
+    let exit_code = exit_code
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "The verification script failed. It ran{location} and exited with code {exit_code}. Fix the cause of the failure; the script runs again when you are done.\n\nScript:\n```bash\n{}\n```\n\nEnd of the output:\n```\n{output}\n```",
+        script.script.trim()
+    )
+}
+
+fn failure_exit_status() -> std::process::ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatusExt::from_raw(256) // Exit code 1 (shifted by 8 bits)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatusExt::from_raw(1)
+    }
+}
+
+#[async_trait]
+impl ContainerService for LocalContainerService {
+    fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
+        &self.msg_stores
+    }
+
+    fn db(&self) -> &DBService {
+        &self.db
+    }
+
+    fn git(&self) -> &GitService {
+        &self.git
+    }
+
+    fn notification_service(&self) -> &NotificationService {
+        &self.notification_service
+    }
+
+    fn coding_agent_slots(&self) -> &Mutex<()> {
+        &self.coding_agent_slots
+    }
+
+    fn log_maintenance(&self) -> &Mutex<Option<Instant>> {
+        &self.log_maintenance
+    }
+
+    async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
+        self.add_db_stream_handle(id, handle).await;
+    }
+
+    async fn take_db_stream_handle(&self, id: &Uuid) -> Option<JoinHandle<()>> {
+        LocalContainerService::take_db_stream_handle(self, id).await
+    }
+
+    async fn git_branch_prefix(&self) -> String {
+        self.config.read().await.git_branch_prefix.clone()
+    }
+
+    async fn max_concurrent_coding_agents(&self) -> Option<u32> {
+        self.config.read().await.max_concurrent_coding_agents
+    }
+
+    async fn queue_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<bool, ContainerError> {
+        self.send_follow_up(workspace, prompt, true).await
+    }
+
+    async fn start_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<bool, ContainerError> {
+        self.send_follow_up(workspace, prompt, false).await
+    }
+
+    fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
+        PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
+    }
+
+    async fn create(&self, workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
+        let task = workspace
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let workspace_dir_name =
+            LocalContainerService::dir_name_from_workspace(&workspace.id, &task.title);
         let workspace_dir = WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name);
 
         let workspace_repos =
@@ -1126,7 +1862,8 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::CustomAcp,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
@@ -1446,3 +2183,833 @@ fn success_exit_status() -> std::process::ExitStatus {
         ExitStatusExt::from_raw(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use db::models::{
+        approval::{Approval, ApprovalRecordStatus},
+        execution_process::CreateExecutionProcess,
+        execution_process_normalized_entries::{
+            ExecutionProcessNormalizedEntries, NormalizedEntriesPage, NormalizedEntriesQuery,
+        },
+        project::{CreateProject, Project},
+        queued_execution::QueuedExecution,
+        session::CreateSession,
+        task::CreateTask,
+        task_dependency::TaskDependency,
+        workspace::CreateWorkspace,
+        workspace_repo::CreateWorkspaceRepo,
+    };
+    use executors::{
+        actions::script::ScriptRequestLanguage,
+        logs::{NORMALIZER_VERSION, NormalizedEntry, utils::patch::ConversationPatch},
+        profile::ExecutorProfileId,
+    };
+    use services::services::{container::ParentBranchChange, git_host::SelfHostedGitHosts};
+    use sqlx::SqlitePool;
+    use tempfile::TempDir;
+    use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
+
+    use super::*;
+
+    fn git(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo_path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn commit_file(repo_path: &Path, content: &str, message: &str) {
+        fs::write(repo_path.join("file.txt"), content).unwrap();
+        git(repo_path, &["add", "-A"]);
+        git(repo_path, &["commit", "-m", message]);
+    }
+
+    fn commit_new_file(repo_path: &Path, name: &str, message: &str) {
+        fs::write(repo_path.join(name), message).unwrap();
+        git(repo_path, &["add", "-A"]);
+        git(repo_path, &["commit", "-m", message]);
+    }
+
+    fn container(pool: SqlitePool) -> LocalContainerService {
+        let msg_stores = Arc::new(RwLock::new(HashMap::new()));
+        let config = Arc::new(RwLock::new(Config::default()));
+        LocalContainerService {
+            db: DBService { pool: pool.clone() },
+            child_store: Arc::new(RwLock::new(HashMap::new())),
+            cancellation_tokens: Arc::new(RwLock::new(HashMap::new())),
+            msg_stores: msg_stores.clone(),
+            db_stream_handles: Arc::new(RwLock::new(HashMap::new())),
+            exit_monitor_handles: Arc::new(RwLock::new(HashMap::new())),
+            config: config.clone(),
+            git: GitService::new(),
+            image_service: ImageService::new(pool.clone()).unwrap(),
+            analytics: None,
+            approvals: Approvals::new(msg_stores, pool),
+            queued_message_service: QueuedMessageService::new(),
+            notification_service: NotificationService::new(config),
+            coding_agent_slots: Arc::new(Mutex::new(())),
+            log_maintenance: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// A workspace whose repo stopped a rebase on conflicts, with the
+    /// agent's resolution left unstaged, after a conflict resolution turn
+    async fn resolved_rebase(pool: &SqlitePool, root: &Path) -> ExecutionContext {
+        let repo_path = root.join("app");
+        fs::create_dir_all(&repo_path).unwrap();
+        git(&repo_path, &["init", "-b", "main"]);
+        commit_file(&repo_path, "base\n", "base");
+        git(&repo_path, &["checkout", "-b", "feature"]);
+        commit_file(&repo_path, "feature\n", "feature");
+        git(&repo_path, &["checkout", "main"]);
+        commit_file(&repo_path, "main\n", "main");
+        git(&repo_path, &["checkout", "feature"]);
+        git(&repo_path, &["rebase", "main"]);
+        fs::write(repo_path.join("file.txt"), "main and feature\n").unwrap();
+
+        let repo = Repo::find_or_create(pool, &repo_path, "app").await.unwrap();
+        let (workspace_id, session_id) = session(pool, Some(root)).await;
+        let workspace_repo = CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch: "main".to_string(),
+        };
+        WorkspaceRepo::create_many(pool, workspace_id, &[workspace_repo])
+            .await
+            .unwrap();
+        let prompt =
+            format!("{CONFLICT_RESOLUTION_PREFIX} A rebase in `app` stopped on conflicts.");
+        let process_id = agent_process(pool, session_id, prompt).await;
+        ExecutionProcess::load_context(pool, process_id)
+            .await
+            .unwrap()
+    }
+
+    /// A workspace, with its worktrees under `container_ref`, and a session
+    /// in it
+    async fn session(pool: &SqlitePool, container_ref: Option<&Path>) -> (Uuid, Uuid) {
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: "app".to_string(),
+            repositories: Vec::new(),
+        };
+        Project::create(pool, &project, project_id).await.unwrap();
+        let task_id = Uuid::new_v4();
+        let task = CreateTask::from_title_description(project_id, "Fix".to_string(), None);
+        Task::create(pool, &task, task_id).await.unwrap();
+        let workspace_id = Uuid::new_v4();
+        let workspace = CreateWorkspace {
+            branch: "feature".to_string(),
+            agent_working_dir: None,
+        };
+        Workspace::create(pool, &workspace, workspace_id, task_id)
+            .await
+            .unwrap();
+        if let Some(container_ref) = container_ref {
+            Workspace::update_container_ref(pool, workspace_id, &container_ref.to_string_lossy())
+                .await
+                .unwrap();
+        }
+        let session_id = Uuid::new_v4();
+        let session = CreateSession { executor: None };
+        Session::create(pool, &session, session_id, workspace_id)
+            .await
+            .unwrap();
+        (workspace_id, session_id)
+    }
+
+    /// A workspace of a new task in `repo`, stacked on `parent` when given,
+    /// with its worktrees under `root/{branch}`
+    async fn stacked_workspace(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo: &Repo,
+        root: &Path,
+        branch: &str,
+        target_branch: &str,
+        parent: Option<&Workspace>,
+    ) -> Workspace {
+        let task_id = Uuid::new_v4();
+        let task = CreateTask {
+            parent_workspace_id: parent.map(|w| w.id),
+            ..CreateTask::from_title_description(project_id, branch.to_string(), None)
+        };
+        Task::create(pool, &task, task_id).await.unwrap();
+        let workspace_id = Uuid::new_v4();
+        let workspace = CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        };
+        Workspace::create(pool, &workspace, workspace_id, task_id)
+            .await
+            .unwrap();
+        Workspace::update_container_ref(pool, workspace_id, &root.join(branch).to_string_lossy())
+            .await
+            .unwrap();
+        let workspace_repo = CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch: target_branch.to_string(),
+        };
+        WorkspaceRepo::create_many(pool, workspace_id, &[workspace_repo])
+            .await
+            .unwrap();
+        Workspace::find_by_id(pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    /// A running process of the session
+    async fn running_process(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        action: ExecutorActionType,
+        run_reason: ExecutionProcessRunReason,
+    ) -> Uuid {
+        let process = CreateExecutionProcess {
+            session_id,
+            executor_action: ExecutorAction::new(action, None),
+            run_reason,
+        };
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create(pool, &process, process_id, &[])
+            .await
+            .unwrap();
+        process_id
+    }
+
+    /// A running coding agent turn
+    async fn agent_process(pool: &SqlitePool, session_id: Uuid, prompt: String) -> Uuid {
+        follow_up_process(pool, session_id, prompt, None).await
+    }
+
+    async fn follow_up_process(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        prompt: String,
+        trigger: Option<FollowUpTrigger>,
+    ) -> Uuid {
+        let request = CodingAgentFollowUpRequest {
+            prompt,
+            session_id: "agent-session".to_string(),
+            executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            working_dir: None,
+            trigger,
+        };
+        running_process(
+            pool,
+            session_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request),
+            ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
+    /// A verification script run that failed
+    async fn failed_verification(pool: &SqlitePool, session_id: Uuid) -> Uuid {
+        let request = ScriptRequest {
+            script: "cargo test".to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::VerificationScript,
+            working_dir: None,
+        };
+        let id = running_process(
+            pool,
+            session_id,
+            ExecutorActionType::ScriptRequest(request),
+            ExecutionProcessRunReason::VerificationScript,
+        )
+        .await;
+        ExecutionProcess::update_completion(pool, id, ExecutionProcessStatus::Failed, Some(101))
+            .await
+            .unwrap();
+        id
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_conflict_resolution_turn_continues_rebase_without_auto_commit(pool: SqlitePool) {
+        let root = TempDir::new().unwrap();
+        let ctx = resolved_rebase(&pool, root.path()).await;
+        let container = container(pool);
+        let repo_path = root.path().join("app");
+
+        // What the exit monitor does after a successful agent turn
+        assert!(!container.try_commit_changes(&ctx).await.unwrap());
+        assert!(!container.continue_conflict_resolution(&ctx).await);
+
+        assert!(matches!(
+            container.git.detect_conflict_op(&repo_path),
+            Ok(None)
+        ));
+        assert_eq!(
+            git(&repo_path, &["log", "--format=%s", "main..HEAD"]),
+            "feature\n"
+        );
+        assert_eq!(
+            git(&repo_path, &["show", "HEAD:file.txt"]),
+            "main and feature\n"
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_leftover_conflict_markers_are_reported_in_the_conversation(pool: SqlitePool) {
+        let root = TempDir::new().unwrap();
+        let ctx = resolved_rebase(&pool, root.path()).await;
+        let repo_path = root.path().join("app");
+        fs::write(
+            repo_path.join("file.txt"),
+            "<<<<<<< HEAD\nmain\n=======\nfeature\n>>>>>>> feature\n",
+        )
+        .unwrap();
+        let container = container(pool);
+        let store = Arc::new(MsgStore::new());
+        container
+            .msg_stores
+            .write()
+            .await
+            .insert(ctx.execution_process.id, store.clone());
+
+        assert!(!container.continue_conflict_resolution(&ctx).await);
+
+        assert!(matches!(
+            container.git.detect_conflict_op(&repo_path),
+            Ok(Some(_))
+        ));
+        assert!(store.get_history().iter().any(|msg| matches!(
+            msg,
+            LogMsg::Stderr(text) if text.starts_with("Conflict markers remain in app: file.txt.")
+        )));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_verification_failures_count_back_to_the_last_user_prompt(pool: SqlitePool) {
+        let (_, session_id) = session(&pool, None).await;
+        let container = container(pool.clone());
+        let count = || container.count_verification_failures(session_id);
+
+        agent_process(&pool, session_id, "Fix the parser".to_string()).await;
+        failed_verification(&pool, session_id).await;
+        assert_eq!(count().await.unwrap(), 1);
+
+        follow_up_process(
+            &pool,
+            session_id,
+            "Tests fail".to_string(),
+            Some(FollowUpTrigger::VerificationFailure),
+        )
+        .await;
+        failed_verification(&pool, session_id).await;
+        assert_eq!(count().await.unwrap(), 2);
+
+        // Written by the user, even though it reads like a verification prompt
+        agent_process(
+            &pool,
+            session_id,
+            "The verification script failed. Try another approach.".to_string(),
+        )
+        .await;
+        assert_eq!(count().await.unwrap(), 0);
+        failed_verification(&pool, session_id).await;
+        assert_eq!(count().await.unwrap(), 1);
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_output_tail_reads_stored_logs_of_finished_processes(pool: SqlitePool) {
+        let (_, session_id) = session(&pool, None).await;
+        let id = failed_verification(&pool, session_id).await;
+        for line in 0..VERIFICATION_OUTPUT_TAIL_LINES + 5 {
+            let msg = if line % 2 == 0 {
+                LogMsg::Stdout(format!("line {line}\n"))
+            } else {
+                LogMsg::Stderr(format!("line {line}\n"))
+            };
+            let line = serde_json::to_string(&msg).unwrap();
+            ExecutionProcessLogs::append_log_line(&pool, id, &format!("{line}\n"))
+                .await
+                .unwrap();
+        }
+
+        // No message store: the process was cleaned up after it exited
+        let container = container(pool);
+        let tail = container.output_tail(&id).await;
+        let lines: Vec<_> = tail.lines().collect();
+        assert_eq!(lines.len(), VERIFICATION_OUTPUT_TAIL_LINES);
+        assert_eq!(lines[0], "line 5");
+        assert_eq!(
+            lines.last().copied(),
+            Some(format!("line {}", VERIFICATION_OUTPUT_TAIL_LINES + 4).as_str())
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_archive_other_attempts_keeps_pinned_attempts(pool: SqlitePool) {
+        let (winner_id, _) = session(&pool, None).await;
+        let task_id = Workspace::find_by_id(&pool, winner_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .task_id;
+        let attempt = |branch: &str| {
+            let pool = pool.clone();
+            let workspace = CreateWorkspace {
+                branch: branch.to_string(),
+                agent_working_dir: None,
+            };
+            async move {
+                let id = Uuid::new_v4();
+                Workspace::create(&pool, &workspace, id, task_id)
+                    .await
+                    .unwrap();
+                id
+            }
+        };
+        let loser = attempt("loser").await;
+        let pinned = attempt("pinned").await;
+        Workspace::update(&pool, pinned, None, Some(true), None)
+            .await
+            .unwrap();
+        let archived = attempt("archived").await;
+        Workspace::set_archived(&pool, archived, true)
+            .await
+            .unwrap();
+        Workspace::set_archived(&pool, winner_id, true)
+            .await
+            .unwrap();
+        let winner = Workspace::find_by_id(&pool, winner_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let container = container(pool.clone());
+        let archived_ids = container.archive_other_attempts(&winner).await.unwrap();
+
+        assert_eq!(archived_ids, vec![loser]);
+        for (id, expected) in [(winner_id, false), (loser, true), (pinned, false)] {
+            let workspace = Workspace::find_by_id(&pool, id).await.unwrap().unwrap();
+            assert_eq!(workspace.archived, expected);
+        }
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_discard_task_attempt_removes_worktrees_and_workspace(pool: SqlitePool) {
+        let root = TempDir::new().unwrap();
+        let workspace_dir = root.path().join("attempt");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        let (workspace_id, session_id) = session(&pool, Some(&workspace_dir)).await;
+        failed_verification(&pool, session_id).await;
+        let workspace = Workspace::find_by_id(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let container = container(pool.clone());
+        container.discard_task_attempt(&workspace).await.unwrap();
+
+        assert!(!workspace_dir.exists());
+        assert!(
+            Workspace::find_by_id(&pool, workspace_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            Session::find_by_id(&pool, session_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_coding_agent_start_waits_in_queue_for_a_free_slot(pool: SqlitePool) {
+        let (_, busy_session_id) = session(&pool, None).await;
+        let running = agent_process(&pool, busy_session_id, "Fix the parser".to_string()).await;
+        let (workspace_id, session_id) = session(&pool, None).await;
+        let workspace = Workspace::find_by_id(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let session = Session::find_by_id(&pool, session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let container = container(pool.clone());
+        container.config.write().await.max_concurrent_coding_agents = Some(1);
+
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: "Fix the lexer".to_string(),
+                executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                working_dir: None,
+            }),
+            None,
+        );
+        let start = container
+            .start_execution(
+                &workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(start, ExecutionStart::Queued));
+        let task = workspace.parent_task(&pool).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::InProgress);
+        let queued = || QueuedExecution::find_by_workspace_id(&pool, workspace_id);
+        assert_eq!(queued().await.unwrap().len(), 1);
+
+        // Still no free slot
+        container.start_queued_executions().await;
+        assert_eq!(queued().await.unwrap().len(), 1);
+
+        // Once the running agent is done the queued start is taken off the
+        // queue; it then fails here for lack of repos
+        ExecutionProcess::update_completion(
+            &pool,
+            running,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        container.start_queued_executions().await;
+        assert!(queued().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_blocked_task_is_not_started(pool: SqlitePool) {
+        let (workspace_id, _) = session(&pool, None).await;
+        let blocker = Workspace::find_by_id(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .parent_task(&pool)
+            .await
+            .unwrap()
+            .unwrap();
+        let task_id = Uuid::new_v4();
+        let create =
+            CreateTask::from_title_description(blocker.project_id, "Follow-up".to_string(), None);
+        let task = Task::create(&pool, &create, task_id).await.unwrap();
+        TaskDependency::create(&pool, task_id, blocker.id)
+            .await
+            .unwrap();
+
+        let container = container(pool.clone());
+        let result = container
+            .start_task_attempt(
+                &task,
+                ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                &[],
+            )
+            .await;
+
+        assert!(matches!(result, Err(ContainerError::TaskBlocked)));
+        assert!(
+            Workspace::fetch_all(&pool, Some(task_id))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_orphaned_process_interrupts_pending_approvals(pool: SqlitePool) {
+        let (workspace_id, session_id) = session(&pool, None).await;
+        let id = agent_process(&pool, session_id, "Fix the parser".to_string()).await;
+        let request = ApprovalRequest::from_create(
+            CreateApprovalRequest {
+                tool_name: "Bash".to_string(),
+                tool_input: serde_json::json!({ "command": "cargo test" }),
+                tool_call_id: "call".to_string(),
+            },
+            id,
+            60,
+        );
+        Approval::create(&pool, &request, &ApprovalStatus::Pending, None)
+            .await
+            .unwrap();
+
+        // What startup does with processes left running by a previous server
+        let container = container(pool.clone());
+        container.cleanup_orphan_executions().await.unwrap();
+
+        let approval = Approval::find_by_id(&pool, &request.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(approval.status, ApprovalRecordStatus::Interrupted);
+        assert!(approval.resumed_execution_process_id.is_none());
+        let process = ExecutionProcess::find_by_id(&pool, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(process.status, ExecutionProcessStatus::Failed);
+        let workspace = Workspace::find_by_id(&pool, workspace_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let task = workspace.parent_task(&pool).await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::InReview);
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_renormalize_stale_logs_keeps_conversation_indices(pool: SqlitePool) {
+        let (_, session_id) = session(&pool, None).await;
+        let id = agent_process(&pool, session_id, "Fix the parser".to_string()).await;
+        let message = |content: &str| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        };
+        // Raw output between the two messages leaves a hole in the indices
+        let logs = [
+            ConversationPatch::add_normalized_entry(0, message("Looking")),
+            ConversationPatch::add_stdout(1, "raw output".to_string()),
+            ConversationPatch::add_normalized_entry(2, message("Fixed")),
+        ];
+        for patch in logs {
+            let line = serde_json::to_string(&LogMsg::JsonPatch(patch)).unwrap();
+            ExecutionProcessLogs::append_log_line(&pool, id, &format!("{line}\n"))
+                .await
+                .unwrap();
+        }
+        ExecutionProcess::update_completion(&pool, id, ExecutionProcessStatus::Completed, Some(0))
+            .await
+            .unwrap();
+
+        let container = container(pool.clone());
+        container.renormalize_stale_logs().await.unwrap();
+
+        let entries =
+            ExecutionProcessNormalizedEntries::find_by_execution_id(&pool, id, NORMALIZER_VERSION)
+                .await
+                .unwrap()
+                .unwrap();
+        let entries: Vec<_> = entries
+            .into_iter()
+            .map(|(index, entry)| (index, entry.content))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![(0, "Looking".to_string()), (2, "Fixed".to_string())]
+        );
+        assert!(
+            ExecutionProcessNormalizedEntries::find_stale_execution_ids(
+                &pool,
+                NORMALIZER_VERSION,
+                10
+            )
+            .await
+            .unwrap()
+            .is_empty()
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_entry_pages_continue_across_process_completion(pool: SqlitePool) {
+        let (_, session_id) = session(&pool, None).await;
+        let id = agent_process(&pool, session_id, "Fix the parser".to_string()).await;
+        let message = |content: &str| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        };
+        let logs = [
+            ConversationPatch::add_normalized_entry(0, message("Looking")),
+            ConversationPatch::add_stdout(1, "raw output".to_string()),
+            ConversationPatch::add_normalized_entry(2, message("Reading")),
+            ConversationPatch::add_normalized_entry(3, message("Fixed")),
+        ];
+        let container = container(pool.clone());
+        let store = Arc::new(MsgStore::new());
+        for patch in logs {
+            let line = serde_json::to_string(&LogMsg::JsonPatch(patch.clone())).unwrap();
+            ExecutionProcessLogs::append_log_line(&pool, id, &format!("{line}\n"))
+                .await
+                .unwrap();
+            store.push_patch(patch);
+        }
+        container.msg_stores.write().await.insert(id, store);
+        let contents = |page: &NormalizedEntriesPage| -> Vec<(usize, String)> {
+            page.entries
+                .iter()
+                .map(|e| (e.entry_index, e.entry.content.clone()))
+                .collect()
+        };
+
+        // The latest page is read while the process runs
+        let latest = NormalizedEntriesQuery {
+            limit: 2,
+            ..Default::default()
+        }
+        .paginate(container.normalized_entries(&id).await.unwrap());
+        assert_eq!(
+            contents(&latest),
+            vec![(2, "Reading".to_string()), (3, "Fixed".to_string())]
+        );
+        assert!(latest.has_more);
+
+        container.msg_stores.write().await.remove(&id);
+        ExecutionProcess::update_completion(&pool, id, ExecutionProcessStatus::Completed, Some(0))
+            .await
+            .unwrap();
+        assert!(container.persist_normalized_entries(&id).await.unwrap());
+
+        // and the one before it from the stored entries once it finished
+        let earlier = ExecutionProcessNormalizedEntries::find_page(
+            &pool,
+            id,
+            &NormalizedEntriesQuery {
+                before: Some(latest.entries[0].entry_index),
+                limit: 2,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(contents(&earlier), vec![(0, "Looking".to_string())]);
+        assert!(!earlier.has_more);
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_renormalize_stale_logs_is_throttled(pool: SqlitePool) {
+        let (_, session_id) = session(&pool, None).await;
+        let container = container(pool.clone());
+        container.renormalize_stale_logs().await.unwrap();
+
+        let id = agent_process(&pool, session_id, "Fix the parser".to_string()).await;
+        ExecutionProcess::update_completion(&pool, id, ExecutionProcessStatus::Completed, Some(0))
+            .await
+            .unwrap();
+        let stale = || {
+            ExecutionProcessNormalizedEntries::find_stale_execution_ids(
+                &pool,
+                NORMALIZER_VERSION,
+                10,
+            )
+        };
+
+        // Startup and the periodic maintenance asking right after each other
+        // only re-normalize once
+        container.renormalize_stale_logs().await.unwrap();
+        assert_eq!(stale().await.unwrap(), vec![id]);
+
+        *container.log_maintenance().lock().await = None;
+        container.renormalize_stale_logs().await.unwrap();
+        assert!(stale().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_restack_after_pr_merge_moves_the_stack_onto_the_fetched_target(pool: SqlitePool) {
+        let root = TempDir::new().unwrap();
+        let origin = root.path().join("origin.git");
+        let repo_path = root.path().join("app");
+        fs::create_dir_all(&origin).unwrap();
+        fs::create_dir_all(&repo_path).unwrap();
+        git(&origin, &["init", "--bare", "-b", "main"]);
+        git(&repo_path, &["init", "-b", "main"]);
+        commit_file(&repo_path, "base\n", "base");
+        git(
+            &repo_path,
+            &["remote", "add", "origin", &origin.to_string_lossy()],
+        );
+        git(&repo_path, &["push", "-u", "origin", "main"]);
+        git(&repo_path, &["checkout", "-b", "parent"]);
+        commit_new_file(&repo_path, "parent.txt", "parent");
+        git(&repo_path, &["checkout", "-b", "child"]);
+        commit_new_file(&repo_path, "child.txt", "child");
+        git(&repo_path, &["checkout", "-b", "grandchild"]);
+        commit_new_file(&repo_path, "grandchild.txt", "grandchild");
+        // Squash merge of the parent's PR on the host; neither the local
+        // target branch nor its remote-tracking branch has seen it
+        git(&repo_path, &["checkout", "-b", "squash", "main"]);
+        git(&repo_path, &["merge", "--squash", "parent"]);
+        git(&repo_path, &["commit", "-m", "parent (#1)"]);
+        git(&repo_path, &["push", "origin", "squash:main"]);
+        git(&repo_path, &["checkout", "main"]);
+        git(&repo_path, &["branch", "-D", "squash"]);
+        git(
+            &repo_path,
+            &["update-ref", "refs/remotes/origin/main", "main"],
+        );
+
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: "app".to_string(),
+            repositories: Vec::new(),
+        };
+        Project::create(&pool, &project, project_id).await.unwrap();
+        let repo = Repo::find_or_create(&pool, &repo_path, "app")
+            .await
+            .unwrap();
+        let workspaces = root.path().join("workspaces");
+        let parent = stacked_workspace(
+            &pool,
+            project_id,
+            &repo,
+            &workspaces,
+            "parent",
+            "main",
+            None,
+        )
+        .await;
+        let child = stacked_workspace(
+            &pool,
+            project_id,
+            &repo,
+            &workspaces,
+            "child",
+            "parent",
+            Some(&parent),
+        )
+        .await;
+        stacked_workspace(
+            &pool,
+            project_id,
+            &repo,
+            &workspaces,
+            "grandchild",
+            "child",
+            Some(&child),
+        )
+        .await;
+
+        let container = container(pool.clone());
+        let old_head = git(&repo_path, &["rev-parse", "parent"]).trim().to_string();
+        container
+            .restack_children(
+                &parent,
+                &repo,
+                ParentBranchChange::PrMerged { old_head },
+                &SelfHostedGitHosts::default(),
+            )
+            .await;
+
+        assert_eq!(
+            git(&repo_path, &["log", "--format=%s", "-1", "origin/main"]),
+            "parent (#1)\n"
+        );
+        assert_eq!(
+            git(&repo_path, &["log", "--format=%s", "origin/main..child"]),
+            "child\n"
+        );
+        assert_eq!(
+            git(&repo_path, &["log", "--format=%s", "child..grandchild"]),
+            "grandchild\n"
+        );
+        let child_repo = WorkspaceRepo::find_by_workspace_and_repo_id(&pool, child.id, repo.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(child_repo.target_branch, "main");
+    }
+}
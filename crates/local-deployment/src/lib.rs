@@ -130,7 +130,7 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
+        let approvals = Approvals::new(msg_stores.clone(), db.pool.clone());
         let queued_message_service = QueuedMessageService::new();
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
@@ -228,7 +228,7 @@ impl Deployment for LocalDeployment {
         &self.analytics
     }
 
-    fn container(&self) -> &impl ContainerService {
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static) {
         &self.container
     }
 
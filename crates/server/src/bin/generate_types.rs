@@ -16,6 +16,11 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::search::SearchSource::decl(),
+        db::models::search::SearchHit::decl(),
+        db::models::storage::TableSize::decl(),
+        db::models::storage::ProjectStorageSize::decl(),
+        db::models::storage::DatabaseSize::decl(),
         db::models::repo::Repo::decl(),
         db::models::repo::UpdateRepo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
@@ -30,13 +35,21 @@ fn generate_types_content() -> String {
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
+        db::models::task::StackedWorkspace::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskAutoStart::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
         db::models::scratch::PreviewSettingsData::decl(),
         db::models::scratch::WorkspaceNotesData::decl(),
+        db::models::scratch::ReviewCommentsData::decl(),
+        db::models::scratch::DiffReviewComment::decl(),
+        db::models::scratch::ReviewCommentStatus::decl(),
         db::models::scratch::ScratchPayload::decl(),
         db::models::scratch::ScratchType::decl(),
         db::models::scratch::Scratch::decl(),
@@ -51,15 +64,28 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::execution_process_normalized_entries::IndexedNormalizedEntry::decl(),
+        db::models::execution_process_normalized_entries::NormalizedEntriesPage::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
+        db::models::merge::MergeStrategy::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge::CiStatus::decl(),
+        db::models::merge::PrCheck::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
+        db::models::approval_policy::ApprovalRuleAction::decl(),
+        db::models::approval_policy::ApprovalRuleMatcher::decl(),
+        db::models::approval_policy::ApprovalRule::decl(),
+        db::models::approval_policy::ApprovalPolicy::decl(),
+        services::services::approvals::policy::ApprovalPolicyDecision::decl(),
+        db::models::approval::ApprovalRecordStatus::decl(),
+        db::models::approval::Approval::decl(),
         utils::diff::Diff::decl(),
+        utils::diff::DiffHunk::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
         utils::api::oauth::LoginStatus::decl(),
@@ -115,16 +141,23 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::TaskDependencies::decl(),
+        server::routes::tasks::AddTaskDependency::decl(),
+        server::routes::tasks::SetTaskAutoStart::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
+        server::routes::task_attempts::CreateParallelTaskAttemptsBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
+        server::routes::task_attempts::RevertChangesRequest::decl(),
+        server::routes::task_attempts::RevertChangesResponse::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
@@ -136,6 +169,8 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
         services::services::git_host::UnifiedPrComment::decl(),
         services::services::git_host::ProviderKind::decl(),
+        services::services::git_host::SelfHostedGitHosts::decl(),
+        services::services::git_host::GiteaHost::decl(),
         services::services::git_host::OpenPrInfo::decl(),
         services::services::git::GitRemote::decl(),
         server::routes::repo::ListPrsError::decl(),
@@ -148,6 +183,11 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::task_attempts::compare::TaskAttemptComparison::decl(),
+        server::routes::task_attempts::compare::PickWinnerResponse::decl(),
+        server::routes::task_attempts::fork::ForkTaskAttemptRequest::decl(),
+        server::routes::task_attempts::fork::ForkTaskAttemptResponse::decl(),
+        server::routes::task_attempts::review_comments::SendReviewResponse::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
@@ -162,10 +202,21 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::ModelPrice::decl(),
         services::services::git::GitBranch::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::container::ExecutionStart::decl(),
+        services::services::usage::UsageGroupBy::decl(),
+        services::services::usage::UsageQuery::decl(),
+        services::services::usage::UsageSummary::decl(),
         services::services::git::ConflictOp::decl(),
+        services::services::git::RevertSelection::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::Transcript::decl(),
+        services::services::transcript::TranscriptSession::decl(),
+        services::services::transcript::TranscriptTurn::decl(),
+        services::services::transcript::TranscriptDiff::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -197,9 +248,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom_acp::CustomAcp::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
+        executors::actions::coding_agent_follow_up::FollowUpTrigger::decl(),
         executors::actions::review::ReviewRequest::decl(),
         executors::actions::review::RepoReviewContext::decl(),
         executors::logs::CommandExitStatus::decl(),
@@ -207,6 +260,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::TurnTokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::TodoItem::decl(),
@@ -301,6 +355,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_acp",
+            generate_json_schema::<executors::executors::custom_acp::CustomAcp>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
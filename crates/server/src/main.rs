@@ -67,7 +67,10 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    // Slots freed by orphaned processes can go to queued coding agents
+    deployment.container().start_queued_executions().await;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_schedule_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -276,7 +276,7 @@ pub struct StartWorkspaceSessionRequest {
     #[schemars(description = "The ID of the task to start")]
     pub task_id: Uuid,
     #[schemars(
-        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID')"
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID', 'CUSTOM_ACP')"
     )]
     pub executor: String,
     #[schemars(description = "Optional executor variant, if needed")]
@@ -897,6 +897,7 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            stack_on_parent: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -5,13 +5,22 @@ use axum::{
     response::Json as ResponseJson,
     routing::post,
 };
+use db::models::{
+    approval::{Approval, ApprovalRecordStatus},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    workspace_repo::WorkspaceRepo,
+};
 use deployment::Deployment;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+};
+use services::services::container::{ContainerService, ExecutionStart};
 use utils::{
     approvals::{ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, error::ApiError};
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
@@ -43,6 +52,120 @@ pub async fn respond_to_approval(
     }
 }
 
+/// Resume the agent session of an approval that was interrupted by a server
+/// restart, asking the agent to repeat the tool call so it can be approved.
+pub async fn resume_approval(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<ExecutionStart>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let approval = Approval::find_by_id(pool, &id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Approval not found".to_string()))?;
+    if approval.status != ApprovalRecordStatus::Interrupted {
+        return Err(ApiError::Conflict(
+            "Only interrupted approvals can be resumed".to_string(),
+        ));
+    }
+    if approval.resumed_execution_process_id.is_some() {
+        return Err(ApiError::Conflict(
+            "Approval has already been resumed".to_string(),
+        ));
+    }
+
+    let ctx = ExecutionProcess::load_context(pool, approval.execution_process_id).await?;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, ctx.workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "A process is already running in this workspace".to_string(),
+        ));
+    }
+
+    let agent_session_id =
+        ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, ctx.session.id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest("The agent session cannot be resumed".to_string())
+            })?;
+    let executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest("No coding agent found for this session".to_string())
+            })?;
+
+    deployment
+        .container()
+        .ensure_container_exists(&ctx.workspace)
+        .await?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, ctx.workspace.id).await?;
+    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let working_dir = ctx
+        .workspace
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+            prompt: resume_prompt(&approval),
+            session_id: agent_session_id,
+            executor_profile_id,
+            working_dir,
+            trigger: None,
+        }),
+        cleanup_action.map(Box::new),
+    );
+
+    let start = deployment
+        .container()
+        .start_execution(
+            &ctx.workspace,
+            &ctx.session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+
+    // A queued resume counts as running for the workspace, so it cannot be
+    // resumed twice before it starts
+    let execution_process_id = match &start {
+        ExecutionStart::Started { execution_process } => {
+            Approval::set_resumed_execution_process_id(pool, &id, execution_process.id).await?;
+            Some(execution_process.id.to_string())
+        }
+        ExecutionStart::Queued => None,
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_resumed",
+            serde_json::json!({
+                "approval_id": &id,
+                "tool_name": approval.tool_name,
+                "execution_process_id": execution_process_id,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(start)))
+}
+
+fn resume_prompt(approval: &Approval) -> String {
+    let input = serde_json::to_string_pretty(&approval.tool_input.0)
+        .unwrap_or_else(|_| approval.tool_input.0.to_string());
+    format!(
+        "The previous run was interrupted while waiting for approval to use the `{}` tool with this input:\n\n```json\n{}\n```\n\nThe tool call was not executed. If it is still needed, make the call again so it can be approved, then continue with the task.",
+        approval.tool_name, input
+    )
+}
+
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals/{id}/respond", post(respond_to_approval))
+        .route("/approvals/{id}/resume", post(resume_approval))
 }
@@ -0,0 +1,17 @@
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use db::models::storage::DatabaseSize;
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_database_size(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DatabaseSize>>, ApiError> {
+    let size = DatabaseSize::measure(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(size)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/database/size", get(get_database_size))
+}
@@ -1,6 +1,7 @@
 use axum::{
     BoxError, Router,
     extract::State,
+    http::HeaderMap,
     response::{
         Sse,
         sse::{Event, KeepAlive},
@@ -14,10 +15,15 @@ use crate::DeploymentImpl;
 
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
 {
+    // Browsers send the id of the last event they got when they reconnect
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok());
     // Ask the container service for a combined "history + live" stream
-    let stream = deployment.stream_events().await;
+    let stream = deployment.stream_events(last_event_id).await;
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
 
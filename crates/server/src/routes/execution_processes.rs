@@ -10,14 +10,19 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    approval::Approval,
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
+    execution_process_normalized_entries::{
+        ExecutionProcessNormalizedEntries, NormalizedEntriesPage, NormalizedEntriesQuery,
+    },
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
+use executors::logs::NORMALIZER_VERSION;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::container::ContainerService;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use utils::{log_msg::LogMsg, msg_store::StreamLagged, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
@@ -30,6 +35,35 @@ pub struct SessionExecutionProcessQuery {
     pub show_soft_deleted: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct NormalizedLogsQuery {
+    /// Sequence number of the last message the client applied, to resume after
+    pub since: Option<u64>,
+}
+
+const DEFAULT_ENTRIES_LIMIT: usize = 100;
+const MAX_ENTRIES_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct NormalizedEntriesParams {
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+    pub limit: Option<usize>,
+    /// Comma separated `NormalizedEntryType` tags, e.g. `tool_use,error_message`
+    pub entry_types: Option<String>,
+    /// Comma separated `ActionType` tags, e.g. `command_run`
+    pub action_types: Option<String>,
+}
+
+fn split_tags(tags: Option<&str>) -> Vec<String> {
+    tags.into_iter()
+        .flat_map(|tags| tags.split(','))
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 pub async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -37,6 +71,65 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+pub async fn get_execution_process_approvals(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Approval>>>, ApiError> {
+    let approvals =
+        Approval::find_by_execution_process_id(&deployment.db().pool, execution_process.id).await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
+/// A page of the normalized conversation of a process, read from the stored
+/// entries. Finished processes whose entries are missing or outdated are
+/// normalized first; running ones are paged in memory.
+pub async fn get_normalized_entries(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(params): Query<NormalizedEntriesParams>,
+) -> Result<ResponseJson<ApiResponse<NormalizedEntriesPage>>, ApiError> {
+    if params.before.is_some() && params.after.is_some() {
+        return Err(ApiError::BadRequest(
+            "Pass either `before` or `after`, not both".to_string(),
+        ));
+    }
+    let query = NormalizedEntriesQuery {
+        before: params.before,
+        after: params.after,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_ENTRIES_LIMIT)
+            .clamp(1, MAX_ENTRIES_LIMIT),
+        entry_types: split_tags(params.entry_types.as_deref()),
+        action_types: split_tags(params.action_types.as_deref()),
+    };
+
+    let pool = &deployment.db().pool;
+    let id = execution_process.id;
+    let mut is_current =
+        ExecutionProcessNormalizedEntries::is_current(pool, id, NORMALIZER_VERSION).await?;
+    if !is_current && execution_process.status != ExecutionProcessStatus::Running {
+        deployment
+            .container()
+            .persist_normalized_entries(&id)
+            .await?;
+        is_current =
+            ExecutionProcessNormalizedEntries::is_current(pool, id, NORMALIZER_VERSION).await?;
+    }
+
+    let page = if is_current {
+        ExecutionProcessNormalizedEntries::find_page(pool, id, &query).await?
+    } else {
+        let entries = deployment
+            .container()
+            .normalized_entries(&id)
+            .await
+            .unwrap_or_default();
+        query.paginate(entries)
+    };
+    Ok(ResponseJson(ApiResponse::success(page)))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -124,10 +217,11 @@ pub async fn stream_normalized_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
     Path(exec_id): Path<Uuid>,
+    Query(query): Query<NormalizedLogsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
     let stream = deployment
         .container()
-        .stream_normalized_logs(&exec_id)
+        .stream_normalized_logs_since(&exec_id, query.since)
         .await
         .ok_or_else(|| {
             ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
@@ -145,9 +239,12 @@ pub async fn stream_normalized_logs_ws(
 
 async fn handle_normalized_logs_ws(
     socket: WebSocket,
-    stream: impl futures_util::Stream<Item = anyhow::Result<LogMsg>> + Unpin + Send + 'static,
+    stream: impl futures_util::Stream<Item = anyhow::Result<(Option<u64>, LogMsg)>>
+    + Unpin
+    + Send
+    + 'static,
 ) -> anyhow::Result<()> {
-    let mut stream = stream.map_ok(|msg| msg.to_ws_message_unchecked());
+    let mut stream = stream.map_ok(|(seq, msg)| msg.to_sequenced_ws_message(seq));
     let (mut sender, mut receiver) = socket.split();
     tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
     while let Some(item) = stream.next().await {
@@ -157,6 +254,15 @@ async fn handle_normalized_logs_ws(
                     break;
                 }
             }
+            // Closing without `finished` makes the client resume after its
+            // last sequence number
+            Err(e)
+                if e.downcast_ref::<std::io::Error>()
+                    .is_some_and(StreamLagged::is) =>
+            {
+                tracing::debug!("normalized logs client fell behind: {}", e);
+                break;
+            }
             Err(e) => {
                 tracing::error!("stream error: {}", e);
                 break;
@@ -248,8 +354,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/approvals", get(get_execution_process_approvals))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .route("/normalized-entries", get(get_normalized_entries))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
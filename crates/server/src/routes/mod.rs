@@ -9,6 +9,7 @@ use crate::{DeploymentImpl, middleware};
 pub mod approvals;
 pub mod config;
 pub mod containers;
+pub mod database;
 pub mod filesystem;
 pub mod github;
 pub mod events;
@@ -21,11 +22,14 @@ pub mod organizations;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod tasks;
 pub mod terminal;
+pub mod usage;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
@@ -36,6 +40,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(projects::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
+        .merge(task_schedules::router())
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
@@ -48,6 +53,9 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
+        .merge(usage::router())
+        .merge(search::router())
+        .merge(database::router())
         .nest("/images", images::routes())
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
@@ -13,13 +13,16 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    approval_policy::{ApprovalPolicy, ProjectApprovalPolicy},
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use services::services::{file_search::SearchQuery, project::ProjectServiceError};
+use services::services::{
+    approvals::policy, file_search::SearchQuery, project::ProjectServiceError,
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -131,6 +134,12 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(Some(max)) = payload.max_concurrent_coding_agents
+        && max < 1
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     match deployment
         .project()
         .update_project(&deployment.db().pool, &existing_project, payload)
@@ -176,6 +185,45 @@ pub async fn delete_project(
     }
 }
 
+pub async fn get_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ApprovalPolicy>>, ApiError> {
+    let policy =
+        ProjectApprovalPolicy::policy_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn update_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ApprovalPolicy>,
+) -> Result<ResponseJson<ApiResponse<ApprovalPolicy>>, ApiError> {
+    policy::validate(&payload).map_err(ApiError::BadRequest)?;
+
+    let row = ProjectApprovalPolicy::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_approval_policy_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "rule_count": payload.rules.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(row.policy.0)))
+}
+
+pub async fn delete_project_approval_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectApprovalPolicy::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 #[derive(serde::Deserialize)]
 pub struct OpenEditorRequest {
     pub editor_type: Option<String>,
@@ -441,6 +489,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route(
+            "/approval-policy",
+            get(get_project_approval_policy)
+                .put(update_project_approval_policy)
+                .delete(delete_project_approval_policy),
+        )
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
@@ -255,7 +255,13 @@ pub async fn list_open_prs(
         None => deployment.git().get_default_remote(&repo.path)?,
     };
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let self_hosted_git_hosts = deployment
+        .config()
+        .read()
+        .await
+        .self_hosted_git_hosts
+        .clone();
+    let git_host = match GitHostService::from_url(&remote.url, &self_hosted_git_hosts) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
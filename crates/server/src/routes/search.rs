@@ -0,0 +1,48 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::search::{SearchDocument, SearchHit};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::search::match_expression;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// Full-text search over tasks, agent prompts and summaries, assistant
+/// messages and commands
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, ApiError> {
+    let Some(expression) = match_expression(&query.q) else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let hits =
+        SearchDocument::search(&deployment.db().pool, &expression, query.project_id, limit).await?;
+
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
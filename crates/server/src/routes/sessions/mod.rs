@@ -23,14 +23,18 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::container::{ContainerService, ExecutionStart};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
-    routes::task_attempts::util::restore_worktrees_to_process,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_session_middleware,
+    routes::task_attempts::{
+        transcript::export_session_transcript, util::restore_worktrees_to_process,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -98,7 +102,7 @@ pub async fn follow_up(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateFollowUpAttempt>,
-) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<ExecutionStart>>, ApiError> {
     let pool = &deployment.db().pool;
 
     // Load workspace from session
@@ -194,6 +198,7 @@ pub async fn follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            trigger: None,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(
@@ -207,7 +212,7 @@ pub async fn follow_up(
 
     let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
 
-    let execution_process = deployment
+    let start = deployment
         .container()
         .start_execution(
             &workspace,
@@ -217,7 +222,7 @@ pub async fn follow_up(
         )
         .await?;
 
-    // Clear the draft follow-up scratch on successful spawn
+    // Clear the draft follow-up scratch once spawned or queued
     // This ensures the scratch is wiped even if the user navigates away quickly
     if let Err(e) = Scratch::delete(pool, session.id, &ScratchType::DraftFollowUp).await {
         // Log but don't fail the request - scratch deletion is best-effort
@@ -228,7 +233,7 @@ pub async fn follow_up(
         );
     }
 
-    Ok(ResponseJson(ApiResponse::success(execution_process)))
+    Ok(ResponseJson(ApiResponse::success(start)))
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
@@ -236,6 +241,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .route("/transcript", get(export_session_transcript))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
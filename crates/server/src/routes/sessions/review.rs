@@ -17,7 +17,7 @@ use executors::{
     profile::ExecutorProfileId,
 };
 use serde::{Deserialize, Serialize};
-use services::services::container::ContainerService;
+use services::services::container::{ContainerService, ExecutionStart};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
@@ -43,7 +43,7 @@ pub async fn start_review(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<StartReviewRequest>,
-) -> Result<ResponseJson<ApiResponse<ExecutionProcess, ReviewError>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<ExecutionStart, ReviewError>>, ApiError> {
     let pool = &deployment.db().pool;
 
     let workspace = Workspace::find_by_id(pool, session.workspace_id)
@@ -111,7 +111,7 @@ pub async fn start_review(
         None,
     );
 
-    let execution_process = deployment
+    let start = deployment
         .container()
         .start_execution(
             &workspace,
@@ -134,5 +134,5 @@ pub async fn start_review(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(execution_process)))
+    Ok(ResponseJson(ApiResponse::success(start)))
 }
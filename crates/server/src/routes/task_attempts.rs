@@ -1,13 +1,17 @@
 pub mod codex_setup;
+pub mod compare;
 pub mod cursor_setup;
+pub mod fork;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod review_comments;
+pub mod transcript;
 pub mod util;
 pub mod workspace_summary;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
@@ -25,7 +29,7 @@ use axum::{
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
-    merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge::{Merge, MergeStatus, MergeStrategy, PrMerge, PullRequestInfo},
     project::SearchResult,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
@@ -45,9 +49,9 @@ use executors::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService,
+    container::{ContainerService, ParentBranchChange},
     file_search::SearchQuery,
-    git::{ConflictOp, GitCliError, GitServiceError},
+    git::{ConflictOp, GitCliError, GitServiceError, RevertSelection},
     workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
@@ -72,6 +76,23 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RevertChangesRequest {
+    pub repo_id: Uuid,
+    pub files: Vec<RevertSelection>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RevertChangesResponse {
+    /// The "user revert" commit, absent when nothing needed reverting
+    pub commit_sha: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -157,6 +178,10 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Base the branch on the parent workspace's branch, so the PR targets it
+    #[serde(default)]
+    #[ts(optional)]
+    pub stack_on_parent: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -178,65 +203,135 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let workspace = start_task_attempt(
+        &deployment,
+        &task,
+        payload.executor_profile_id,
+        &payload.repos,
+        payload.stack_on_parent.unwrap_or(false),
+    )
+    .await?;
 
+    Ok(ResponseJson(ApiResponse::success(workspace)))
+}
+
+#[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
+pub struct CreateParallelTaskAttemptsBody {
+    pub task_id: Uuid,
+    /// One attempt is started per profile, all on the same repos and branches
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<WorkspaceRepoInput>,
+    /// Base the branches on the parent workspace's branch, so the PRs target it
+    #[serde(default)]
+    #[ts(optional)]
+    pub stack_on_parent: Option<bool>,
+}
+
+/// Start one attempt of a task per executor profile so the results can be compared
+#[axum::debug_handler]
+pub async fn create_parallel_task_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateParallelTaskAttemptsBody>,
+) -> Result<ResponseJson<ApiResponse<Vec<Workspace>>>, ApiError> {
     if payload.repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
         ));
     }
+    if payload.executor_profile_ids.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one executor profile is required".to_string(),
+        ));
+    }
 
-    let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
+    let stack_on_parent = payload.stack_on_parent.unwrap_or(false);
+    let mut workspaces = Vec::with_capacity(payload.executor_profile_ids.len());
+    for executor_profile_id in payload.executor_profile_ids {
+        match start_task_attempt(
+            &deployment,
+            &task,
+            executor_profile_id,
+            &payload.repos,
+            stack_on_parent,
+        )
+        .await
+        {
+            Ok(workspace) => workspaces.push(workspace),
+            Err(err) => {
+                // Don't leave part of the set behind
+                for workspace in &workspaces {
+                    if let Err(e) = deployment.container().discard_task_attempt(workspace).await {
+                        tracing::error!(
+                            "Failed to discard task attempt {} after a failed parallel start: {}",
+                            workspace.id,
+                            e
+                        );
+                    }
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(workspaces)))
+}
+
+async fn start_task_attempt(
+    deployment: &DeploymentImpl,
+    task: &Task,
+    executor_profile_id: ExecutorProfileId,
+    repos: &[WorkspaceRepoInput],
+    stack_on_parent: bool,
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // A stacked attempt targets the parent's branch in every repo the parent has
+    let stack_base = if stack_on_parent {
+        let parent_workspace_id = task.parent_workspace_id.ok_or_else(|| {
+            ApiError::BadRequest("Only tasks created from a workspace can be stacked".to_string())
+        })?;
+        let parent = Workspace::find_by_id(pool, parent_workspace_id)
             .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
+            .ok_or(SqlxError::RowNotFound)?;
+        let parent_repo_ids: HashSet<Uuid> = WorkspaceRepo::find_by_workspace_id(pool, parent.id)
+            .await?
+            .into_iter()
+            .map(|wr| wr.repo_id)
+            .collect();
+        Some((parent.branch, parent_repo_ids))
     } else {
         None
     };
 
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name.clone(),
-            agent_working_dir,
-        },
-        attempt_id,
-        payload.task_id,
-    )
-    .await?;
-
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
+    let workspace_repos: Vec<CreateWorkspaceRepo> = repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
+            target_branch: match &stack_base {
+                Some((branch, repo_ids)) if repo_ids.contains(&r.repo_id) => branch.clone(),
+                _ => r.target_branch.clone(),
+            },
         })
         .collect();
 
-    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
-    if let Err(err) = deployment
+    let workspace = deployment
         .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
-        .await
-    {
-        tracing::error!("Failed to start task attempt: {}", err);
-    }
+        .start_task_attempt(task, executor_profile_id.clone(), &workspace_repos)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -246,14 +341,15 @@ pub async fn create_task_attempt(
                 "variant": &executor_profile_id.variant,
                 "executor": &executor_profile_id.executor,
                 "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
+                "repository_count": repos.len(),
+                "stacked": stack_base.is_some(),
             }),
         )
         .await;
 
     tracing::info!("Created attempt for task {}", task.id);
 
-    Ok(ResponseJson(ApiResponse::success(workspace)))
+    Ok(workspace)
 }
 
 #[axum::debug_handler]
@@ -410,6 +506,35 @@ async fn handle_workspaces_ws(
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct MergeTaskAttemptRequest {
     pub repo_id: Uuid,
+    /// Overrides the repository's configured merge strategy
+    #[serde(default)]
+    #[ts(optional)]
+    pub strategy: Option<MergeStrategy>,
+    /// Overrides the message rendered from the repository's commit template
+    #[serde(default)]
+    #[ts(optional)]
+    pub commit_message: Option<String>,
+}
+
+/// Commit message used when a repository has no template of its own
+const DEFAULT_MERGE_COMMIT_TEMPLATE: &str = "{title} (tob-vibe-kanban {short_id})\n\n{description}";
+
+/// Fill in a merge commit template, dropping whatever trails off empty
+/// (e.g. a missing description)
+fn render_merge_commit_message(template: &str, task: &Task, branch: &str) -> String {
+    let task_id = task.id.to_string();
+    let short_id = task_id.split('-').next().unwrap_or(&task_id);
+    template
+        .replace("{title}", &task.title)
+        .replace(
+            "{description}",
+            task.description.as_deref().unwrap_or("").trim(),
+        )
+        .replace("{task_id}", &task_id)
+        .replace("{short_id}", short_id)
+        .replace("{branch}", branch)
+        .trim_end()
+        .to_string()
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -417,6 +542,29 @@ pub struct PushTaskAttemptRequest {
     pub repo_id: Uuid,
 }
 
+/// Rebase the workspaces stacked on `parent` after the response went out;
+/// rebasing and force pushing a whole stack can take a while
+fn spawn_restack_children(
+    deployment: &DeploymentImpl,
+    parent: Workspace,
+    repo: Repo,
+    change: ParentBranchChange,
+) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        let git_hosts = deployment
+            .config()
+            .read()
+            .await
+            .self_hosted_git_hosts
+            .clone();
+        deployment
+            .container()
+            .restack_children(&parent, &repo, change, &git_hosts)
+            .await;
+    });
+}
+
 #[axum::debug_handler]
 pub async fn merge_task_attempt(
     Extension(workspace): Extension<Workspace>,
@@ -450,31 +598,35 @@ pub async fn merge_task_attempt(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     let task = workspace
         .parent_task(pool)
         .await?
         .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
-    let task_uuid_str = task.id.to_string();
-    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
-
-    let mut commit_message = format!("{} (tob-vibe-kanban {})", task.title, first_uuid_section);
-
-    // Add description on next line if it exists
-    if let Some(description) = &task.description
-        && !description.trim().is_empty()
-    {
-        commit_message.push_str("\n\n");
-        commit_message.push_str(description);
-    }
+    let strategy = request.strategy.unwrap_or(repo.merge_strategy);
+    let commit_message = match request.commit_message {
+        Some(message) if !message.trim().is_empty() => message,
+        _ => render_merge_commit_message(
+            repo.merge_commit_template
+                .as_deref()
+                .unwrap_or(DEFAULT_MERGE_COMMIT_TEMPLATE),
+            &task,
+            &workspace.branch,
+        ),
+    };
 
+    // Squash merges move the task branch, so remember where it was for restacking
+    let old_head = deployment
+        .git()
+        .get_branch_oid(&repo.path, &workspace.branch)?;
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
+        strategy,
     )?;
 
     Merge::create_direct(
@@ -483,9 +635,20 @@ pub async fn merge_task_attempt(
         workspace_repo.repo_id,
         &workspace_repo.target_branch,
         &merge_commit_id,
+        strategy,
     )
     .await?;
     Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    spawn_restack_children(
+        &deployment,
+        workspace.clone(),
+        repo.clone(),
+        ParentBranchChange::Merged { old_head },
+    );
+    deployment
+        .container()
+        .start_unblocked_dependents(task.id)
+        .await;
     if !workspace.pinned {
         Workspace::set_archived(pool, workspace.id, true).await?;
     }
@@ -521,6 +684,7 @@ pub async fn merge_task_attempt(
             serde_json::json!({
                 "task_id": task.id.to_string(),
                 "workspace_id": workspace.id.to_string(),
+                "strategy": strategy,
             }),
         )
         .await;
@@ -1140,6 +1304,10 @@ pub async fn rebase_task_attempt(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    // Workspaces stacked on this one are moved along from the old head
+    let old_head = deployment
+        .git()
+        .get_branch_oid(&repo.path, &workspace.branch)?;
     let result = deployment.git().rebase_branch(
         &repo.path,
         &worktree_path,
@@ -1173,6 +1341,13 @@ pub async fn rebase_task_attempt(
         };
     }
 
+    spawn_restack_children(
+        &deployment,
+        workspace.clone(),
+        repo.clone(),
+        ParentBranchChange::Rebased { old_head },
+    );
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1210,6 +1385,129 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    if deployment
+        .git()
+        .detect_conflict_op(&worktree_path)?
+        .is_none()
+        || deployment
+            .git()
+            .get_conflicted_files(&worktree_path)?
+            .is_empty()
+    {
+        return Err(ApiError::BadRequest(
+            "There are no conflicts to resolve in this repository".to_string(),
+        ));
+    }
+
+    // The operation is continued once the agent's turn is done
+    if !deployment
+        .container()
+        .resolve_conflicts_with_agent(&workspace, &repo)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Could not hand the conflicts to the agent: the workspace has no agent session yet or a message is already queued".to_string(),
+        ));
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflicts_resolve_with_agent",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": payload.repo_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Revert files or hunks of the workspace diff against the base commit and
+/// commit the result, so the next agent turn starts from what was kept
+#[axum::debug_handler]
+pub async fn revert_task_attempt_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertChangesRequest>,
+) -> Result<ResponseJson<ApiResponse<RevertChangesResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if payload.files.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Select at least one file to revert".to_string(),
+        ));
+    }
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Cannot revert changes while the agent is running".to_string(),
+        ));
+    }
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    let base_commit = deployment.git().get_base_commit(
+        &repo.path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )?;
+
+    let paths: Vec<&str> = payload.files.iter().map(|f| f.path.as_str()).collect();
+    let message = format!("User revert: {}", paths.join(", "));
+    let commit_sha = deployment.git().revert_worktree_changes(
+        &worktree_path,
+        &base_commit,
+        &payload.files,
+        &message,
+    )?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_changes_reverted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": payload.repo_id.to_string(),
+                "files": payload.files.len(),
+                "hunk_level": payload.files.iter().any(|f| f.hunks.is_some()),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RevertChangesResponse {
+        commit_sha,
+    })))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -1302,7 +1600,7 @@ pub async fn start_dev_server(
 
         let execution_process = deployment
             .container()
-            .start_execution(
+            .start_execution_now(
                 &workspace,
                 &session,
                 &executor_action,
@@ -1439,7 +1737,7 @@ pub async fn run_setup_script(
 
     let execution_process = deployment
         .container()
-        .start_execution(
+        .start_execution_now(
             &workspace,
             &session,
             &executor_action,
@@ -1518,7 +1816,7 @@ pub async fn run_cleanup_script(
 
     let execution_process = deployment
         .container()
-        .start_execution(
+        .start_execution_now(
             &workspace,
             &session,
             &executor_action,
@@ -1773,6 +2071,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
+        .route("/revert", post(revert_task_attempt_changes))
         .route("/pr", post(pr::create_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
@@ -1785,6 +2085,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/search", get(search_workspace_files))
         .route("/first-message", get(get_first_user_message))
         .route("/mark-seen", put(mark_seen))
+        .route("/pick-winner", post(compare::pick_winner))
+        .route("/fork", post(fork::fork_task_attempt))
+        .route("/review/send", post(review_comments::send_review_comments))
+        .route(
+            "/transcript",
+            get(transcript::export_task_attempt_transcript),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -1792,6 +2099,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_task_attempts).post(create_task_attempt))
+        .route("/parallel", post(create_parallel_task_attempts))
+        .route("/compare", get(compare::compare_task_attempts))
+        .route("/compare/diff", get(compare::get_cross_attempt_diff))
         .route("/from-pr", post(pr::create_workspace_from_pr))
         .route("/count", get(get_workspace_count))
         .route("/stream/ws", get(stream_workspaces_ws))
@@ -65,7 +65,7 @@ pub async fn run_codex_setup(
 
     let execution_process = deployment
         .container()
-        .start_execution(
+        .start_execution_now(
             workspace,
             &session,
             &executor_action,
@@ -0,0 +1,218 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    git::DiffTarget,
+    usage::{UsageGroupBy, UsageQuery, UsageSummary, summarize_usage},
+};
+use ts_rs::TS;
+use utils::{diff::Diff, response::ApiResponse};
+use uuid::Uuid;
+
+use super::workspace_summary::{DiffStats, compute_workspace_diff_stats};
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct CompareTaskAttemptsQuery {
+    pub task_id: Uuid,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+/// How one attempt of a task did, for side-by-side comparison
+#[derive(Debug, Serialize, TS)]
+pub struct TaskAttemptComparison {
+    pub workspace: Workspace,
+    /// Profile of the latest coding agent run
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Changes against the target branches, `None` without a worktree
+    pub diff_stats: Option<DiffStats>,
+    /// Status of the latest verification script run
+    pub verification_status: Option<ExecutionProcessStatus>,
+    /// Seconds from the first process start to the last completion, `None`
+    /// while a process is still running
+    pub duration_seconds: Option<i64>,
+    /// `None` when no turn recorded token usage
+    pub usage: Option<UsageSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CrossAttemptDiffQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PickWinnerResponse {
+    pub archived_workspace_ids: Vec<Uuid>,
+}
+
+pub async fn compare_task_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CompareTaskAttemptsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttemptComparison>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let prices = deployment.config().read().await.model_prices.clone();
+
+    let workspaces: Vec<Workspace> = Workspace::fetch_all(pool, Some(query.task_id))
+        .await?
+        .into_iter()
+        .filter(|ws| query.include_archived || !ws.archived)
+        .collect();
+
+    let mut comparisons = Vec::with_capacity(workspaces.len());
+    for workspace in workspaces {
+        let executor_profile_id =
+            match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+                Some(session) => {
+                    ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?
+                }
+                None => None,
+            };
+
+        let diff_stats = if workspace.container_ref.is_some() {
+            compute_workspace_diff_stats(&deployment, &workspace)
+                .await
+                .ok()
+        } else {
+            None
+        };
+
+        let verification_status = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::VerificationScript,
+        )
+        .await?
+        .map(|process| process.status);
+
+        let span = ExecutionProcess::find_run_span_for_workspace(pool, workspace.id).await?;
+        let duration_seconds = match (span.started_at, span.completed_at) {
+            (Some(started_at), Some(completed_at)) if span.running == 0 => {
+                Some((completed_at - started_at).num_seconds())
+            }
+            _ => None,
+        };
+
+        let usage = summarize_usage(
+            pool,
+            &UsageQuery {
+                group_by: UsageGroupBy::Workspace,
+                workspace_id: Some(workspace.id),
+                ..Default::default()
+            },
+            &prices,
+        )
+        .await?
+        .into_iter()
+        .next();
+
+        comparisons.push(TaskAttemptComparison {
+            workspace,
+            executor_profile_id,
+            diff_stats,
+            verification_status,
+            duration_seconds,
+            usage,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(comparisons)))
+}
+
+/// Diff the branch of attempt `to` against the branch of attempt `from` in
+/// every repo the two attempts share.
+pub async fn get_cross_attempt_diff(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CrossAttemptDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let from = Workspace::find_by_id(pool, query.from)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace not found".to_string()))?;
+    let to = Workspace::find_by_id(pool, query.to)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace not found".to_string()))?;
+    if from.task_id != to.task_id {
+        return Err(ApiError::BadRequest(
+            "Only attempts of the same task can be compared".to_string(),
+        ));
+    }
+
+    let from_repos = WorkspaceRepo::find_repos_for_workspace(pool, from.id).await?;
+    let to_repo_ids: Vec<Uuid> = WorkspaceRepo::find_repos_for_workspace(pool, to.id)
+        .await?
+        .into_iter()
+        .map(|repo| repo.id)
+        .collect();
+
+    let mut all_diffs = Vec::new();
+    for repo in from_repos
+        .into_iter()
+        .filter(|repo| to_repo_ids.contains(&repo.id))
+    {
+        let git = deployment.git().clone();
+        let from_branch = from.branch.clone();
+        let to_branch = to.branch.clone();
+        let repo_path = repo.path.clone();
+        let diffs = tokio::task::spawn_blocking(move || {
+            git.get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo_path,
+                    branch_name: &to_branch,
+                    base_branch: &from_branch,
+                },
+                None,
+            )
+        })
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+        all_diffs.extend(diffs.into_iter().map(|mut diff| {
+            diff.repo_id = Some(repo.id);
+            diff
+        }));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(all_diffs)))
+}
+
+/// Keep this attempt and archive the task's other attempts that are not
+/// pinned, stopping anything they still have running.
+pub async fn pick_winner(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<PickWinnerResponse>>, ApiError> {
+    let archived_workspace_ids = deployment
+        .container()
+        .archive_other_attempts(&workspace)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_winner_picked",
+            serde_json::json!({
+                "task_id": workspace.task_id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "archived_count": archived_workspace_ids.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PickWinnerResponse {
+        archived_workspace_ids,
+    })))
+}
@@ -63,7 +63,7 @@ pub async fn run_cursor_setup(
 
     let execution_process = deployment
         .container()
-        .start_execution(
+        .start_execution_now(
             workspace,
             &session,
             &executor_action,
@@ -0,0 +1,354 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::Repo,
+    session::{CreateSession, Session},
+    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use executors::{
+    actions::ExecutorActionType, executors::BaseAgentCapability, profile::ExecutorConfigs,
+};
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, git::GitService};
+use sqlx::{Error as SqlxError, SqlitePool};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ForkTaskAttemptRequest {
+    /// Coding agent turn whose resulting commits the fork starts from
+    pub process_id: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ForkTaskAttemptResponse {
+    pub workspace: Workspace,
+    pub session: Session,
+    /// Whether the session continues the agent's conversation up to the turn
+    pub agent_session_forked: bool,
+}
+
+/// Start a new workspace of the same task on a new branch at the commits a
+/// past coding agent turn left behind, leaving the original workspace as is
+#[axum::debug_handler]
+pub async fn fork_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ForkTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<ForkTaskAttemptResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (process, source_session) = find_fork_source(pool, &workspace, payload.process_id).await?;
+
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?;
+    let workspace_repos = WorkspaceRepo::find_by_workspace_id(pool, workspace.id).await?;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+
+    // Every repo needs a checkpoint, so check them all before creating branches
+    let mut checkpoints = Vec::with_capacity(repos.len());
+    for repo in &repos {
+        let commit = repo_states
+            .iter()
+            .find(|state| state.repo_id == repo.id)
+            .and_then(|state| state.after_head_commit.clone())
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "The turn has no recorded commit for repository '{}'",
+                    repo.name
+                ))
+            })?;
+        checkpoints.push((repo, commit));
+    }
+
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let fork_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_workspace(&fork_id, &task.title)
+        .await;
+    create_fork_branches(deployment.git(), &checkpoints, &branch)?;
+
+    let fork = match create_fork_workspace(
+        &deployment,
+        &workspace,
+        &workspace_repos,
+        fork_id,
+        task.id,
+        &branch,
+    )
+    .await
+    {
+        Ok(fork) => fork,
+        Err(e) => {
+            // Nothing refers to the branches yet, so they go along with it
+            delete_fork_branches(deployment.git(), &checkpoints, &branch);
+            return Err(e);
+        }
+    };
+
+    let session = Session::create(
+        pool,
+        &CreateSession {
+            executor: source_session.executor.clone(),
+        },
+        Uuid::new_v4(),
+        fork.id,
+    )
+    .await?;
+
+    // Agents that can fork their sessions continue the conversation as it was
+    // after the turn
+    let executor_profile_id =
+        process
+            .executor_action()
+            .ok()
+            .and_then(|action| match action.typ() {
+                ExecutorActionType::CodingAgentInitialRequest(request) => {
+                    Some(request.executor_profile_id.clone())
+                }
+                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                    Some(request.executor_profile_id.clone())
+                }
+                _ => None,
+            });
+    let supports_fork = executor_profile_id.is_some_and(|profile| {
+        ExecutorConfigs::get_cached()
+            .get_coding_agent_or_default(&profile)
+            .capabilities()
+            .contains(&BaseAgentCapability::SessionFork)
+    });
+    let agent_session_id = if supports_fork {
+        CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+            .await?
+            .and_then(|turn| turn.agent_session_id)
+    } else {
+        None
+    };
+    if let Some(agent_session_id) = &agent_session_id {
+        Session::set_forked_agent_session_id(pool, session.id, agent_session_id).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_forked",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "fork_workspace_id": fork.id.to_string(),
+                "agent_session_forked": agent_session_id.is_some(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Forked workspace {} from process {} of workspace {}",
+        fork.id,
+        process.id,
+        workspace.id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        ForkTaskAttemptResponse {
+            workspace: fork,
+            session,
+            agent_session_forked: agent_session_id.is_some(),
+        },
+    )))
+}
+
+/// The coding agent turn of `workspace` to fork from, and its session
+async fn find_fork_source(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    process_id: Uuid,
+) -> Result<(ExecutionProcess, Session), ApiError> {
+    let process = ExecutionProcess::find_by_id(pool, process_id)
+        .await?
+        .ok_or(ExecutionProcessError::ExecutionProcessNotFound)?;
+    let source_session = Session::find_by_id(pool, process.session_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    if source_session.workspace_id != workspace.id {
+        return Err(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Process does not belong to this workspace".to_string(),
+        )));
+    }
+    if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+        return Err(ApiError::BadRequest(
+            "Only coding agent turns can be forked from".to_string(),
+        ));
+    }
+    Ok((process, source_session))
+}
+
+/// Create the fork's branch at its checkpoint in every repo, removing the
+/// ones already created if a repo fails
+fn create_fork_branches(
+    git: &GitService,
+    checkpoints: &[(&Repo, String)],
+    branch: &str,
+) -> Result<(), ApiError> {
+    for (created, (repo, commit)) in checkpoints.iter().enumerate() {
+        if let Err(e) = git.create_branch_at_commit(&repo.path, branch, commit) {
+            delete_fork_branches(git, &checkpoints[..created], branch);
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+fn delete_fork_branches(git: &GitService, checkpoints: &[(&Repo, String)], branch: &str) {
+    for (repo, _) in checkpoints {
+        if let Err(e) = git.delete_branch(&repo.path, branch) {
+            tracing::warn!(
+                "Failed to delete branch '{}' of failed fork in {}: {}",
+                branch,
+                repo.name,
+                e
+            );
+        }
+    }
+}
+
+/// The fork's workspace with its worktrees on `branch`; a partly created
+/// workspace is discarded again
+async fn create_fork_workspace(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    workspace_repos: &[WorkspaceRepo],
+    fork_id: Uuid,
+    task_id: Uuid,
+    branch: &str,
+) -> Result<Workspace, ApiError> {
+    let pool = &deployment.db().pool;
+    let fork = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: workspace.agent_working_dir.clone(),
+        },
+        fork_id,
+        task_id,
+    )
+    .await?;
+
+    let result: Result<Workspace, ApiError> = async {
+        let fork_repos: Vec<CreateWorkspaceRepo> = workspace_repos
+            .iter()
+            .map(|wr| CreateWorkspaceRepo {
+                repo_id: wr.repo_id,
+                target_branch: wr.target_branch.clone(),
+            })
+            .collect();
+        WorkspaceRepo::create_many(pool, fork.id, &fork_repos).await?;
+
+        // The branches exist already, so the worktrees just check them out
+        deployment
+            .container()
+            .ensure_container_exists(&fork)
+            .await?;
+        Ok(Workspace::find_by_id(pool, fork.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?)
+    }
+    .await;
+
+    if result.is_err()
+        && let Err(e) = deployment.container().discard_task_attempt(&fork).await
+    {
+        tracing::warn!("Failed to discard failed fork {}: {}", fork.id, e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, process::Command};
+
+    use axum::{http::StatusCode, response::IntoResponse};
+    use db::models::{
+        project::{CreateProject, Project},
+        task::{CreateTask, Task},
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo_path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    async fn workspace(pool: &SqlitePool) -> Workspace {
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: "app".to_string(),
+            repositories: Vec::new(),
+        };
+        Project::create(pool, &project, project_id).await.unwrap();
+        let task_id = Uuid::new_v4();
+        let task = CreateTask::from_title_description(project_id, "Fix".to_string(), None);
+        Task::create(pool, &task, task_id).await.unwrap();
+        let workspace = CreateWorkspace {
+            branch: "feature".to_string(),
+            agent_working_dir: None,
+        };
+        Workspace::create(pool, &workspace, Uuid::new_v4(), task_id)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_fork_from_unknown_process_is_not_found(pool: SqlitePool) {
+        let workspace = workspace(&pool).await;
+
+        let error = find_fork_source(&pool, &workspace, Uuid::new_v4())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn test_fork_branches_are_rolled_back_when_a_repo_fails(pool: SqlitePool) {
+        let root = TempDir::new().unwrap();
+        let mut repos = Vec::new();
+        for name in ["api", "web"] {
+            let repo_path = root.path().join(name);
+            fs::create_dir_all(&repo_path).unwrap();
+            git(&repo_path, &["init", "-b", "main"]);
+            fs::write(repo_path.join("file.txt"), name).unwrap();
+            git(&repo_path, &["add", "-A"]);
+            git(&repo_path, &["commit", "-m", name]);
+            repos.push(Repo::find_or_create(&pool, &repo_path, name).await.unwrap());
+        }
+        let api_head = git(&repos[0].path, &["rev-parse", "HEAD"])
+            .trim()
+            .to_string();
+        // The second repo has no such commit
+        let checkpoints = vec![(&repos[0], api_head.clone()), (&repos[1], api_head)];
+
+        let git_service = GitService::new();
+        let result = create_fork_branches(&git_service, &checkpoints, "fork");
+
+        assert!(result.is_err());
+        for repo in &repos {
+            assert_eq!(git(&repo.path, &["branch", "--list", "fork"]), "");
+        }
+    }
+}
@@ -58,7 +58,7 @@ pub async fn run_gh_cli_setup(
 
     let execution_process = deployment
         .container()
-        .start_execution(
+        .start_execution_now(
             workspace,
             &session,
             &executor_action,
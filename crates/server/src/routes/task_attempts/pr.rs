@@ -170,6 +170,7 @@ async fn trigger_pr_description_follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            trigger: None,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -279,20 +280,27 @@ pub async fn create_pr(
         }
     }
 
-    let git_host = match git_host::GitHostService::from_url(&target_remote.url) {
-        Ok(host) => host,
-        Err(GitHostError::UnsupportedProvider) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::UnsupportedProvider,
-            )));
-        }
-        Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::CliNotInstalled { provider },
-            )));
-        }
-        Err(e) => return Err(ApiError::GitHost(e)),
-    };
+    let self_hosted_git_hosts = deployment
+        .config()
+        .read()
+        .await
+        .self_hosted_git_hosts
+        .clone();
+    let git_host =
+        match git_host::GitHostService::from_url(&target_remote.url, &self_hosted_git_hosts) {
+            Ok(host) => host,
+            Err(GitHostError::UnsupportedProvider) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::UnsupportedProvider,
+                )));
+            }
+            Err(GitHostError::CliNotInstalled { provider }) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::CliNotInstalled { provider },
+                )));
+            }
+            Err(e) => return Err(ApiError::GitHost(e)),
+        };
 
     let provider = git_host.provider_kind();
 
@@ -416,7 +424,13 @@ pub async fn attach_existing_pr(
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote.url) {
+    let self_hosted_git_hosts = deployment
+        .config()
+        .read()
+        .await
+        .self_hosted_git_hosts
+        .clone();
+    let git_host = match git_host::GitHostService::from_url(&remote.url, &self_hosted_git_hosts) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -479,6 +493,10 @@ pub async fn attach_existing_pr(
         // If PR is merged, mark task as done and archive workspace
         if matches!(pr_info.status, MergeStatus::Merged) {
             Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            deployment
+                .container()
+                .start_unblocked_dependents(task.id)
+                .await;
             if !workspace.pinned {
                 Workspace::set_archived(pool, workspace.id, true).await?;
             }
@@ -533,7 +551,13 @@ pub async fn get_pr_comments(
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote.url) {
+    let self_hosted_git_hosts = deployment
+        .config()
+        .read()
+        .await
+        .self_hosted_git_hosts
+        .clone();
+    let git_host = match git_host::GitHostService::from_url(&remote.url, &self_hosted_git_hosts) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -744,7 +768,7 @@ pub async fn create_workspace_from_pr(
 
             if let Err(e) = deployment
                 .container()
-                .start_execution(
+                .start_execution_now(
                     &workspace,
                     &session,
                     &setup_action,
@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{
+    scratch::{
+        DiffReviewComment, ReviewCommentStatus, ReviewCommentsData, Scratch, ScratchPayload,
+        ScratchType,
+    },
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, TS)]
+pub struct SendReviewResponse {
+    /// Number of comments included in the prompt
+    pub sent: usize,
+}
+
+/// A pending comment with the code it points at, ready for the prompt
+struct ReviewExcerpt<'a> {
+    comment: &'a DiffReviewComment,
+    /// File path prefixed with its repo directory
+    display_path: String,
+    code: Option<String>,
+}
+
+fn review_prompt(excerpts: &[ReviewExcerpt]) -> String {
+    let mut prompt = String::from(
+        "Address the review comments below. Each one points at lines of a file in this workspace; \
+         change the code where it asks for changes and keep the rest as is.",
+    );
+    for excerpt in excerpts {
+        let comment = excerpt.comment;
+        let lines = if comment.start_line == comment.end_line {
+            format!("line {}", comment.start_line)
+        } else {
+            format!("lines {}-{}", comment.start_line, comment.end_line)
+        };
+        prompt.push_str(&format!("\n\n## `{}` {lines}", excerpt.display_path));
+        if let Some(code) = &excerpt.code {
+            let fence = if code.contains("```") { "````" } else { "```" };
+            prompt.push_str(&format!("\n\n{fence}\n{code}\n{fence}"));
+        }
+        prompt.push('\n');
+        for line in comment.text.trim().lines() {
+            prompt.push_str(&format!("\n> {line}"));
+        }
+    }
+    prompt
+}
+
+fn read_excerpt(file: &Path, start_line: u32, end_line: u32) -> Option<String> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let start = start_line.max(1) as usize - 1;
+    let len = end_line.saturating_sub(start_line) as usize + 1;
+    let lines: Vec<&str> = content.lines().skip(start).take(len).collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Turn the workspace's pending review comments into one follow-up prompt and
+/// start it as the agent's next turn. The comments are marked as sent, then
+/// resolved once an agent turn changes their lines.
+#[axum::debug_handler]
+pub async fn send_review_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<SendReviewResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let data = match Scratch::find_by_id(pool, workspace.id, &ScratchType::ReviewComments)
+        .await?
+        .map(|scratch| scratch.payload)
+    {
+        Some(ScratchPayload::ReviewComments(data)) => data,
+        _ => ReviewCommentsData { comments: vec![] },
+    };
+    if !data
+        .comments
+        .iter()
+        .any(|c| c.status == ReviewCommentStatus::Pending)
+    {
+        return Err(ApiError::BadRequest(
+            "There are no pending review comments to send".to_string(),
+        ));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let excerpts: Vec<ReviewExcerpt> = data
+        .comments
+        .iter()
+        .filter(|c| c.status == ReviewCommentStatus::Pending)
+        .map(|comment| {
+            let repo_name = repos
+                .iter()
+                .find(|repo| repo.id == comment.repo_id)
+                .map(|repo| repo.name.as_str());
+            let code = repo_name.and_then(|name| {
+                read_excerpt(
+                    &Path::new(&container_ref)
+                        .join(name)
+                        .join(&comment.file_path),
+                    comment.start_line,
+                    comment.end_line,
+                )
+            });
+            ReviewExcerpt {
+                comment,
+                display_path: match repo_name {
+                    Some(name) => format!("{name}/{}", comment.file_path),
+                    None => comment.file_path.clone(),
+                },
+                code,
+            }
+        })
+        .collect();
+    let sent = excerpts.len();
+    let sent_ids: Vec<_> = excerpts.iter().map(|excerpt| excerpt.comment.id).collect();
+    let prompt = review_prompt(&excerpts);
+
+    // Comments only count as sent once their turn has started
+    if !deployment
+        .container()
+        .start_follow_up(&workspace, prompt)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Could not send the review: the workspace has no agent session yet or the agent is still busy".to_string(),
+        ));
+    }
+
+    // Comments added or edited while the turn was starting stay as they are
+    Scratch::transition_review_comments(
+        pool,
+        workspace.id,
+        &sent_ids,
+        ReviewCommentStatus::Pending,
+        ReviewCommentStatus::Sent,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_review_sent",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "comments": sent,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(SendReviewResponse {
+        sent,
+    })))
+}
@@ -0,0 +1,96 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use db::models::{session::Session, workspace::Workspace};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::transcript::{Transcript, TranscriptFormat};
+use sqlx::Error as SqlxError;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+async fn transcript_response(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    sessions: &[Session],
+    format: TranscriptFormat,
+    file_stem: String,
+) -> Result<Response, ApiError> {
+    let task = workspace
+        .parent_task(&deployment.db().pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let transcript = Transcript::build(deployment.container(), &task, workspace, sessions).await?;
+    let body = transcript.render(format).map_err(std::io::Error::from)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "transcript_exported",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "format": format.extension(),
+                "sessions": sessions.len(),
+            }),
+        )
+        .await;
+
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        file_stem.replace(['/', '"', '\\'], "-"),
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Download the conversation of every session of the workspace
+pub async fn export_task_attempt_transcript(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let mut sessions = Session::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    // Sessions are listed by last use, transcripts read in creation order
+    sessions.sort_by_key(|session| session.created_at);
+    let file_stem = workspace.branch.clone();
+    transcript_response(&deployment, &workspace, &sessions, query.format, file_stem).await
+}
+
+/// Download the conversation of a single session
+pub async fn export_session_transcript(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let workspace = Workspace::find_by_id(&deployment.db().pool, session.workspace_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let file_stem = format!(
+        "{}-session-{}",
+        workspace.branch,
+        &session.id.to_string()[..8]
+    );
+    transcript_response(
+        &deployment,
+        &workspace,
+        std::slice::from_ref(&session),
+        query.format,
+        file_stem,
+    )
+    .await
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    task::Task,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, UpdateTaskSchedule},
+    workspace_repo::CreateWorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::task_schedule::CronSchedule;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    pub task_id: Option<Uuid>,
+}
+
+/// Validate the schedule and work out its first run, `None` when disabled
+fn next_run_at(
+    cron: &str,
+    enabled: bool,
+    repos: &[CreateWorkspaceRepo],
+) -> Result<Option<DateTime<Utc>>, ApiError> {
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    let schedule = CronSchedule::from_str(cron).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if !enabled {
+        return Ok(None);
+    }
+    schedule
+        .next_after(Utc::now())
+        .map(Some)
+        .ok_or_else(|| ApiError::BadRequest("Schedule never fires".to_string()))
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules = TaskSchedule::find_all(&deployment.db().pool, query.task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if Task::find_by_id(pool, payload.task_id).await?.is_none() {
+        return Err(ApiError::BadRequest("Task not found".to_string()));
+    }
+    let next_run_at = next_run_at(&payload.cron, payload.enabled, &payload.repos)?;
+
+    let schedule = TaskSchedule::create(pool, &payload, next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "task_id": schedule.task_id.to_string(),
+                "schedule_id": schedule.id.to_string(),
+                "create_new_task": schedule.create_new_task,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if TaskSchedule::find_by_id(pool, id).await?.is_none() {
+        return Err(ApiError::BadRequest("Task schedule not found".to_string()));
+    }
+    let next_run_at = next_run_at(&payload.cron, payload.enabled, &payload.repos)?;
+
+    let schedule = TaskSchedule::update(pool, id, &payload, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskSchedule::delete(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/task-schedules",
+            get(get_task_schedules).post(create_task_schedule),
+        )
+        .route(
+            "/task-schedules/{id}",
+            put(update_task_schedule).delete(delete_task_schedule),
+        )
+}
@@ -4,7 +4,7 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -15,7 +15,8 @@ use axum::{
 use db::models::{
     image::TaskImage,
     repo::{Repo, RepoError},
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{TaskAutoStart, TaskDependency},
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -23,7 +24,10 @@ use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
+use services::services::{
+    container::{ContainerService, ExecutionStart},
+    workspace_manager::WorkspaceManager,
+};
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -214,12 +218,18 @@ pub async fn create_task_and_start(
         .collect();
     WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
 
-    let is_attempt_running = deployment
+    let (is_attempt_running, is_attempt_queued) = match deployment
         .container()
         .start_workspace(&workspace, payload.executor_profile_id.clone())
         .await
-        .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+    {
+        Ok(ExecutionStart::Started { .. }) => (true, false),
+        Ok(ExecutionStart::Queued) => (false, true),
+        Err(err) => {
+            tracing::error!("Failed to start task attempt: {}", err);
+            (false, false)
+        }
+    };
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -240,6 +250,7 @@ pub async fn create_task_and_start(
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
         has_in_progress_attempt: is_attempt_running,
+        has_queued_attempt: is_attempt_queued,
         last_attempt_failed: false,
         executor: payload.executor_profile_id.executor.to_string(),
     })))
@@ -258,6 +269,7 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
+    let was_done = existing_task.status == TaskStatus::Done;
     let status = payload.status.unwrap_or(existing_task.status);
     let parent_workspace_id = payload
         .parent_workspace_id
@@ -279,6 +291,13 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    if !was_done && task.status == TaskStatus::Done {
+        deployment
+            .container()
+            .start_unblocked_dependents(task.id)
+            .await;
+    }
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -309,6 +328,9 @@ pub async fn delete_task(
         .filter_map(|attempt| attempt.container_ref.as_ref().map(PathBuf::from))
         .collect();
 
+    // Dependents lose this blocker with the task and may be ready to auto start
+    let dependents = TaskDependency::find_dependents(pool, task.id).await?;
+
     // Use a transaction to ensure atomicity: either all operations succeed or all are rolled back
     let mut tx = pool.begin().await?;
 
@@ -339,6 +361,13 @@ pub async fn delete_task(
         );
     }
 
+    for dependent in &dependents {
+        deployment
+            .container()
+            .start_if_unblocked(dependent.id)
+            .await;
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_deleted",
@@ -389,6 +418,127 @@ pub async fn delete_task(
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+#[derive(Debug, Serialize, TS)]
+pub struct TaskDependencies {
+    /// Tasks that must be done before this one can start
+    pub blocked_by: Vec<Task>,
+    /// Tasks waiting for this one
+    pub blocking: Vec<Task>,
+    pub auto_start: Option<TaskAutoStart>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AddTaskDependency {
+    pub blocked_by_task_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskAutoStart {
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<WorkspaceRepoInput>,
+}
+
+async fn load_task_dependencies(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+) -> Result<TaskDependencies, ApiError> {
+    let pool = &deployment.db().pool;
+    Ok(TaskDependencies {
+        blocked_by: TaskDependency::find_blockers(pool, task_id).await?,
+        blocking: TaskDependency::find_dependents(pool, task_id).await?,
+        auto_start: TaskAutoStart::find_by_task_id(pool, task_id).await?,
+    })
+}
+
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = load_task_dependencies(&deployment, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AddTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let blocker = Task::find_by_id(pool, payload.blocked_by_task_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Blocking task not found".to_string()))?;
+    if blocker.project_id != task.project_id {
+        return Err(ApiError::BadRequest(
+            "Tasks can only depend on tasks of the same project".to_string(),
+        ));
+    }
+    if TaskDependency::would_create_cycle(pool, task.id, blocker.id).await? {
+        return Err(ApiError::Conflict(
+            "This dependency would create a cycle".to_string(),
+        ));
+    }
+
+    TaskDependency::create(pool, task.id, blocker.id).await?;
+
+    let dependencies = load_task_dependencies(&deployment, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_task_id, blocked_by_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    if TaskDependency::delete(&deployment.db().pool, task.id, blocked_by_task_id).await? > 0 {
+        deployment.container().start_if_unblocked(task.id).await;
+    }
+
+    let dependencies = load_task_dependencies(&deployment, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+/// Start an attempt with the given profile and repos as soon as every blocker
+/// of the task is done. Fires once, right away if the blockers already are.
+pub async fn set_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskAutoStart>,
+) -> Result<ResponseJson<ApiResponse<TaskAutoStart>>, ApiError> {
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let repos: Vec<CreateWorkspaceRepo> = payload
+        .repos
+        .iter()
+        .map(|r| CreateWorkspaceRepo {
+            repo_id: r.repo_id,
+            target_branch: r.target_branch.clone(),
+        })
+        .collect();
+    let auto_start = TaskAutoStart::upsert(
+        &deployment.db().pool,
+        task.id,
+        &payload.executor_profile_id,
+        &repos,
+    )
+    .await?;
+    deployment.container().start_if_unblocked(task.id).await;
+
+    Ok(ResponseJson(ApiResponse::success(auto_start)))
+}
+
+pub async fn delete_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskAutoStart::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
@@ -396,6 +546,18 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{blocked_by_task_id}",
+            delete(remove_task_dependency),
+        )
+        .route(
+            "/auto-start",
+            put(set_task_auto_start).delete(delete_task_auto_start),
+        )
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
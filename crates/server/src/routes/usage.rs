@@ -0,0 +1,25 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use deployment::Deployment;
+use services::services::usage::{UsageQuery, UsageSummary, summarize_usage};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<UsageSummary>>>, ApiError> {
+    let prices = deployment.config().read().await.model_prices.clone();
+    let summaries = summarize_usage(&deployment.db().pool, &query, &prices).await?;
+
+    Ok(ResponseJson(ApiResponse::success(summaries)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage))
+}
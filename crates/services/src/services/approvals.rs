@@ -1,4 +1,5 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -8,6 +9,8 @@ use std::{
 
 use dashmap::DashMap;
 use db::models::{
+    approval::Approval,
+    approval_policy::ApprovalRuleAction,
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
 };
@@ -29,6 +32,8 @@ use utils::{
 };
 use uuid::Uuid;
 
+use self::policy::{APPROVAL_RULE_METADATA_KEY, ApprovalPolicyDecision, CompiledApprovalPolicy};
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
@@ -51,6 +56,7 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    pool: SqlitePool,
 }
 
 #[derive(Debug, Error)]
@@ -70,11 +76,12 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>, pool: SqlitePool) -> Self {
         Self {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
+            pool,
         }
     }
 
@@ -89,6 +96,11 @@ impl Approvals {
             .shared();
         let req_id = request.id.clone();
 
+        if let Err(e) = Approval::create(&self.pool, &request, &ApprovalStatus::Pending, None).await
+        {
+            tracing::warn!("Failed to persist approval request {}: {}", req_id, e);
+        }
+
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
             // Find the matching tool use entry by name and input
             let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
@@ -137,6 +149,68 @@ impl Approvals {
         Ok((request, waiter))
     }
 
+    /// Evaluate the approval policy for a tool call. The decision is recorded on the
+    /// matching tool use entry; returns the final status when a rule approves or
+    /// denies the call, or `None` when a human has to decide.
+    pub async fn apply_policy(
+        &self,
+        request: &ApprovalRequest,
+        policy: &CompiledApprovalPolicy,
+    ) -> Option<ApprovalStatus> {
+        let store = self.msg_store_by_id(&request.execution_process_id).await;
+        let matching_tool = store
+            .as_ref()
+            .and_then(|store| find_matching_tool_use(store.clone(), &request.tool_call_id));
+        let action_type = matching_tool
+            .as_ref()
+            .and_then(|(_, entry)| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+                _ => None,
+            });
+
+        let decision = policy.evaluate(&request.tool_name, action_type);
+        let status = match decision.action {
+            ApprovalRuleAction::Approve => Some(ApprovalStatus::Approved),
+            ApprovalRuleAction::Deny => Some(ApprovalStatus::Denied {
+                reason: Some(match &decision.rule {
+                    Some(rule) => format!("Denied by approval rule '{rule}'"),
+                    None => "Denied by approval policy".to_string(),
+                }),
+            }),
+            ApprovalRuleAction::Ask => None,
+        };
+
+        tracing::debug!(
+            "Approval policy decision for tool '{}' ({}): {:?}",
+            request.tool_name,
+            request.tool_call_id,
+            decision
+        );
+
+        // Only record decisions that came from a rule or skipped the human
+        if (decision.rule.is_some() || status.is_some())
+            && let (Some(store), Some((idx, entry))) = (store, matching_tool)
+        {
+            let entry = with_policy_decision(entry, &decision);
+            let entry = match &status {
+                Some(status) => ToolStatus::from_approval_status(status)
+                    .and_then(|tool_status| entry.with_tool_status(tool_status))
+                    .unwrap_or(entry),
+                None => entry,
+            };
+            store.push_patch(ConversationPatch::replace(idx, entry));
+        }
+
+        if let Some(status) = &status
+            && let Err(e) =
+                Approval::create(&self.pool, request, status, decision.rule.as_deref()).await
+        {
+            tracing::warn!("Failed to persist approval decision {}: {}", request.id, e);
+        }
+
+        status
+    }
+
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
@@ -148,6 +222,10 @@ impl Approvals {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
 
+            if let Err(e) = Approval::update_status(pool, id, &req.status).await {
+                tracing::warn!("Failed to persist approval response {}: {}", id, e);
+            }
+
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
                     ApprovalError::Custom(anyhow::anyhow!("Invalid approval status")),
@@ -203,6 +281,7 @@ impl Approvals {
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
+        let pool = self.pool.clone();
 
         let now = chrono::Utc::now();
         let to_wait = (timeout_at - now)
@@ -226,6 +305,10 @@ impl Approvals {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
 
+                if let Err(e) = Approval::update_status(&pool, &id, &status).await {
+                    tracing::warn!("Failed to persist approval timeout {}: {}", id, e);
+                }
+
                 let store = {
                     let map = msg_stores.read().await;
                     map.get(&pending_approval.execution_process_id).cloned()
@@ -263,12 +346,14 @@ impl Approvals {
 
     pub(crate) async fn cancel(&self, id: &str) {
         if let Some((_, pending_approval)) = self.pending.remove(id) {
-            self.completed.insert(
-                id.to_string(),
-                ApprovalStatus::Denied {
-                    reason: Some("Cancelled".to_string()),
-                },
-            );
+            let status = ApprovalStatus::Denied {
+                reason: Some("Cancelled".to_string()),
+            };
+            self.completed.insert(id.to_string(), status.clone());
+
+            if let Err(e) = Approval::update_status(&self.pool, id, &status).await {
+                tracing::warn!("Failed to persist approval cancellation {}: {}", id, e);
+            }
 
             if let Some(store) = self
                 .msg_store_by_id(&pending_approval.execution_process_id)
@@ -320,6 +405,22 @@ pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_i
     }
 }
 
+/// Attach the policy decision to the entry metadata, next to the tool call id
+fn with_policy_decision(
+    mut entry: NormalizedEntry,
+    decision: &ApprovalPolicyDecision,
+) -> NormalizedEntry {
+    let mut metadata = match entry.metadata.take() {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    if let Ok(value) = serde_json::to_value(decision) {
+        metadata.insert(APPROVAL_RULE_METADATA_KEY.to_string(), value);
+    }
+    entry.metadata = Some(serde_json::Value::Object(metadata));
+    entry
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
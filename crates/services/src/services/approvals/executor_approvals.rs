@@ -1,14 +1,20 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use db::{self, DBService, models::execution_process::ExecutionProcess};
+use db::{
+    self, DBService,
+    models::{approval_policy::ProjectApprovalPolicy, execution_process::ExecutionProcess},
+};
 use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
 use serde_json::Value;
 use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{Approvals, policy::CompiledApprovalPolicy},
+    notification::NotificationService,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
@@ -42,7 +48,20 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_call_id: &str,
         cancel: CancellationToken,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+        let context = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
+            .await
+            .ok();
+        let policy = match &context {
+            Some(ctx) => ProjectApprovalPolicy::policy_for_project(&self.db.pool, ctx.project.id)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(CompiledApprovalPolicy::compile)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to load approval policy, asking instead: {}", e);
+                    Default::default()
+                }),
+            None => Default::default(),
+        };
 
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
@@ -51,8 +70,15 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
                 tool_call_id: tool_call_id.to_string(),
             },
             self.execution_process_id,
+            policy.policy().timeout_seconds,
         );
 
+        if let Some(status) = self.approvals.apply_policy(&request, &policy).await {
+            return Ok(status);
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
         let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
@@ -61,10 +87,9 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
 
         let approval_id = request.id.clone();
 
-        let task_name = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
-            .await
+        let task_name = context
             .map(|ctx| ctx.task.title)
-            .unwrap_or_else(|_| "Unknown task".to_string());
+            .unwrap_or_else(|| "Unknown task".to_string());
 
         self.notification_service
             .notify(
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
@@ -7,7 +9,7 @@ pub use v7::{
     ThemeMode, UiLanguage,
 };
 
-use crate::services::config::versions::v7;
+use crate::services::{config::versions::v7, git_host::SelfHostedGitHosts};
 
 fn default_git_branch_prefix() -> String {
     "vk".to_string()
@@ -17,6 +19,10 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+fn default_verification_max_iterations() -> u32 {
+    3
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -24,6 +30,18 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+/// Price of a model in USD per million tokens, used to estimate the cost of
+/// coding agent turns from their recorded token usage.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -56,6 +74,26 @@ pub struct Config {
     pub commit_reminder: bool,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    /// Model prices keyed by model name. A key also matches models it is a
+    /// prefix of, the longest matching key wins.
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+    /// How many times a failing verification script is handed back to the
+    /// coding agent before the workspace is left for review
+    #[serde(default = "default_verification_max_iterations")]
+    pub verification_max_iterations: u32,
+    /// Coding agents allowed to run at once across all projects; further
+    /// starts wait in a queue. `None` for no limit
+    #[serde(default)]
+    pub max_concurrent_coding_agents: Option<u32>,
+    /// Hostnames of self-hosted git servers, e.g. a company GitLab instance
+    #[serde(default)]
+    pub self_hosted_git_hosts: SelfHostedGitHosts,
+    /// Days the raw logs of finished processes are kept. Normalized
+    /// conversations and turn summaries are kept regardless. `None` keeps
+    /// raw logs forever
+    #[serde(default)]
+    pub log_retention_days: Option<u32>,
 }
 
 impl Config {
@@ -85,6 +123,11 @@ impl Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            model_prices: HashMap::new(),
+            verification_max_iterations: default_verification_max_iterations(),
+            max_concurrent_coding_agents: None,
+            self_hosted_git_hosts: SelfHostedGitHosts::default(),
+            log_retention_days: None,
         }
     }
 
@@ -139,6 +182,11 @@ impl Default for Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            model_prices: HashMap::new(),
+            verification_max_iterations: default_verification_max_iterations(),
+            max_concurrent_coding_agents: None,
+            self_hosted_git_hosts: SelfHostedGitHosts::default(),
+            log_retention_days: None,
         }
     }
 }
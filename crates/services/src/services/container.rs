@@ -1,7 +1,8 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -9,20 +10,27 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        approval::Approval,
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_entries::ExecutionProcessNormalizedEntries,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        merge::{Merge, MergeStatus},
+        project::Project,
+        queued_execution::QueuedExecution,
         repo::Repo,
+        search::SearchDocument,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_dependency::{TaskAutoStart, TaskDependency},
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 #[cfg(feature = "qa-mode")]
@@ -36,29 +44,218 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    logs::{
+        NORMALIZER_VERSION, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        utils::{
+            ConversationPatch,
+            patch::{
+                conversation_snapshot, normalized_entries_from_patches, rebase_patch_onto_snapshot,
+            },
+        },
+    },
     profile::ExecutorProfileId,
 };
-use futures::{StreamExt, future, stream::BoxStream};
+use futures::{StreamExt, TryStreamExt, future, stream::BoxStream};
 use json_patch::Patch;
+use serde::Serialize;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+use ts_rs::TS;
 use utils::{
     log_msg::LogMsg,
-    msg_store::MsgStore,
+    msg_store::{MsgStore, SequencedLogMsg},
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    git::{ConflictOp, GitService, GitServiceError},
+    git_host::{GitHostProvider, GitHostService, SelfHostedGitHosts},
     notification::NotificationService,
+    search,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// How long a normalizer may take to let go of the logs it replays before its
+/// entries are stored as incomplete
+const NORMALIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Least time between two runs of the background re-normalization
+const RENORMALIZATION_INTERVAL: Duration = Duration::from_secs(1800);
+
+/// How often the entries of a running process are added to the search index
+const LOG_INDEX_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Processes normalized per query of the background re-normalization
+const RENORMALIZATION_BATCH_SIZE: i64 = 50;
+
+/// Processes whose logs are compressed per query of the log maintenance
+const LOG_MAINTENANCE_BATCH_SIZE: i64 = 50;
+
+/// Collect the patches of a normalized log stream, and whether the stream
+/// reached its end. A stream of replayed logs ends once the normalizer has
+/// read all of them and let go of its store; one that doesn't within
+/// `NORMALIZATION_TIMEOUT` is cut short.
+async fn collect_normalized_patches_until_end(
+    mut stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> (Vec<Patch>, bool) {
+    let mut patches = Vec::new();
+    let collect = async {
+        while let Some(msg) = stream.next().await {
+            match msg {
+                Ok(LogMsg::JsonPatch(patch)) => patches.push(patch),
+                Ok(LogMsg::Finished) => break,
+                _ => {}
+            }
+        }
+    };
+    let ended = tokio::time::timeout(NORMALIZATION_TIMEOUT, collect)
+        .await
+        .is_ok();
+    (patches, ended)
+}
+
+/// Collect the patches of a normalized log stream
+async fn collect_normalized_patches(
+    stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> Vec<Patch> {
+    collect_normalized_patches_until_end(stream).await.0
+}
+
+/// Collect the final entries of a normalized log stream, and whether the
+/// stream signalled its end
+async fn collect_normalized_entries(
+    stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> (Vec<(usize, NormalizedEntry)>, bool) {
+    let (patches, ended) = collect_normalized_patches_until_end(stream).await;
+    (normalized_entries_from_patches(&patches), ended)
+}
+
+/// Make the final entries of a process searchable, replacing what was indexed
+/// for it before
+async fn index_log_entries(
+    pool: &sqlx::SqlitePool,
+    execution_id: Uuid,
+    entries: &[(usize, NormalizedEntry)],
+) -> Result<(), SqlxError> {
+    let documents: Vec<_> = entries
+        .iter()
+        .filter_map(|(index, entry)| {
+            search::searchable_entry(entry).map(|(source, text)| (*index as i64, source, text))
+        })
+        .collect();
+    SearchDocument::replace_log_entries(pool, execution_id, &documents).await
+}
+
+/// Make the entries a running process produced since the last call
+/// searchable. Entries still being streamed are indexed again once they change.
+async fn index_pending_log_entries(
+    pool: &sqlx::SqlitePool,
+    execution_id: Uuid,
+    pending: &mut BTreeMap<usize, NormalizedEntry>,
+) {
+    if pending.is_empty() {
+        return;
+    }
+    let documents: Vec<_> = pending
+        .iter()
+        .filter_map(|(index, entry)| {
+            search::searchable_entry(entry).map(|(source, text)| (*index as i64, source, text))
+        })
+        .collect();
+    if let Err(e) = SearchDocument::upsert_log_entries(pool, execution_id, &documents).await {
+        tracing::error!(
+            "Failed to index log entries for execution {}: {}",
+            execution_id,
+            e
+        );
+    }
+    pending.clear();
+}
+
+/// The normalized patches of a running process's store, ending with
+/// `Finished` once the process's store goes away. A subscriber that falls
+/// behind gets a `StreamLagged` error as the last item instead, so the client
+/// reconnects and resumes after the last sequence number it got.
+fn live_normalized_stream(
+    stream: BoxStream<'static, Result<SequencedLogMsg, std::io::Error>>,
+) -> BoxStream<'static, Result<(Option<u64>, LogMsg), std::io::Error>> {
+    stream
+        .try_filter(|(_, msg)| future::ready(matches!(msg, LogMsg::JsonPatch(..))))
+        .map_ok(|(seq, msg)| (Some(seq), msg))
+        .chain(futures::stream::once(async {
+            Ok::<_, std::io::Error>((None, LogMsg::Finished))
+        }))
+        .scan(false, |failed, item| {
+            if *failed {
+                return future::ready(None);
+            }
+            *failed = item.is_err();
+            future::ready(Some(item))
+        })
+        .boxed()
+}
+
+/// What happened to the branch of a workspace that others are stacked on
+#[derive(Debug, Clone)]
+pub enum ParentBranchChange {
+    /// The branch was rebased; `old_head` is the commit it pointed at before
+    Rebased { old_head: String },
+    /// The branch was merged into its target branch; `old_head` is the commit
+    /// it pointed at before, since a squash merge moves it onto the result
+    Merged { old_head: String },
+    /// The PR of the branch was merged on the git host, so its target branch
+    /// is only up to date on the remote; `old_head` as for `Merged`
+    PrMerged { old_head: String },
+}
+
+/// Start of the prompts that hand git conflicts to the agent, used to continue
+/// the interrupted operation once it is done
+pub const CONFLICT_RESOLUTION_PREFIX: &str = "Resolve the git conflicts in this workspace.";
+
+fn conflict_resolution_prompt(
+    repo_name: &str,
+    op: &ConflictOp,
+    conflicted_files: &[String],
+    ours: Option<&str>,
+    theirs: Option<&str>,
+) -> String {
+    let subcommand = match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    };
+    let mut prompt = format!(
+        "{CONFLICT_RESOLUTION_PREFIX} A {subcommand} in `{repo_name}` stopped on conflicts. \
+         Edit the conflicted files so they keep the intent of both sides, remove every conflict marker \
+         and stage them with `git add`. Do not run `git {subcommand} --continue`, `--abort` or `git commit`; \
+         the {subcommand} is continued once you are done."
+    );
+
+    prompt.push_str("\n\n## Sides");
+    prompt.push_str(&format!(
+        "\n\n- Current (HEAD): {}",
+        ours.unwrap_or("(unknown)")
+    ));
+    prompt.push_str(&format!(
+        "\n- Being applied: {}",
+        theirs.unwrap_or("(unknown)")
+    ));
+
+    prompt.push_str("\n\n## Conflicted files\n");
+    for file in conflicted_files {
+        prompt.push_str(&format!("\n- `{file}`"));
+    }
+    prompt
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -81,16 +278,37 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("Task is blocked by tasks that are not done yet")]
+    TaskBlocked,
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
 
+/// How a requested execution went ahead
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionStart {
+    Started {
+        execution_process: ExecutionProcess,
+    },
+    /// Waiting for a free coding agent slot; it starts once one frees up
+    Queued,
+}
+
 #[async_trait]
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
 
     fn db(&self) -> &DBService;
 
+    /// Serializes coding agent slot checks so two starts cannot both take the
+    /// last free slot
+    fn coding_agent_slots(&self) -> &Mutex<()>;
+
+    /// Held while the background log jobs run, so they never overlap, along
+    /// with when stale logs were last re-normalized
+    fn log_maintenance(&self) -> &Mutex<Option<Instant>>;
+
     fn git(&self) -> &GitService;
 
     fn notification_service(&self) -> &NotificationService;
@@ -228,6 +446,9 @@ pub trait ContainerService {
             tracing::error!("Failed to update task status to InReview: {e}");
         }
 
+        // The finished process may have freed a coding agent slot
+        self.start_queued_executions().await;
+
         // Skip notification if process was intentionally killed by user
         if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
             return;
@@ -306,6 +527,20 @@ pub trait ContainerService {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
+            // Pending approvals can no longer be answered by this process
+            match Approval::mark_interrupted(&self.db().pool, process.id).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(
+                    "Marked {} pending approvals of orphaned execution process {} as interrupted",
+                    count,
+                    process.id
+                ),
+                Err(e) => tracing::error!(
+                    "Failed to mark approvals of orphaned execution process {} as interrupted: {}",
+                    process.id,
+                    e
+                ),
+            }
             // Update task status to InReview for coding agent and setup script failures
             if matches!(
                 process.run_reason,
@@ -444,6 +679,53 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    /// Chain the verification scripts of `repos`, continuing with `next_action`
+    /// once every script has passed. Returns `None` if no repo has one.
+    fn verification_actions_for_repos(
+        &self,
+        repos: &[Repo],
+        next_action: Option<ExecutorAction>,
+    ) -> Option<ExecutorAction> {
+        let repos_with_verification: Vec<_> = repos
+            .iter()
+            .filter(|r| r.verification_script.is_some())
+            .collect();
+
+        if repos_with_verification.is_empty() {
+            return None;
+        }
+
+        let mut iter = repos_with_verification.iter();
+        let first = iter.next()?;
+        let mut root_action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: first.verification_script.clone().unwrap(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::VerificationScript,
+                working_dir: Some(first.name.clone()),
+            }),
+            None,
+        );
+
+        for repo in iter {
+            root_action = root_action.append_action(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: repo.verification_script.clone().unwrap(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::VerificationScript,
+                    working_dir: Some(repo.name.clone()),
+                }),
+                None,
+            ));
+        }
+
+        if let Some(next_action) = next_action {
+            root_action = root_action.append_action(next_action);
+        }
+
+        Some(root_action)
+    }
+
     fn setup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -514,6 +796,16 @@ pub trait ContainerService {
     }
 
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
+        // drop queued starts so they don't launch after the stop
+        if let Err(e) = QueuedExecution::delete_by_workspace_id(&self.db().pool, workspace.id).await
+        {
+            tracing::debug!(
+                "Failed to remove queued executions for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
             Ok(s) => s,
@@ -592,6 +884,27 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// Global limit on concurrently running coding agents, `None` for no limit
+    async fn max_concurrent_coding_agents(&self) -> Option<u32>;
+
+    /// Send a prompt to the agent of the workspace's latest session, queued
+    /// behind the running process if there is one. Returns false when it can't
+    /// be sent yet, e.g. because another message is already queued.
+    async fn queue_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<bool, ContainerError>;
+
+    /// Start a prompt as a turn of the workspace's latest session right away.
+    /// Returns false when it can't start now, e.g. because a process is still
+    /// running or a message is queued.
+    async fn start_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<bool, ContainerError>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -660,7 +973,7 @@ pub trait ContainerService {
     ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
         // First try in-memory store (existing behavior)
         if let Some(store) = self.get_msg_store_by_id(id).await {
-            Some(
+            return Some(
                 store
                     .history_plus_stream() // BoxStream<Result<LogMsg, io::Error>>
                     .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
@@ -668,162 +981,471 @@ pub trait ContainerService {
                         Ok::<_, std::io::Error>(LogMsg::Finished)
                     }))
                     .boxed(),
-            )
-        } else {
-            // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            );
+        }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+        // Then entries stored when the process finished
+        match ExecutionProcessNormalizedEntries::find_by_execution_id(
+            &self.db().pool,
+            *id,
+            NORMALIZER_VERSION,
+        )
+        .await
+        {
+            Ok(Some(entries)) => {
+                // Stored indices have holes where raw output was, which a
+                // client's entries array can't, so entries are added in order
+                let patches: Vec<_> = entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (_, entry))| {
+                        Ok::<_, std::io::Error>(LogMsg::JsonPatch(
+                            ConversationPatch::add_normalized_entry(index, entry),
+                        ))
+                    })
+                    .chain(std::iter::once(Ok(LogMsg::Finished)))
+                    .collect();
+                return Some(futures::stream::iter(patches).boxed());
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch normalized entries for execution {}: {}",
+                    id,
+                    e
+                );
+            }
+        }
+
+        self.normalize_stored_logs(id, true).await
+    }
+
+    /// Normalized logs for a client that already applied the messages up to
+    /// sequence number `since`. Messages of a running process carry their
+    /// sequence number to resume after. When the client cannot be caught up
+    /// message by message, e.g. because the messages it missed were dropped
+    /// from memory, it first gets a snapshot replacing all of its entries.
+    async fn stream_normalized_logs_since(
+        &self,
+        id: &Uuid,
+        since: Option<u64>,
+    ) -> Option<BoxStream<'static, Result<(Option<u64>, LogMsg), std::io::Error>>> {
+        let Some(store) = self.get_msg_store_by_id(id).await else {
+            let stream = self.stream_normalized_logs(id).await?;
+            if since.is_none() {
+                return Some(stream.map_ok(|msg| (None, msg)).boxed());
+            }
+            // The process finished while the client was away
+            let (snapshot, _) = conversation_snapshot(&collect_normalized_patches(stream).await);
+            return Some(
+                futures::stream::iter([
+                    Ok::<_, std::io::Error>((None, LogMsg::JsonPatch(snapshot))),
+                    Ok((None, LogMsg::Finished)),
+                ])
+                .boxed(),
+            );
+        };
+
+        if let Some(stream) = since.and_then(|after| store.stream_since(after)) {
+            return Some(live_normalized_stream(stream));
+        }
+        if since.is_none() && !store.has_evicted() {
+            return Some(live_normalized_stream(
+                store.sequenced_history_plus_stream(),
+            ));
+        }
+
+        // Rebuild the conversation up to now from the stored raw logs, then
+        // continue with the live messages after it
+        let head = store.last_seq();
+        let live = store.stream_since(head)?;
+        let patches = match self.normalize_stored_logs(id, false).await {
+            Some(stream) => collect_normalized_patches(stream).await,
+            None => Vec::new(),
+        };
+        let (snapshot, snapshot_len) = conversation_snapshot(&patches);
+        let live = live_normalized_stream(live).map_ok(move |(seq, msg)| match msg {
+            LogMsg::JsonPatch(patch) => (
+                seq,
+                LogMsg::JsonPatch(rebase_patch_onto_snapshot(patch, snapshot_len)),
+            ),
+            msg => (seq, msg),
+        });
+        Some(
+            futures::stream::once(async move {
+                Ok::<_, std::io::Error>((Some(head), LogMsg::JsonPatch(snapshot)))
+            })
+            .chain(live)
+            .boxed(),
+        )
+    }
+
+    /// Replay the raw logs stored for a process through its executor's
+    /// normalizer. The worktree is recreated first when `recreate_worktree`
+    /// is set, as some normalizers resolve paths against it.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let log_records =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
             }
-            temp_store.push_finished();
+        }
+        temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_profile_id);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+            }
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
+        }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
+    }
+
+    /// Normalized entries of a process, with their entry indices. Logs are
+    /// only replayed through the normalizer when no entries were stored. A
+    /// running process gives the entries it has produced so far.
+    async fn normalized_entries(&self, id: &Uuid) -> Option<Vec<(usize, NormalizedEntry)>> {
+        match ExecutionProcessNormalizedEntries::find_by_execution_id(
+            &self.db().pool,
+            *id,
+            NORMALIZER_VERSION,
+        )
+        .await
+        {
+            Ok(Some(entries)) => return Some(entries),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch normalized entries for execution {}: {}",
+                    id,
+                    e
+                );
+            }
+        }
+        // The live stream of a running process doesn't end or go quiet, so
+        // take what its store holds right now, unless its first patches are
+        // only left in the database
+        if let Some(store) = self.get_msg_store_by_id(id).await
+            && !store.has_evicted()
+        {
+            let patches: Vec<Patch> = store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::JsonPatch(patch) => Some(patch),
+                    _ => None,
+                })
+                .collect();
+            return Some(normalized_entries_from_patches(&patches));
+        }
+        // Reading entries has no use for a worktree, so none is recreated
+        let stream = self.normalize_stored_logs(id, false).await?;
+        Some(collect_normalized_entries(stream).await.0)
+    }
+
+    /// Normalize a finished process's logs with the current normalizer and
+    /// store and index the entries, so it is served without replaying its raw
+    /// logs. Returns whether the stored entries are known to be complete.
+    async fn persist_normalized_entries(&self, id: &Uuid) -> Result<bool, ContainerError> {
+        // Logs of a process that is still in memory may not all be stored yet;
+        // its finalization persists the entries once they are
+        if self.get_msg_store_by_id(id).await.is_some() {
+            return Ok(false);
+        }
+        let pool = &self.db().pool;
+        match self.normalize_stored_logs(id, false).await {
+            Some(stream) => {
+                let (entries, ended) = collect_normalized_entries(stream).await;
+                // A normalizer that never let go of the logs was cut short.
+                // Its entries count as complete once an earlier run over the
+                // same logs agrees with them.
+                let complete = ended
+                    || ExecutionProcessNormalizedEntries::find_by_execution_id(
+                        pool,
+                        *id,
+                        NORMALIZER_VERSION,
+                    )
+                    .await?
+                    .is_some_and(|stored| {
+                        matches!(
+                            (serde_json::to_value(&stored), serde_json::to_value(&entries)),
+                            (Ok(stored), Ok(entries)) if stored == entries
+                        )
+                    });
+                ExecutionProcessNormalizedEntries::replace(
+                    pool,
+                    *id,
+                    NORMALIZER_VERSION,
+                    &entries,
+                    complete,
+                )
+                .await?;
+                index_log_entries(pool, *id, &entries).await?;
+                Ok(complete)
+            }
+            // Without raw logs, e.g. after retention dropped them, whatever
+            // was stored before is the best there is. Marking it keeps the
+            // process from being picked up again.
+            None => {
+                ExecutionProcessNormalizedEntries::set_normalizer_version(
+                    pool,
+                    *id,
+                    NORMALIZER_VERSION,
+                )
+                .await?;
+                if let Some(entries) = ExecutionProcessNormalizedEntries::find_by_execution_id(
+                    pool,
+                    *id,
+                    NORMALIZER_VERSION,
+                )
+                .await?
+                {
+                    index_log_entries(pool, *id, &entries).await?;
                 }
+                // Nothing left to normalize them again from
+                Ok(true)
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
+        }
+    }
+
+    /// Store the entries of stale logs, compress the logs of finished
+    /// processes, and drop raw logs of processes that completed more than
+    /// `retention_days` days ago. Holds the log maintenance lock throughout.
+    async fn maintain_logs(&self, retention_days: Option<u32>) -> Result<(), ContainerError> {
+        let mut last_renormalized = self.log_maintenance().lock().await;
+        // Entries left incomplete when their process finished are redone from
+        // the raw logs first, so retention can drop those as well
+        self.renormalize_stale_logs_throttled(&mut last_renormalized)
+            .await?;
+
+        let pool = &self.db().pool;
+        let mut compacted = 0;
+        loop {
+            let ids = ExecutionProcessLogs::find_uncompacted_execution_ids(
+                pool,
+                LOG_MAINTENANCE_BATCH_SIZE,
             )
+            .await?;
+            if ids.is_empty() {
+                break;
+            }
+            for id in ids {
+                ExecutionProcessLogs::compact(pool, id).await?;
+                compacted += 1;
+            }
         }
+        if compacted > 0 {
+            tracing::info!("Compressed logs of {} execution processes", compacted);
+        }
+
+        if let Some(days) = retention_days {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(days.into());
+            let deleted = ExecutionProcessLogs::delete_completed_before(pool, cutoff).await?;
+            if deleted > 0 {
+                tracing::info!(
+                    "Dropped {} raw log records of processes completed more than {} days ago",
+                    deleted,
+                    days
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Store entries for finished coding agent processes that were never
+    /// normalized, were normalized by an older normalizer version, or whose
+    /// entries are not known to be complete. This also backfills the search
+    /// index with the logs of processes that predate it.
+    async fn renormalize_stale_logs(&self) -> Result<(), ContainerError> {
+        let mut last_renormalized = self.log_maintenance().lock().await;
+        self.renormalize_stale_logs_throttled(&mut last_renormalized)
+            .await
+    }
+
+    /// Re-normalize stale logs unless that was done less than
+    /// `RENORMALIZATION_INTERVAL` ago. Callers hold the log maintenance lock.
+    async fn renormalize_stale_logs_throttled(
+        &self,
+        last_renormalized: &mut Option<Instant>,
+    ) -> Result<(), ContainerError> {
+        if last_renormalized.is_some_and(|at| at.elapsed() < RENORMALIZATION_INTERVAL) {
+            return Ok(());
+        }
+        let pool = &self.db().pool;
+        // Processes left stale by this run, which it doesn't retry
+        let mut left_stale = HashSet::new();
+        let mut renormalized = 0;
+        loop {
+            let ids = ExecutionProcessNormalizedEntries::find_stale_execution_ids(
+                pool,
+                NORMALIZER_VERSION,
+                RENORMALIZATION_BATCH_SIZE + left_stale.len() as i64,
+            )
+            .await?;
+            let pending: Vec<Uuid> = ids
+                .into_iter()
+                .filter(|id| !left_stale.contains(id))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            for id in pending {
+                match self.persist_normalized_entries(&id).await {
+                    Ok(complete) => {
+                        renormalized += 1;
+                        if !complete {
+                            left_stale.insert(id);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to normalize logs of execution {}: {}", id, e);
+                        left_stale.insert(id);
+                    }
+                }
+            }
+        }
+        if renormalized > 0 {
+            tracing::info!(
+                "Stored normalized entries of {} execution processes (normalizer version {})",
+                renormalized,
+                NORMALIZER_VERSION
+            );
+        }
+        *last_renormalized = Some(Instant::now());
+        Ok(())
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
@@ -840,8 +1462,23 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
-
-                while let Some(Ok(msg)) = stream.next().await {
+                // Normalized entries are indexed in batches while the process
+                // runs, so its output is searchable before it finishes
+                let mut pending_entries = BTreeMap::new();
+                let mut index_interval = tokio::time::interval(LOG_INDEX_INTERVAL);
+
+                loop {
+                    let msg = tokio::select! {
+                        msg = stream.next() => match msg {
+                            Some(Ok(msg)) => msg,
+                            _ => break,
+                        },
+                        _ = index_interval.tick() => {
+                            index_pending_log_entries(&db.pool, execution_id, &mut pending_entries)
+                                .await;
+                            continue;
+                        }
+                    };
                     match &msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
                             // Serialize this individual message as a JSONL line
@@ -890,21 +1527,351 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::JsonPatch(patch) => {
+                            pending_entries.extend(normalized_entries_from_patches([patch]));
+                        }
                         LogMsg::Finished => {
                             break;
                         }
-                        LogMsg::JsonPatch(_) | LogMsg::Ready => continue,
+                        LogMsg::Ready => continue,
                     }
                 }
+                index_pending_log_entries(&db.pool, execution_id, &mut pending_entries).await;
             }
         })
     }
 
+    /// Create a new attempt of the task on the given repos and start it.
+    /// Tasks with unfinished blockers are refused. Failing starts are logged;
+    /// the attempt is kept either way.
+    async fn start_task_attempt(
+        &self,
+        task: &Task,
+        executor_profile_id: ExecutorProfileId,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        if TaskDependency::count_unfinished_blockers(pool, task.id).await? > 0 {
+            return Err(ContainerError::TaskBlocked);
+        }
+
+        // Compute agent_working_dir based on repo count:
+        // - Single repo: use repo name as working dir (agent runs in repo directory)
+        // - Multiple repos: use None (agent runs in workspace root)
+        let agent_working_dir = if repos.len() == 1 {
+            let repo = Repo::find_by_id(pool, repos[0].repo_id)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            Some(repo.name)
+        } else {
+            None
+        };
+
+        let attempt_id = Uuid::new_v4();
+        let git_branch_name = self
+            .git_branch_from_workspace(&attempt_id, &task.title)
+            .await;
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: git_branch_name,
+                agent_working_dir,
+            },
+            attempt_id,
+            task.id,
+        )
+        .await?;
+
+        WorkspaceRepo::create_many(pool, workspace.id, repos).await?;
+        match self.start_workspace(&workspace, executor_profile_id).await {
+            Ok(ExecutionStart::Started { .. }) => {}
+            Ok(ExecutionStart::Queued) => {
+                tracing::info!(
+                    "Task attempt {} queued for a coding agent slot",
+                    workspace.id
+                );
+            }
+            Err(err) => tracing::error!("Failed to start task attempt: {}", err),
+        }
+
+        Ok(workspace)
+    }
+
+    /// Keep `winner` and archive the other attempts of its task, stopping
+    /// anything they still run. Pinned attempts are kept as well. Returns the
+    /// ids of the archived attempts.
+    async fn archive_other_attempts(
+        &self,
+        winner: &Workspace,
+    ) -> Result<Vec<Uuid>, ContainerError> {
+        let pool = &self.db().pool;
+        if winner.archived {
+            Workspace::set_archived(pool, winner.id, false).await?;
+        }
+
+        let mut archived = Vec::new();
+        for other in Workspace::fetch_all(pool, Some(winner.task_id)).await? {
+            if other.id == winner.id || other.archived || other.pinned {
+                continue;
+            }
+            self.try_stop(&other, true).await;
+            Workspace::set_archived(pool, other.id, true).await?;
+            archived.push(other.id);
+        }
+        Ok(archived)
+    }
+
+    /// Remove an attempt that was just created, e.g. when starting a set of
+    /// attempts fails partway: stop its processes, remove its worktrees and
+    /// delete it.
+    async fn discard_task_attempt(&self, workspace: &Workspace) -> Result<(), ContainerError> {
+        self.delete(workspace).await?;
+        Workspace::delete(&self.db().pool, workspace.id).await?;
+        Ok(())
+    }
+
+    /// Start the dependents of a task that just reached done, if they have an
+    /// auto start configured and no other unfinished blockers.
+    async fn start_unblocked_dependents(&self, task_id: Uuid) {
+        let pool = &self.db().pool;
+        let ready = match TaskAutoStart::find_ready_after(pool, task_id).await {
+            Ok(ready) => ready,
+            Err(e) => {
+                tracing::error!("Failed to load dependents of task {}: {}", task_id, e);
+                return;
+            }
+        };
+
+        for auto_start in ready {
+            self.run_auto_start(auto_start).await;
+        }
+    }
+
+    /// Start the task if it has an auto start configured and every blocker is
+    /// done, e.g. after the auto start was set or a blocker went away.
+    async fn start_if_unblocked(&self, task_id: Uuid) {
+        match TaskAutoStart::find_ready(&self.db().pool, task_id).await {
+            Ok(Some(auto_start)) => self.run_auto_start(auto_start).await,
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to load auto start of task {}: {}", task_id, e),
+        }
+    }
+
+    async fn run_auto_start(&self, auto_start: TaskAutoStart) {
+        let pool = &self.db().pool;
+        let task = match Task::find_by_id(pool, auto_start.task_id).await {
+            Ok(Some(task)) => task,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to load task {}: {}", auto_start.task_id, e);
+                return;
+            }
+        };
+
+        // Auto start fires once; a failed start is left for the user
+        if let Err(e) = TaskAutoStart::delete(pool, task.id).await {
+            tracing::error!("Failed to clear auto start of task {}: {}", task.id, e);
+            return;
+        }
+        match self
+            .start_task_attempt(&task, auto_start.executor_profile_id.0, &auto_start.repos.0)
+            .await
+        {
+            Ok(workspace) => tracing::info!(
+                "Blockers of task {} are done, started attempt {}",
+                task.id,
+                workspace.id
+            ),
+            Err(e) => tracing::error!("Failed to auto start task {}: {}", task.id, e),
+        }
+    }
+
+    /// Rebase the workspaces stacked on `parent` in `repo` after its branch
+    /// changed, moving them onto the parent's target branch once it is merged.
+    /// The whole stack above is rebased in turn; failures are logged and leave
+    /// that part of the stack as it was.
+    async fn restack_children(
+        &self,
+        parent: &Workspace,
+        repo: &Repo,
+        change: ParentBranchChange,
+        git_hosts: &SelfHostedGitHosts,
+    ) {
+        let mut pending = vec![(parent.clone(), change)];
+        while let Some((parent, change)) = pending.pop() {
+            let children =
+                match Workspace::find_stacked_children(&self.db().pool, &parent, Some(repo.id))
+                    .await
+                {
+                    Ok(children) => children,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to load workspaces stacked on {}: {}",
+                            parent.id,
+                            e
+                        );
+                        continue;
+                    }
+                };
+
+            for child in children {
+                match self
+                    .restack_child(&parent, &child, repo, &change, git_hosts)
+                    .await
+                {
+                    Ok(old_head) => pending.push((child, ParentBranchChange::Rebased { old_head })),
+                    Err(e) => tracing::error!(
+                        "Failed to restack workspace {} on '{}': {}",
+                        child.id,
+                        parent.branch,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Rebase one stacked workspace and bring its open PRs along, returning
+    /// the commit its branch pointed at before
+    async fn restack_child(
+        &self,
+        parent: &Workspace,
+        child: &Workspace,
+        repo: &Repo,
+        change: &ParentBranchChange,
+        git_hosts: &SelfHostedGitHosts,
+    ) -> Result<String, ContainerError> {
+        let pool = &self.db().pool;
+        let (new_base, old_base) = match change {
+            ParentBranchChange::Rebased { old_head } => (parent.branch.clone(), old_head.clone()),
+            ParentBranchChange::Merged { old_head } | ParentBranchChange::PrMerged { old_head } => {
+                let parent_repo =
+                    WorkspaceRepo::find_by_workspace_and_repo_id(pool, parent.id, repo.id)
+                        .await?
+                        .ok_or(SqlxError::RowNotFound)?;
+                // The child now belongs on the branch the parent went into, even
+                // if the rebase below stops on conflicts
+                WorkspaceRepo::update_target_branch(
+                    pool,
+                    child.id,
+                    repo.id,
+                    &parent_repo.target_branch,
+                )
+                .await?;
+                let new_base = if matches!(change, ParentBranchChange::PrMerged { .. }) {
+                    // The local target branch doesn't have the merge yet
+                    self.git()
+                        .fetch_remote_tracking_branch(&repo.path, &parent_repo.target_branch)?
+                } else {
+                    parent_repo.target_branch
+                };
+                (new_base, old_head.clone())
+            }
+        };
+
+        let container_ref = self.ensure_container_exists(child).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let old_head = self.git().get_branch_oid(&repo.path, &child.branch)?;
+        self.git().rebase_branch(
+            &repo.path,
+            &worktree_path,
+            &new_base,
+            &old_base,
+            &child.branch,
+        )?;
+        tracing::info!(
+            "Restacked workspace {} onto '{}' in {}",
+            child.id,
+            new_base,
+            repo.name
+        );
+
+        let open_prs: Vec<_> = Merge::find_by_workspace_and_repo_id(pool, child.id, repo.id)
+            .await?
+            .into_iter()
+            .filter_map(|merge| match merge {
+                Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => Some(pr),
+                _ => None,
+            })
+            .collect();
+        if open_prs.is_empty() {
+            return Ok(old_head);
+        }
+
+        // The rebase rewrote the branch, so the PR only follows with a force push
+        self.git()
+            .push_to_remote(&worktree_path, &child.branch, true)?;
+
+        if matches!(
+            change,
+            ParentBranchChange::Merged { .. } | ParentBranchChange::PrMerged { .. }
+        ) {
+            // PR bases are plain branch names on the host
+            let base_branch = match self
+                .git()
+                .get_remote_from_branch_name(&repo.path, &new_base)
+            {
+                Ok(remote) => new_base
+                    .strip_prefix(&format!("{}/", remote.name))
+                    .unwrap_or(&new_base)
+                    .to_string(),
+                Err(_) => new_base.clone(),
+            };
+            for pr in open_prs {
+                let result = match GitHostService::from_url(&pr.pr_info.url, git_hosts) {
+                    Ok(git_host) => git_host.update_pr_base(&pr.pr_info.url, &base_branch).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => Merge::update_target_branch(pool, pr.id, &base_branch).await?,
+                    Err(e) => tracing::error!(
+                        "Failed to retarget PR #{} to '{}': {}",
+                        pr.pr_info.number,
+                        base_branch,
+                        e
+                    ),
+                }
+            }
+        }
+
+        Ok(old_head)
+    }
+
+    /// Hand the conflicts of an interrupted git operation in one of the
+    /// workspace's repos to its agent. Returns false when there is nothing to
+    /// resolve or the prompt can't be sent yet.
+    async fn resolve_conflicts_with_agent(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<bool, ContainerError> {
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let Some(op) = self.git().detect_conflict_op(&worktree_path)? else {
+            return Ok(false);
+        };
+        let conflicted_files = self.git().get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            return Ok(false);
+        }
+        let (ours, theirs) = self.git().get_conflict_subjects(&worktree_path, &op);
+
+        let prompt = conflict_resolution_prompt(
+            &repo.name,
+            &op,
+            &conflicted_files,
+            ours.as_deref(),
+            theirs.as_deref(),
+        );
+        self.queue_follow_up(workspace, prompt).await
+    }
+
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_profile_id: ExecutorProfileId,
-    ) -> Result<ExecutionProcess, ContainerError> {
+    ) -> Result<ExecutionStart, ContainerError> {
         // Create container
         self.create(workspace).await?;
 
@@ -954,7 +1921,7 @@ pub trait ContainerService {
             cleanup_action.map(Box::new),
         );
 
-        let execution_process = if all_parallel {
+        let start = if all_parallel {
             // All parallel: start each setup independently, then start coding agent
             for repo in &repos_with_setup {
                 if let Some(action) = Self::setup_action_for_repo(repo)
@@ -989,15 +1956,148 @@ pub trait ContainerService {
             .await?
         };
 
-        Ok(execution_process)
+        Ok(start)
     }
 
+    /// Start an execution. Coding agent runs that would exceed the global or
+    /// project concurrency limit are queued instead; they start once a slot
+    /// frees up.
     async fn start_execution(
         &self,
         workspace: &Workspace,
         session: &Session,
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
+    ) -> Result<ExecutionStart, ContainerError> {
+        if run_reason != &ExecutionProcessRunReason::CodingAgent {
+            let execution_process = self
+                .start_execution_now(workspace, session, executor_action, run_reason)
+                .await?;
+            return Ok(ExecutionStart::Started { execution_process });
+        }
+
+        let _slots = self.coding_agent_slots().lock().await;
+        // Earlier queued starts go first
+        self.start_queued_executions_locked().await;
+
+        let pool = &self.db().pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if !self.has_free_coding_agent_slot(task.project_id).await? {
+            QueuedExecution::create(pool, workspace.id, session.id, executor_action, run_reason)
+                .await?;
+            Task::update_status(pool, task.id, TaskStatus::InProgress).await?;
+            tracing::info!(
+                "Queued coding agent for workspace {} until a slot is free",
+                workspace.id
+            );
+            return Ok(ExecutionStart::Queued);
+        }
+
+        let execution_process = self
+            .start_execution_now(workspace, session, executor_action, run_reason)
+            .await?;
+        Ok(ExecutionStart::Started { execution_process })
+    }
+
+    /// Whether another coding agent may start in the project without
+    /// exceeding the global or project limit
+    async fn has_free_coding_agent_slot(&self, project_id: Uuid) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+
+        if let Some(max) = self.max_concurrent_coding_agents().await
+            && ExecutionProcess::count_running_coding_agents(pool, None).await? >= i64::from(max)
+        {
+            return Ok(false);
+        }
+
+        let project_max = Project::find_by_id(pool, project_id)
+            .await?
+            .and_then(|project| project.max_concurrent_coding_agents);
+        if let Some(max) = project_max
+            && ExecutionProcess::count_running_coding_agents(pool, Some(project_id)).await? >= max
+        {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Start queued coding agent runs, oldest first, while slots are free.
+    /// Call when a run finishes and at startup.
+    async fn start_queued_executions(&self) {
+        let _slots = self.coding_agent_slots().lock().await;
+        self.start_queued_executions_locked().await;
+    }
+
+    /// Same as [`Self::start_queued_executions`], with the slot lock held
+    async fn start_queued_executions_locked(&self) {
+        let queued = match QueuedExecution::find_all(&self.db().pool).await {
+            Ok(queued) => queued,
+            Err(e) => {
+                tracing::error!("Failed to load queued executions: {}", e);
+                return;
+            }
+        };
+
+        for entry in queued {
+            if let Err(e) = self.try_start_queued_execution(&entry).await {
+                tracing::error!(
+                    "Failed to start queued execution for workspace {}: {}",
+                    entry.workspace_id,
+                    e
+                );
+            }
+        }
+    }
+
+    async fn try_start_queued_execution(
+        &self,
+        entry: &QueuedExecution,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+
+        let workspace = Workspace::find_by_id(pool, entry.workspace_id).await?;
+        let session = Session::find_by_id(pool, entry.session_id).await?;
+        let (Some(workspace), Some(session)) = (workspace, session) else {
+            QueuedExecution::delete(pool, entry.id).await?;
+            return Ok(());
+        };
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if !self.has_free_coding_agent_slot(task.project_id).await? {
+            return Ok(());
+        }
+
+        // Dequeue before starting so a failing start is not retried forever
+        QueuedExecution::delete(pool, entry.id).await?;
+        self.ensure_container_exists(&workspace).await?;
+        // Reload to pick up a container ref recreated while waiting
+        let workspace = Workspace::find_by_id(pool, workspace.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        self.start_execution_now(
+            &workspace,
+            &session,
+            &entry.executor_action.0,
+            &entry.run_reason,
+        )
+        .await?;
+        tracing::info!("Started queued coding agent for workspace {}", workspace.id);
+        Ok(())
+    }
+
+    /// Start an execution without checking coding agent concurrency limits
+    async fn start_execution_now(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Update task status to InProgress when starting an execution
         let task = workspace
@@ -1194,8 +2294,14 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
-            (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
-                ExecutionProcessRunReason::SetupScript
+            (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(next)) => {
+                match next.context {
+                    ScriptContext::VerificationScript => {
+                        ExecutionProcessRunReason::VerificationScript
+                    }
+                    ScriptContext::CleanupScript => ExecutionProcessRunReason::CleanupScript,
+                    _ => ExecutionProcessRunReason::SetupScript,
+                }
             }
             (
                 ExecutorActionType::CodingAgentInitialRequest(_)
@@ -1211,10 +2317,18 @@ pub trait ContainerService {
             ) => ExecutionProcessRunReason::CodingAgent,
         };
 
-        self.start_execution(&ctx.workspace, &ctx.session, next_action, &next_run_reason)
-            .await?;
-
-        tracing::debug!("Started next action: {:?}", next_action);
+        match self
+            .start_execution(&ctx.workspace, &ctx.session, next_action, &next_run_reason)
+            .await?
+        {
+            ExecutionStart::Started { .. } => {
+                tracing::debug!("Started next action: {:?}", next_action)
+            }
+            ExecutionStart::Queued => tracing::info!(
+                "Queued next action of workspace {} until a coding agent slot is free",
+                ctx.workspace.id
+            ),
+        }
         Ok(())
     }
 }
@@ -497,6 +497,18 @@ impl EventService {
         }
     }
 
+    /// Push the current state of a workspace to the workspaces stream, for
+    /// changes in tables the update hook doesn't watch
+    pub async fn push_workspace_update(&self, workspace_id: Uuid) -> Result<(), SqlxError> {
+        if let Some(workspace_with_status) =
+            Workspace::find_by_id_with_status(&self.db.pool, workspace_id).await?
+        {
+            self.msg_store
+                .push_patch(workspace_patch::replace(&workspace_with_status));
+        }
+        Ok(())
+    }
+
     pub fn msg_store(&self) -> &Arc<MsgStore> {
         &self.msg_store
     }
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
+use db::models::merge::MergeStrategy;
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort,
@@ -8,7 +9,10 @@ use git2::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
-use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
+use utils::diff::{
+    Diff, DiffChangeKind, DiffHunk, FileDiffDetails, compute_line_change_counts, diff_hunks,
+    revert_hunks,
+};
 
 mod cli;
 
@@ -40,6 +44,8 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Invalid revert selection: {0}")]
+    InvalidRevertSelection(String),
 }
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
@@ -49,6 +55,18 @@ pub struct GitService {}
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
 
+/// A file of the workspace diff to revert, optionally narrowed to some hunks.
+/// A rename is reverted by selecting both its old and new path.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RevertSelection {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Indices into the `hunks` of the file's diff; the whole file when absent
+    #[serde(default)]
+    #[ts(optional)]
+    pub hunks: Option<Vec<usize>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -59,6 +77,14 @@ pub enum ConflictOp {
     Revert,
 }
 
+/// Whether the text holds a complete conflict block as git writes it
+fn has_conflict_markers(content: &str) -> bool {
+    let mut lines = content.lines();
+    lines.any(|l| l.starts_with("<<<<<<<"))
+        && lines.any(|l| l == "=======")
+        && lines.any(|l| l.starts_with(">>>>>>>"))
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -546,6 +572,7 @@ impl GitService {
                     (None, None)
                 };
 
+                let hunks = Self::content_hunks(&change, &old_content, &new_content);
                 file_diffs.push(Diff {
                     change,
                     old_path,
@@ -556,6 +583,7 @@ impl GitService {
                     additions,
                     deletions,
                     repo_id: None,
+                    hunks,
                 });
 
                 delta_index += 1;
@@ -768,6 +796,7 @@ impl GitService {
             (None, None) => (None, None),
         };
 
+        let hunks = Self::content_hunks(&change, &old_content, &new_content);
         Diff {
             change,
             old_path: old_path_opt,
@@ -778,6 +807,22 @@ impl GitService {
             additions,
             deletions,
             repo_id: None,
+            hunks,
+        }
+    }
+
+    /// Hunks of a file's change as a revert numbers them. The missing side of
+    /// an added or deleted file is empty; any other missing side isn't text.
+    fn content_hunks(
+        change: &DiffChangeKind,
+        old_content: &Option<String>,
+        new_content: &Option<String>,
+    ) -> Vec<DiffHunk> {
+        match (change, old_content, new_content) {
+            (_, Some(old), Some(new)) => diff_hunks(old, new),
+            (DiffChangeKind::Added, None, Some(new)) => diff_hunks("", new),
+            (DiffChangeKind::Deleted, Some(old), None) => diff_hunks(old, ""),
+            _ => Vec::new(),
         }
     }
 
@@ -802,7 +847,9 @@ impl GitService {
         Ok(None)
     }
 
-    /// Merge changes from a task branch into the base branch.
+    /// Merge changes from a task branch into the base branch. The commit
+    /// message is unused by [`MergeStrategy::Rebase`], which keeps the task
+    /// branch's own commits.
     pub fn merge_changes(
         &self,
         base_worktree_path: &Path,
@@ -810,6 +857,7 @@ impl GitService {
         task_branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
+        strategy: MergeStrategy,
     ) -> Result<String, GitServiceError> {
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
@@ -847,16 +895,28 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let sha = match strategy {
+                    MergeStrategy::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
+                    ),
+                    MergeStrategy::Rebase => git_cli.merge_fast_forward(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                    MergeStrategy::MergeCommit => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                }
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                })?;
 
                 // Update task branch ref for continuity
                 let task_refname = format!("refs/heads/{task_branch_name}");
@@ -877,28 +937,45 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
-                let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
-                )?;
+                let merged_commit_id = match strategy {
+                    MergeStrategy::Rebase => {
+                        // The base is an ancestor of the task branch (checked above),
+                        // so the task commits land on it as they are
+                        let refname = format!("refs/heads/{base_branch_name}");
+                        task_repo.reference(
+                            &refname,
+                            task_commit.id(),
+                            true,
+                            "Fast-forward merge",
+                        )?;
+                        task_commit.id()
+                    }
+                    MergeStrategy::Squash | MergeStrategy::MergeCommit => {
+                        // Create the commit in-memory (no checkout) and update the base branch ref
+                        let signature = self.signature_with_fallback(&task_repo)?;
+                        self.perform_in_memory_merge(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            &signature,
+                            commit_message,
+                            base_branch_name,
+                            strategy,
+                        )?
+                    }
+                };
 
-                // Update the task branch to the new squash commit so follow-up
+                // Update the task branch to the merged commit so follow-up
                 // work can continue from the merged state without conflicts.
                 let task_refname = format!("refs/heads/{task_branch_name}");
                 base_repo.reference(
                     &task_refname,
-                    squash_commit_id,
+                    merged_commit_id,
                     true,
-                    "Reset task branch after squash merge",
+                    "Reset task branch after merge",
                 )?;
 
-                Ok(squash_commit_id.to_string())
+                Ok(merged_commit_id.to_string())
             }
         }
     }
@@ -1303,8 +1380,9 @@ impl GitService {
         Ok(branches)
     }
 
-    /// Perform a squash merge of task branch into base branch, but fail on conflicts
-    fn perform_squash_merge(
+    /// Merge the task branch into the base branch as a squash or merge commit, but fail on conflicts
+    #[allow(clippy::too_many_arguments)]
+    fn perform_in_memory_merge(
         &self,
         repo: &Repository,
         base_commit: &git2::Commit,
@@ -1312,6 +1390,7 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        strategy: MergeStrategy,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1333,21 +1412,26 @@ impl GitService {
         let tree_id = index.write_tree_to(repo)?;
         let tree = repo.find_tree(tree_id)?;
 
-        // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
+        // A squash commit has the base commit as sole parent; a merge commit
+        // also records the task commit
+        let parents = match strategy {
+            MergeStrategy::MergeCommit => vec![base_commit, task_commit],
+            MergeStrategy::Squash | MergeStrategy::Rebase => vec![base_commit],
+        };
+        let merge_commit_id = repo.commit(
             None,           // Don't update any reference yet
             signature,      // Author
             signature,      // Committer
             commit_message, // Custom message
             &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
+            &parents,
         )?;
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
-        repo.reference(&refname, squash_commit_id, true, "Squash merge")?;
+        repo.reference(&refname, merge_commit_id, true, "Merge task branch")?;
 
-        Ok(squash_commit_id)
+        Ok(merge_commit_id)
     }
 
     /// Rebase a worktree branch onto a new base
@@ -1500,6 +1584,211 @@ impl GitService {
         Ok(())
     }
 
+    /// Create a local branch pointing at `commit_sha`, failing if it exists.
+    pub fn create_branch_at_commit(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        commit_sha: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo.find_commit(git2::Oid::from_str(commit_sha)?)?;
+        repo.branch(branch_name, &commit, false)?;
+        Ok(())
+    }
+
+    /// Delete a local branch that no worktree has checked out.
+    pub fn delete_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        repo.find_branch(branch_name, BranchType::Local)?.delete()?;
+        Ok(())
+    }
+
+    /// Revert the selected files or hunks of the worktree's changes since
+    /// `base_commit` and commit the result on the current branch. Returns the
+    /// new commit, or `None` if the selection changed nothing.
+    pub fn revert_worktree_changes(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+        selections: &[RevertSelection],
+        message: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        if self.is_rebase_in_progress(worktree_path)? {
+            return Err(GitServiceError::RebaseInProgress);
+        }
+        let repo = self.open_repo(worktree_path)?;
+        let base_tree = repo.find_commit(base_commit.as_oid())?.tree()?;
+
+        for selection in selections {
+            let rel_path = Path::new(&selection.path);
+            if rel_path.is_absolute()
+                || rel_path
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(GitServiceError::InvalidRevertSelection(format!(
+                    "{} is outside the repository",
+                    selection.path
+                )));
+            }
+            let full_path = worktree_path.join(rel_path);
+            let base_blob = match base_tree.get_path(rel_path) {
+                Ok(entry) => Some(entry.to_object(&repo)?.peel_to_blob()?),
+                Err(_) => None,
+            };
+
+            let Some(hunks) = &selection.hunks else {
+                if base_blob.is_some() {
+                    let mut checkout = git2::build::CheckoutBuilder::new();
+                    checkout
+                        .force()
+                        .disable_pathspec_match(true)
+                        .path(&selection.path);
+                    repo.checkout_tree(base_tree.as_object(), Some(&mut checkout))?;
+                } else if full_path.exists() {
+                    std::fs::remove_file(&full_path)?;
+                }
+                continue;
+            };
+
+            let base_content = match &base_blob {
+                Some(blob) if blob.is_binary() => None,
+                Some(blob) => std::str::from_utf8(blob.content()).ok().map(str::to_string),
+                None => Some(String::new()),
+            };
+            let current_content = if full_path.exists() {
+                std::fs::read(&full_path)
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+            } else {
+                Some(String::new())
+            };
+            let (Some(base_content), Some(current_content)) = (base_content, current_content)
+            else {
+                return Err(GitServiceError::InvalidRevertSelection(format!(
+                    "{} is not a text file, so only the whole file can be reverted",
+                    selection.path
+                )));
+            };
+            let reverted =
+                revert_hunks(&base_content, &current_content, hunks).ok_or_else(|| {
+                    GitServiceError::InvalidRevertSelection(format!(
+                        "{} has no such hunk",
+                        selection.path
+                    ))
+                })?;
+
+            if reverted.is_empty() && base_blob.is_none() {
+                if full_path.exists() {
+                    std::fs::remove_file(&full_path)?;
+                }
+            } else if reverted != current_content {
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&full_path, reverted)?;
+            }
+        }
+
+        // Stage only the reverted paths so unrelated changes stay out of the
+        // commit
+        let mut index = repo.index()?;
+        for selection in selections {
+            let rel_path = Path::new(&selection.path);
+            if worktree_path.join(rel_path).exists() {
+                index.add_path(rel_path)?;
+            } else if index.get_path(rel_path, 0).is_some() {
+                index.remove_path(rel_path)?;
+            }
+        }
+        index.write()?;
+
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let head = repo.head()?.peel_to_commit()?;
+        if tree.id() == head.tree_id() {
+            return Ok(None);
+        }
+        let signature = self.signature_with_fallback(&repo)?;
+        let commit_id = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&head],
+        )?;
+        Ok(Some(commit_id.to_string()))
+    }
+
+    /// Unified diff of everything that changed between two commits
+    pub fn get_unified_diff_between(
+        &self,
+        repo_path: &Path,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let from_tree = repo
+            .find_commit(git2::Oid::from_str(from_commit)?)?
+            .tree()?;
+        let to_tree = repo.find_commit(git2::Oid::from_str(to_commit)?)?.tree()?;
+        let diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+
+        let mut out = String::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                out.push(line.origin());
+            }
+            out.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+        Ok(out)
+    }
+
+    /// Lines of `path` as of `from_commit` that changed by `to_commit`, as
+    /// inclusive 1-based ranges. An insertion touches the lines around it.
+    pub fn get_changed_line_ranges(
+        &self,
+        repo_path: &Path,
+        from_commit: &str,
+        to_commit: &str,
+        path: &str,
+    ) -> Result<Vec<(u32, u32)>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let from_tree = repo
+            .find_commit(git2::Oid::from_str(from_commit)?)?
+            .tree()?;
+        let to_tree = repo.find_commit(git2::Oid::from_str(to_commit)?)?.tree()?;
+
+        let mut opts = DiffOptions::new();
+        opts.pathspec(path)
+            .disable_pathspec_match(true)
+            .context_lines(0);
+        let diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut opts))?;
+
+        let mut ranges = Vec::new();
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |_, hunk| {
+                let (start, lines) = (hunk.old_start(), hunk.old_lines());
+                ranges.push(if lines == 0 {
+                    (start.max(1), start + 1)
+                } else {
+                    (start, start + lines - 1)
+                });
+                true
+            }),
+            None,
+        )?;
+        Ok(ranges)
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let git = GitCli::new();
@@ -1587,6 +1876,100 @@ impl GitService {
         Ok(())
     }
 
+    /// Subjects of the commits on both sides of an in-progress conflict: the
+    /// commit being built on (HEAD) and the one being applied.
+    pub fn get_conflict_subjects(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> (Option<String>, Option<String>) {
+        let git = GitCli::new();
+        let theirs_ref = match op {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        };
+        (
+            git.commit_subject(worktree_path, "HEAD").ok(),
+            git.commit_subject(worktree_path, theirs_ref).ok(),
+        )
+    }
+
+    /// Files that still contain conflict markers, among the conflicted files
+    /// and the ones changed against HEAD.
+    pub fn find_conflict_markers(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let git = GitCli::new();
+        let mut candidates = self.get_conflicted_files(worktree_path)?;
+        let changed = git.changed_files_against_head(worktree_path).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git diff against HEAD failed: {e}"))
+        })?;
+        for path in changed {
+            if !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+
+        Ok(candidates
+            .into_iter()
+            .filter(|path| {
+                std::fs::read(worktree_path.join(path))
+                    .is_ok_and(|bytes| has_conflict_markers(&String::from_utf8_lossy(&bytes)))
+            })
+            .collect())
+    }
+
+    /// Continue the in-progress conflicted operation, refusing while conflict
+    /// markers remain. A rebase that stops on a later commit reports its new
+    /// conflicts as [`GitServiceError::MergeConflicts`].
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        let remaining = self.find_conflict_markers(worktree_path)?;
+        if !remaining.is_empty() {
+            return Err(GitServiceError::MergeConflicts {
+                message: format!(
+                    "Conflict markers remain in: {}. Resolve them before continuing.",
+                    remaining.join(", ")
+                ),
+                conflicted_files: remaining,
+            });
+        }
+
+        let git = GitCli::new();
+        git.add_all(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let subcommand = match op {
+            ConflictOp::Rebase => "rebase",
+            ConflictOp::Merge => "merge",
+            ConflictOp::CherryPick => "cherry-pick",
+            ConflictOp::Revert => "revert",
+        };
+        match git.continue_operation(worktree_path, subcommand) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let conflicted_files = self.get_conflicted_files(worktree_path).unwrap_or_default();
+                if conflicted_files.is_empty() {
+                    return Err(GitServiceError::InvalidRepository(format!(
+                        "git {subcommand} --continue failed: {e}"
+                    )));
+                }
+                Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "Continuing the {subcommand} stopped on new conflicts in: {}.",
+                        conflicted_files.join(", ")
+                    ),
+                    conflicted_files,
+                })
+            }
+        }
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -1675,6 +2058,32 @@ impl GitService {
             .map_err(GitServiceError::GitCLI)
     }
 
+    /// Fetch `branch_name` from its remote into its remote-tracking branch and
+    /// return that branch, e.g. `origin/main`. Remote branches are fetched in
+    /// place.
+    pub fn fetch_remote_tracking_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
+        if branch_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &branch_ref)?;
+            return Ok(branch_name.to_string());
+        }
+
+        let remote = self.resolve_remote_for_branch(repo_path, branch_name)?;
+        let refspec = format!(
+            "+refs/heads/{branch_name}:refs/remotes/{}/{branch_name}",
+            remote.name
+        );
+        GitCli::new()
+            .fetch_with_refspec(repo_path, &remote.url, &refspec)
+            .map_err(GitServiceError::GitCLI)?;
+        Ok(format!("{}/{branch_name}", remote.name))
+    }
+
     pub fn resolve_remote_for_branch(
         &self,
         repo_path: &Path,
@@ -640,6 +640,43 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Fast-forward `base_branch` to `from_branch`, failing if it can't be
+    /// fast-forwarded. Returns the new HEAD.
+    pub fn merge_fast_forward(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Merge `from_branch` into `base_branch` with a merge commit, even when
+    /// a fast-forward would be possible. Returns the merge commit.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -673,6 +710,39 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Continue an in-progress `rebase`, `merge`, `cherry-pick` or `revert`
+    /// (the subcommand) once its conflicts are staged as resolved.
+    pub fn continue_operation(
+        &self,
+        worktree_path: &Path,
+        subcommand: &str,
+    ) -> Result<(), GitCliError> {
+        // Keep the prepared commit messages instead of waiting on an editor
+        let envs = vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(worktree_path, [subcommand, "--continue"], &envs)
+            .map(|_| ())
+    }
+
+    /// Subject line of the commit `rev` resolves to.
+    pub fn commit_subject(&self, repo_path: &Path, rev: &str) -> Result<String, GitCliError> {
+        let out = self.git(repo_path, ["log", "-1", "--format=%s", rev])?;
+        Ok(out.trim().to_string())
+    }
+
+    /// List tracked files whose content differs from HEAD, staged or not.
+    pub fn changed_files_against_head(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<String>, GitCliError> {
+        let out = self.git(worktree_path, ["diff", "--name-only", "HEAD"])?;
+        Ok(out
+            .lines()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -471,6 +471,8 @@ impl AzCli {
             status: Self::map_azure_status(status),
             merged_at,
             merge_commit_sha,
+            ci_status: None,
+            failing_checks: Vec::new(),
         }
     }
 
@@ -1,15 +1,38 @@
 //! Git hosting provider detection from repository URLs.
 
-use super::types::ProviderKind;
+use url::Url;
+
+use super::types::{ProviderKind, SelfHostedGitHosts};
 
 /// Detect the git hosting provider from a remote URL.
 ///
 /// Supports:
+/// - Self-hosted GitLab, Gitea and Forgejo servers configured in `self_hosted`
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
-pub fn detect_provider_from_url(url: &str) -> ProviderKind {
+/// - GitLab: gitlab.com and hosts starting with `gitlab.` (e.g., `https://gitlab.company.com/group/repo`)
+/// - Gitea/Forgejo: codeberg.org and hosts starting with `gitea.` or `forgejo.`
+pub fn detect_provider_from_url(url: &str, self_hosted: &SelfHostedGitHosts) -> ProviderKind {
     let url_lower = url.to_lowercase();
+    let host = hostname(&url_lower);
+
+    if let Some(host) = &host {
+        if self_hosted
+            .gitlab
+            .iter()
+            .any(|h| h.trim().eq_ignore_ascii_case(host))
+        {
+            return ProviderKind::GitLab;
+        }
+        if self_hosted
+            .gitea
+            .iter()
+            .any(|h| hostname(&h.url.to_lowercase()).as_ref() == Some(host))
+        {
+            return ProviderKind::Gitea;
+        }
+    }
 
     if url_lower.contains("github.com") {
         return ProviderKind::GitHub;
@@ -33,15 +56,38 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::GitHub;
     }
 
+    if let Some(host) = &host {
+        if host == "gitlab.com" || host.starts_with("gitlab.") {
+            return ProviderKind::GitLab;
+        }
+        if host == "codeberg.org" || host.starts_with("gitea.") || host.starts_with("forgejo.") {
+            return ProviderKind::Gitea;
+        }
+    }
+
     ProviderKind::Unknown
 }
 
+/// Hostname of an `https://`, `ssh://` or scp-like `user@host:path` URL
+pub(super) fn hostname(url: &str) -> Option<String> {
+    if let Ok(parsed) = Url::parse(url)
+        && let Some(host) = parsed.host_str()
+    {
+        return Some(host.to_string());
+    }
+    let (user_host, _) = url.split_once(':')?;
+    let host = user_host.rsplit('@').next()?;
+    (!host.is_empty() && !host.contains('/')).then(|| host.to_string())
+}
+
 /// Detect the git hosting provider from a PR URL.
 ///
 /// Supports:
 /// - GitHub: `https://github.com/owner/repo/pull/123`
 /// - GitHub Enterprise: `https://github.company.com/owner/repo/pull/123`
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo/pullrequest/123`
+/// - GitLab: `https://gitlab.company.com/group/repo/-/merge_requests/123`
+/// - Gitea/Forgejo: `https://codeberg.org/owner/repo/pulls/123`
 #[cfg(test)]
 fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     let url_lower = pr_url.to_lowercase();
@@ -59,13 +105,28 @@ fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
         return ProviderKind::AzureDevOps;
     }
 
+    // GitLab pattern: contains /-/merge_requests/ in the path
+    if url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+
+    // Gitea pattern: contains /pulls/ in the path
+    if url_lower.contains("/pulls/") {
+        return ProviderKind::Gitea;
+    }
+
     // Fall back to general URL detection
-    detect_provider_from_url(pr_url)
+    detect_provider_from_url(pr_url, &SelfHostedGitHosts::default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::git_host::types::GiteaHost;
+
+    fn detect_provider_from_url(url: &str) -> ProviderKind {
+        super::detect_provider_from_url(url, &SelfHostedGitHosts::default())
+    }
 
     #[test]
     fn test_github_com_https() {
@@ -137,11 +198,43 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab() {
         assert_eq!(
             detect_provider_from_url("https://gitlab.com/owner/repo"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.company.com:group/sub/repo.git"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_self_hosted_gitlab() {
+        let self_hosted = SelfHostedGitHosts {
+            gitlab: vec!["git.internal".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            super::detect_provider_from_url("https://git.internal/team/repo.git", &self_hosted),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            super::detect_provider_from_url("ssh://git@git.internal:2222/team/repo", &self_hosted),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            super::detect_provider_from_url("git@git.internal:team/repo.git", &self_hosted),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("https://git.internal/team/repo.git"),
             ProviderKind::Unknown
         );
+    }
+
+    #[test]
+    fn test_unknown_provider() {
         assert_eq!(
             detect_provider_from_url("https://bitbucket.org/owner/repo"),
             ProviderKind::Unknown
@@ -175,4 +268,44 @@ mod tests {
             ProviderKind::AzureDevOps
         );
     }
+
+    #[test]
+    fn test_pr_url_gitlab() {
+        assert_eq!(
+            detect_provider_from_pr_url("https://git.internal/team/repo/-/merge_requests/12"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_gitea() {
+        assert_eq!(
+            detect_provider_from_url("https://codeberg.org/owner/repo.git"),
+            ProviderKind::Gitea
+        );
+        assert_eq!(
+            detect_provider_from_url("git@forgejo.company.com:owner/repo.git"),
+            ProviderKind::Gitea
+        );
+
+        let self_hosted = SelfHostedGitHosts {
+            gitea: vec![GiteaHost {
+                url: "https://mirror.internal:3000/".to_string(),
+                token: None,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            super::detect_provider_from_url("git@mirror.internal:owner/repo.git", &self_hosted),
+            ProviderKind::Gitea
+        );
+    }
+
+    #[test]
+    fn test_pr_url_gitea() {
+        assert_eq!(
+            detect_provider_from_pr_url("https://codeberg.org/owner/repo/pulls/3"),
+            ProviderKind::Gitea
+        );
+    }
 }
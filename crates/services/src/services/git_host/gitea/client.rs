@@ -0,0 +1,633 @@
+//! Minimal client for the Gitea REST API (`/api/v1`), which Forgejo serves
+//! unchanged.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiStatus, MergeStatus, PrCheck, PullRequestInfo};
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use url::Url;
+
+use crate::services::git_host::types::{
+    CreatePrRequest, GiteaHost, OpenPrInfo, UnifiedPrComment, summarize_checks,
+};
+
+/// Pages fetched at most when listing pull requests
+const MAX_PAGES: usize = 20;
+const PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiteaRepoInfo {
+    pub owner: String,
+    pub repo_name: String,
+}
+
+#[derive(Serialize)]
+struct GiteaCreatePull<'a> {
+    head: &'a str,
+    base: &'a str,
+    title: &'a str,
+    body: &'a str,
+}
+
+#[derive(Serialize)]
+struct GiteaEditPull<'a> {
+    base: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GiteaPull {
+    number: i64,
+    html_url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: Option<GiteaBranch>,
+    base: Option<GiteaBranch>,
+}
+
+#[derive(Deserialize)]
+struct GiteaBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: Option<String>,
+}
+
+/// Combined commit status; `statuses` holds the latest status per context
+#[derive(Deserialize)]
+struct GiteaCombinedStatus {
+    #[serde(default)]
+    statuses: Vec<GiteaCommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct GiteaCommitStatus {
+    #[serde(default)]
+    context: String,
+    #[serde(default)]
+    status: String,
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaUser {
+    login: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaComment {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    user: Option<GiteaUser>,
+    created_at: Option<DateTime<Utc>>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GiteaReview {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    state: String,
+    user: Option<GiteaUser>,
+    submitted_at: Option<DateTime<Utc>>,
+    html_url: Option<String>,
+    #[serde(default)]
+    comments_count: i64,
+}
+
+#[derive(Deserialize)]
+struct GiteaReviewComment {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    user: Option<GiteaUser>,
+    created_at: Option<DateTime<Utc>>,
+    html_url: Option<String>,
+    #[serde(default)]
+    path: String,
+    /// Line in the new file, 0 when the comment is on a removed line
+    #[serde(default)]
+    position: i64,
+    /// Line in the old file
+    #[serde(default)]
+    original_position: i64,
+    diff_hunk: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum GiteaClientError {
+    #[error("Gitea request failed: {0}")]
+    Transport(String),
+    #[error("Gitea authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("Gitea API returned {status}: {body}")]
+    Http { status: u16, body: String },
+    #[error("Gitea API returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+impl GiteaClientError {
+    /// Returns true if the error is transient and should be retried.
+    fn should_retry(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Http { status, .. } => (500..=599).contains(status),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GiteaClient {
+    http: Client,
+    api_base: Url,
+    token: Option<String>,
+}
+
+impl GiteaClient {
+    pub fn new(host: &GiteaHost) -> Result<Self, GiteaClientError> {
+        let api_base =
+            Url::parse(&format!("{}/api/v1/", host.url.trim_end_matches('/'))).map_err(|e| {
+                GiteaClientError::UnexpectedOutput(format!("Invalid Gitea URL {}: {e}", host.url))
+            })?;
+        let http = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| GiteaClientError::Transport(e.to_string()))?;
+
+        Ok(Self {
+            http,
+            api_base,
+            token: host.token.clone().filter(|t| !t.trim().is_empty()),
+        })
+    }
+
+    /// Work out owner and repository from a remote URL. Only the last two path
+    /// segments are used, so instances served from a sub-path work too.
+    pub fn parse_remote_url(remote_url: &str) -> Option<GiteaRepoInfo> {
+        let remote_url = remote_url.trim();
+        let path = match Url::parse(remote_url) {
+            Ok(url) if url.has_host() => url.path().to_string(),
+            _ => remote_url.split_once(':')?.1.to_string(),
+        };
+        let mut segments = path
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .rsplit('/')
+            .filter(|s| !s.is_empty());
+        let repo_name = segments.next()?.to_string();
+        let owner = segments.next()?.to_string();
+
+        Some(GiteaRepoInfo { owner, repo_name })
+    }
+
+    /// Split a pull request URL into its repository and index.
+    ///
+    /// Format: `https://host/{owner}/{repo}/pulls/{index}`
+    pub fn parse_pr_url(pr_url: &str) -> Option<(GiteaRepoInfo, i64)> {
+        let url = Url::parse(pr_url).ok()?;
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let pulls_idx = segments.iter().rposition(|s| *s == "pulls")?;
+        if pulls_idx < 2 {
+            return None;
+        }
+        let index = segments.get(pulls_idx + 1)?.parse().ok()?;
+
+        Some((
+            GiteaRepoInfo {
+                owner: segments[pulls_idx - 2].to_string(),
+                repo_name: segments[pulls_idx - 1].to_string(),
+            },
+            index,
+        ))
+    }
+
+    async fn send<B>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<reqwest::Response, GiteaClientError>
+    where
+        B: Serialize,
+    {
+        let url = self
+            .api_base
+            .join(path)
+            .map_err(|e| GiteaClientError::UnexpectedOutput(e.to_string()))?;
+        // A retried POST could open a second pull request
+        let idempotent = matches!(
+            method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE
+        );
+
+        (|| async {
+            let mut req = self.http.request(method.clone(), url.clone());
+            if let Some(token) = &self.token {
+                req = req.header("Authorization", format!("token {token}"));
+            }
+            if let Some(b) = body {
+                req = req.json(b);
+            }
+
+            let res = req
+                .send()
+                .await
+                .map_err(|e| GiteaClientError::Transport(e.to_string()))?;
+
+            match res.status() {
+                s if s.is_success() => Ok(res),
+                StatusCode::UNAUTHORIZED => Err(GiteaClientError::AuthFailed(
+                    res.text().await.unwrap_or_default(),
+                )),
+                s => Err(GiteaClientError::Http {
+                    status: s.as_u16(),
+                    body: res.text().await.unwrap_or_default(),
+                }),
+            }
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GiteaClientError| idempotent && e.should_retry())
+        .notify(|err: &GiteaClientError, dur: Duration| {
+            tracing::warn!(
+                "Gitea API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, GiteaClientError> {
+        let res = self.send(Method::GET, path, None::<&()>).await?;
+        res.json::<T>()
+            .await
+            .map_err(|e| GiteaClientError::UnexpectedOutput(format!("{path}: {e}")))
+    }
+
+    /// Follow `page`/`limit` pagination. The `Link` header's `next` relation
+    /// is preferred; without one a short page marks the end.
+    async fn get_paged<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>, GiteaClientError> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut next = format!("{path}{separator}page=1&limit={PAGE_SIZE}");
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let res = self.send(Method::GET, &next, None::<&()>).await?;
+            let next_link = res
+                .headers()
+                .get(reqwest::header::LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_page_link);
+            let batch: Vec<T> = res
+                .json()
+                .await
+                .map_err(|e| GiteaClientError::UnexpectedOutput(format!("{path}: {e}")))?;
+            let short = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            next = match next_link {
+                Some(link) => link,
+                None if !short => format!("{path}{separator}page={}&limit={PAGE_SIZE}", page + 1),
+                None => break,
+            };
+        }
+        Ok(items)
+    }
+
+    fn repo_path(repo: &GiteaRepoInfo) -> String {
+        format!("repos/{}/{}", repo.owner, repo.repo_name)
+    }
+
+    /// Open a pull request. `head` is `branch`, or `owner:branch` for a fork.
+    pub async fn create_pull(
+        &self,
+        repo: &GiteaRepoInfo,
+        request: &CreatePrRequest,
+        head: &str,
+    ) -> Result<PullRequestInfo, GiteaClientError> {
+        // Gitea and Forgejo mark work-in-progress pull requests by title prefix
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let payload = GiteaCreatePull {
+            head,
+            base: &request.base_branch,
+            title: &title,
+            body: request.body.as_deref().unwrap_or(""),
+        };
+
+        let res = self
+            .send(
+                Method::POST,
+                &format!("{}/pulls", Self::repo_path(repo)),
+                Some(&payload),
+            )
+            .await?;
+        let pull: GiteaPull = res
+            .json()
+            .await
+            .map_err(|e| GiteaClientError::UnexpectedOutput(e.to_string()))?;
+        Ok(Self::pull_to_info(pull))
+    }
+
+    pub async fn get_pull(
+        &self,
+        repo: &GiteaRepoInfo,
+        index: i64,
+    ) -> Result<PullRequestInfo, GiteaClientError> {
+        let pull: GiteaPull = self
+            .get(&format!("{}/pulls/{index}", Self::repo_path(repo)))
+            .await?;
+        let head_sha = pull.head.as_ref().and_then(|h| h.sha.clone());
+        let mut info = Self::pull_to_info(pull);
+
+        // CI only matters while the pull request can still be merged
+        if matches!(info.status, MergeStatus::Open)
+            && let Some(sha) = head_sha
+        {
+            let combined: GiteaCombinedStatus = self
+                .get(&format!("{}/commits/{sha}/status", Self::repo_path(repo)))
+                .await?;
+            (info.ci_status, info.failing_checks) = Self::summarize_statuses(combined.statuses);
+        }
+        Ok(info)
+    }
+
+    /// Change the base branch of a pull request.
+    pub async fn update_pull_base(
+        &self,
+        repo: &GiteaRepoInfo,
+        index: i64,
+        base: &str,
+    ) -> Result<(), GiteaClientError> {
+        self.send(
+            Method::PATCH,
+            &format!("{}/pulls/{index}", Self::repo_path(repo)),
+            Some(&GiteaEditPull { base }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Pull requests whose head is `branch` (includes closed/merged).
+    pub async fn list_pulls_for_branch(
+        &self,
+        repo: &GiteaRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GiteaClientError> {
+        // The list endpoint has no head filter on older Gitea releases
+        let pulls: Vec<GiteaPull> = self
+            .get_paged(&format!("{}/pulls?state=all", Self::repo_path(repo)))
+            .await?;
+        Ok(pulls
+            .into_iter()
+            .filter(|p| p.head.as_ref().is_some_and(|h| h.ref_name == branch))
+            .map(Self::pull_to_info)
+            .collect())
+    }
+
+    pub async fn list_open_pulls(
+        &self,
+        repo: &GiteaRepoInfo,
+    ) -> Result<Vec<OpenPrInfo>, GiteaClientError> {
+        let pulls: Vec<GiteaPull> = self
+            .get_paged(&format!("{}/pulls?state=open", Self::repo_path(repo)))
+            .await?;
+        Ok(pulls
+            .into_iter()
+            .map(|p| OpenPrInfo {
+                number: p.number,
+                url: p.html_url,
+                title: p.title,
+                head_branch: p.head.map(|b| b.ref_name).unwrap_or_default(),
+                base_branch: p.base.map(|b| b.ref_name).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Login of the user the token belongs to; anonymous clients have none
+    pub async fn current_user(&self) -> Result<Option<String>, GiteaClientError> {
+        if self.token.is_none() {
+            return Ok(None);
+        }
+        let user: GiteaUser = self.get("user").await?;
+        Ok(user.login)
+    }
+
+    /// Conversation comments, review summaries and inline review comments of
+    /// a pull request, oldest first.
+    pub async fn get_comments(
+        &self,
+        repo: &GiteaRepoInfo,
+        index: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GiteaClientError> {
+        let repo_path = Self::repo_path(repo);
+        let comments: Vec<GiteaComment> = self
+            .get_paged(&format!("{repo_path}/issues/{index}/comments"))
+            .await?;
+        let reviews: Vec<GiteaReview> = self
+            .get_paged(&format!("{repo_path}/pulls/{index}/reviews"))
+            .await?;
+
+        let mut unified: Vec<UnifiedPrComment> = comments
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: login(c.user),
+                author_association: None,
+                body: c.body,
+                created_at: c.created_at.unwrap_or_else(Utc::now),
+                url: c.html_url,
+            })
+            .collect();
+
+        for review in reviews {
+            if review.state.eq_ignore_ascii_case("pending") {
+                continue;
+            }
+            if review.comments_count > 0 {
+                let review_comments: Vec<GiteaReviewComment> = self
+                    .get(&format!(
+                        "{repo_path}/pulls/{index}/reviews/{}/comments",
+                        review.id
+                    ))
+                    .await?;
+                unified.extend(review_comments.into_iter().map(Self::review_comment));
+            }
+            if !review.body.trim().is_empty() {
+                unified.push(UnifiedPrComment::General {
+                    id: format!("review-{}", review.id),
+                    author: login(review.user),
+                    author_association: None,
+                    body: review.body,
+                    created_at: review.submitted_at.unwrap_or_else(Utc::now),
+                    url: review.html_url,
+                });
+            }
+        }
+
+        unified.sort_by_key(|c| c.created_at());
+        Ok(unified)
+    }
+}
+
+impl GiteaClient {
+    fn pull_to_info(pull: GiteaPull) -> PullRequestInfo {
+        let status = if pull.merged {
+            MergeStatus::Merged
+        } else {
+            match pull.state.to_ascii_lowercase().as_str() {
+                "" | "open" => MergeStatus::Open,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            }
+        };
+        PullRequestInfo {
+            number: pull.number,
+            url: pull.html_url,
+            status,
+            merged_at: pull.merged_at,
+            merge_commit_sha: pull.merge_commit_sha,
+            ci_status: None,
+            failing_checks: Vec::new(),
+        }
+    }
+
+    fn summarize_statuses(statuses: Vec<GiteaCommitStatus>) -> (Option<CiStatus>, Vec<PrCheck>) {
+        summarize_checks(statuses.into_iter().map(|s| {
+            let status = match s.status.to_ascii_lowercase().as_str() {
+                "success" | "warning" => CiStatus::Success,
+                "failure" | "error" => CiStatus::Failure,
+                _ => CiStatus::Pending,
+            };
+            (
+                status,
+                PrCheck {
+                    name: s.context,
+                    url: s.target_url.filter(|u| !u.is_empty()),
+                },
+            )
+        }))
+    }
+
+    fn review_comment(c: GiteaReviewComment) -> UnifiedPrComment {
+        let (line, side) = if c.position > 0 {
+            (Some(c.position), "RIGHT")
+        } else {
+            (Some(c.original_position).filter(|l| *l > 0), "LEFT")
+        };
+        UnifiedPrComment::Review {
+            id: c.id,
+            author: login(c.user),
+            author_association: None,
+            body: c.body,
+            created_at: c.created_at.unwrap_or_else(Utc::now),
+            url: c.html_url,
+            path: c.path,
+            line,
+            side: Some(side.to_string()),
+            diff_hunk: c.diff_hunk.filter(|h| !h.is_empty()),
+        }
+    }
+}
+
+/// The `rel="next"` target of a `Link` header
+fn next_page_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == r#"rel="next""#)
+            .then(|| {
+                target
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+fn login(user: Option<GiteaUser>) -> String {
+    user.and_then(|u| u.login)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(owner: &str, repo_name: &str) -> GiteaRepoInfo {
+        GiteaRepoInfo {
+            owner: owner.to_string(),
+            repo_name: repo_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        assert_eq!(
+            GiteaClient::parse_remote_url("https://codeberg.org/acme/widgets.git"),
+            Some(repo("acme", "widgets"))
+        );
+        assert_eq!(
+            GiteaClient::parse_remote_url("https://git.internal/gitea/acme/widgets"),
+            Some(repo("acme", "widgets"))
+        );
+        assert_eq!(
+            GiteaClient::parse_remote_url("git@git.internal:acme/widgets.git"),
+            Some(repo("acme", "widgets"))
+        );
+        assert_eq!(GiteaClient::parse_remote_url("https://git.internal/"), None);
+    }
+
+    #[test]
+    fn test_parse_pr_url() {
+        assert_eq!(
+            GiteaClient::parse_pr_url("https://codeberg.org/acme/widgets/pulls/12"),
+            Some((repo("acme", "widgets"), 12))
+        );
+        assert_eq!(
+            GiteaClient::parse_pr_url("https://git.internal/gitea/acme/widgets/pulls/3/files"),
+            Some((repo("acme", "widgets"), 3))
+        );
+        assert_eq!(
+            GiteaClient::parse_pr_url("https://codeberg.org/acme/widgets/issues/12"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_page_link() {
+        let header = r#"<https://codeberg.org/api/v1/repos/acme/widgets/issues/3/comments?limit=50&page=2>; rel="next",<https://codeberg.org/api/v1/repos/acme/widgets/issues/3/comments?limit=50&page=4>; rel="last""#;
+        assert_eq!(
+            next_page_link(header).as_deref(),
+            Some(
+                "https://codeberg.org/api/v1/repos/acme/widgets/issues/3/comments?limit=50&page=2"
+            )
+        );
+        assert_eq!(
+            next_page_link(r#"<https://codeberg.org/api/v1/x?page=1>; rel="first""#),
+            None
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Gitea and Forgejo hosting service implementation.
+
+mod client;
+
+use std::path::Path;
+
+use async_trait::async_trait;
+pub use client::{GiteaClient, GiteaClientError, GiteaRepoInfo};
+use db::models::merge::PullRequestInfo;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, GiteaHost, OpenPrInfo, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    client: GiteaClient,
+}
+
+impl GiteaProvider {
+    pub fn new(host: &GiteaHost) -> Result<Self, GitHostError> {
+        Ok(Self {
+            client: GiteaClient::new(host)?,
+        })
+    }
+
+    fn get_repo_info(&self, remote_url: &str) -> Result<GiteaRepoInfo, GitHostError> {
+        GiteaClient::parse_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse Gitea repository from URL: {remote_url}"
+            ))
+        })
+    }
+}
+
+impl From<GiteaClientError> for GitHostError {
+    fn from(error: GiteaClientError) -> Self {
+        match error {
+            GiteaClientError::AuthFailed(msg) => GitHostError::AuthFailed(msg),
+            GiteaClientError::Http { status: 403, body } => {
+                GitHostError::InsufficientPermissions(body)
+            }
+            GiteaClientError::Http { status: 404, body } => {
+                GitHostError::RepoNotFoundOrNoAccess(body)
+            }
+            GiteaClientError::Http { status, body } => {
+                GitHostError::PullRequest(format!("HTTP {status}: {body}"))
+            }
+            GiteaClientError::Transport(msg) => GitHostError::PullRequest(msg),
+            GiteaClientError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GiteaProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target = self.get_repo_info(remote_url)?;
+
+        // For cross-fork PRs the head is given as "owner:branch"
+        let head = match &request.head_repo_url {
+            Some(head_url) => {
+                let head_repo = self.get_repo_info(head_url)?;
+                if head_repo.owner != target.owner {
+                    format!("{}:{}", head_repo.owner, request.head_branch)
+                } else {
+                    request.head_branch.clone()
+                }
+            }
+            None => request.head_branch.clone(),
+        };
+
+        let pr = self.client.create_pull(&target, request, &head).await?;
+        info!("Created Gitea PR #{} for branch {}", pr.number, head);
+        Ok(pr)
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (repo, index) = GiteaClient::parse_pr_url(pr_url).ok_or_else(|| {
+            GitHostError::PullRequest(format!("Could not parse Gitea PR URL: {pr_url}"))
+        })?;
+        Ok(self.client.get_pull(&repo, index).await?)
+    }
+
+    async fn update_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GitHostError> {
+        let (repo, index) = GiteaClient::parse_pr_url(pr_url).ok_or_else(|| {
+            GitHostError::PullRequest(format!("Could not parse Gitea PR URL: {pr_url}"))
+        })?;
+        self.client
+            .update_pull_base(&repo, index, base_branch)
+            .await?;
+        info!("Retargeted Gitea PR #{} to {}", index, base_branch);
+        Ok(())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo = self.get_repo_info(remote_url)?;
+        Ok(self
+            .client
+            .list_pulls_for_branch(&repo, branch_name)
+            .await?)
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo = self.get_repo_info(remote_url)?;
+        Ok(self.client.get_comments(&repo, pr_number).await?)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let repo = self.get_repo_info(remote_url)?;
+        Ok(self.client.list_open_pulls(&repo).await?)
+    }
+
+    async fn current_user(&self, _remote_url: &str) -> Result<Option<String>, GitHostError> {
+        Ok(self.client.current_user().await?)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Gitea
+    }
+}
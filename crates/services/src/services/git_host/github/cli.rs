@@ -11,14 +11,16 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{CiStatus, MergeStatus, PrCheck, PullRequestInfo};
 use serde::Deserialize;
 use tempfile::NamedTempFile;
 use thiserror::Error;
+use url::Url;
 use utils::shell::resolve_executable_path_blocking;
 
 use crate::services::git_host::types::{
     CreatePrRequest, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    summarize_checks,
 };
 
 /// A repository from a GitHub organization (non-archived).
@@ -114,6 +116,32 @@ struct GhPrResponse {
     state: String,
     merged_at: Option<DateTime<Utc>>,
     merge_commit: Option<GhMergeCommit>,
+    #[serde(default)]
+    status_check_rollup: Vec<GhStatusCheck>,
+}
+
+/// Entry of `statusCheckRollup`: a check run (GitHub Actions, apps) or a
+/// commit status posted through the statuses API
+#[derive(Deserialize)]
+#[serde(tag = "__typename")]
+enum GhStatusCheck {
+    #[serde(rename_all = "camelCase")]
+    CheckRun {
+        name: String,
+        #[serde(default)]
+        status: String,
+        conclusion: Option<String>,
+        details_url: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatusContext {
+        context: String,
+        #[serde(default)]
+        state: String,
+        target_url: Option<String>,
+    },
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize)]
@@ -269,13 +297,62 @@ impl GhCli {
                 "view",
                 pr_url,
                 "--json",
-                "number,url,state,mergedAt,mergeCommit",
+                "number,url,state,mergedAt,mergeCommit,statusCheckRollup",
             ],
             None,
         )?;
         Self::parse_pr_view(&raw)
     }
 
+    /// Change the base branch of a pull request.
+    pub fn edit_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GhCliError> {
+        self.run(["pr", "edit", pr_url, "--base", base_branch], None)?;
+        Ok(())
+    }
+
+    /// Login of the authenticated user.
+    pub fn current_user(&self) -> Result<String, GhCliError> {
+        let raw = self.run(["api", "user", "--jq", ".login"], None)?;
+        Ok(raw.trim().to_string())
+    }
+
+    /// Log of the failed steps of a GitHub Actions job.
+    pub fn get_failed_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: &str,
+    ) -> Result<String, GhCliError> {
+        self.run(
+            [
+                "run",
+                "view",
+                "--job",
+                job_id,
+                "--log-failed",
+                "--repo",
+                &format!("{owner}/{repo}"),
+            ],
+            None,
+        )
+    }
+
+    /// Owner, repo and job id of an Actions job URL.
+    ///
+    /// Format: `https://github.com/{owner}/{repo}/actions/runs/{run}/job/{job}`
+    pub fn parse_actions_job_url(url: &str) -> Option<(String, String, String)> {
+        let url = Url::parse(url).ok()?;
+        let segments: Vec<&str> = url.path_segments()?.collect();
+        match segments.as_slice() {
+            [owner, repo, "actions", "runs", _, "job", job_id, ..]
+                if job_id.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                Some((owner.to_string(), repo.to_string(), job_id.to_string()))
+            }
+            _ => None,
+        }
+    }
+
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
@@ -437,6 +514,8 @@ impl GhCli {
             status: MergeStatus::Open,
             merged_at: None,
             merge_commit_sha: None,
+            ci_status: None,
+            failing_checks: Vec::new(),
         })
     }
 
@@ -483,6 +562,11 @@ impl GhCli {
         } else {
             &pr.state
         };
+        let (ci_status, failing_checks) = summarize_checks(
+            pr.status_check_rollup
+                .into_iter()
+                .filter_map(Self::status_check_result),
+        );
         PullRequestInfo {
             number: pr.number,
             url: pr.url,
@@ -494,6 +578,56 @@ impl GhCli {
             },
             merged_at: pr.merged_at,
             merge_commit_sha: pr.merge_commit.and_then(|c| c.oid),
+            ci_status,
+            failing_checks,
+        }
+    }
+
+    /// Outcome of one rollup entry; skipped and neutral checks don't count
+    fn status_check_result(check: GhStatusCheck) -> Option<(CiStatus, PrCheck)> {
+        match check {
+            GhStatusCheck::CheckRun {
+                name,
+                status,
+                conclusion,
+                details_url,
+            } => {
+                let outcome = if !status.eq_ignore_ascii_case("COMPLETED") {
+                    CiStatus::Pending
+                } else {
+                    match conclusion.unwrap_or_default().to_ascii_uppercase().as_str() {
+                        "SUCCESS" => CiStatus::Success,
+                        "NEUTRAL" | "SKIPPED" | "STALE" => return None,
+                        _ => CiStatus::Failure,
+                    }
+                };
+                Some((
+                    outcome,
+                    PrCheck {
+                        name,
+                        url: details_url,
+                    },
+                ))
+            }
+            GhStatusCheck::StatusContext {
+                context,
+                state,
+                target_url,
+            } => {
+                let outcome = match state.to_ascii_uppercase().as_str() {
+                    "SUCCESS" => CiStatus::Success,
+                    "FAILURE" | "ERROR" => CiStatus::Failure,
+                    _ => CiStatus::Pending,
+                };
+                Some((
+                    outcome,
+                    PrCheck {
+                        name: context,
+                        url: target_url,
+                    },
+                ))
+            }
+            GhStatusCheck::Unknown => None,
         }
     }
 
@@ -571,3 +705,52 @@ impl GhCli {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_actions_job_url() {
+        assert_eq!(
+            GhCli::parse_actions_job_url(
+                "https://github.com/acme/widgets/actions/runs/123/job/456"
+            ),
+            Some(("acme".to_string(), "widgets".to_string(), "456".to_string()))
+        );
+        assert_eq!(
+            GhCli::parse_actions_job_url("https://ci.example.com/acme/widgets/builds/7"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_status_check_rollup() {
+        let raw = r#"{
+            "number": 5,
+            "url": "https://github.com/acme/widgets/pull/5",
+            "state": "OPEN",
+            "mergedAt": null,
+            "mergeCommit": null,
+            "statusCheckRollup": [
+                {"__typename": "CheckRun", "name": "lint", "status": "COMPLETED",
+                 "conclusion": "SUCCESS", "detailsUrl": "https://x/1"},
+                {"__typename": "CheckRun", "name": "docs", "status": "COMPLETED",
+                 "conclusion": "SKIPPED", "detailsUrl": "https://x/2"},
+                {"__typename": "CheckRun", "name": "test", "status": "COMPLETED",
+                 "conclusion": "FAILURE", "detailsUrl": "https://x/3"},
+                {"__typename": "StatusContext", "context": "ci/deploy", "state": "PENDING",
+                 "targetUrl": null}
+            ]
+        }"#;
+        let info = GhCli::parse_pr_view(raw).unwrap();
+        assert_eq!(info.ci_status, Some(CiStatus::Failure));
+        assert_eq!(
+            info.failing_checks,
+            vec![PrCheck {
+                name: "test".to_string(),
+                url: Some("https://x/3".to_string()),
+            }]
+        );
+    }
+}
@@ -6,9 +6,9 @@ use std::{path::Path, time::Duration};
 
 use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
-pub use cli::{GhCli, GhCliError, GitHubOrgRepoInfo};
 use cli::GitHubRepoInfo;
-use db::models::merge::PullRequestInfo;
+pub use cli::{GhCli, GhCliError, GitHubOrgRepoInfo};
+use db::models::merge::{PrCheck, PullRequestInfo};
 use tokio::task;
 use tracing::info;
 
@@ -400,6 +400,52 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn update_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        let base = base_branch.to_string();
+        task::spawn_blocking(move || cli.edit_pr_base(&url, &base))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for editing PR: {err}"
+                ))
+            })??;
+        info!("Retargeted GitHub PR {} to {}", pr_url, base_branch);
+        Ok(())
+    }
+
+    async fn current_user(&self, _remote_url: &str) -> Result<Option<String>, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let login = task::spawn_blocking(move || cli.current_user())
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching the current user: {err}"
+                ))
+            })??;
+        Ok(Some(login).filter(|l| !l.is_empty()))
+    }
+
+    async fn get_check_log(&self, check: &PrCheck) -> Result<Option<String>, GitHostError> {
+        // Only GitHub Actions jobs have logs the CLI can fetch
+        let Some((owner, repo, job_id)) =
+            check.url.as_deref().and_then(GhCli::parse_actions_job_url)
+        else {
+            return Ok(None);
+        };
+
+        let cli = self.gh_cli.clone();
+        let log = task::spawn_blocking(move || cli.get_failed_job_log(&owner, &repo, &job_id))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching job log: {err}"
+                ))
+            })??;
+        Ok(Some(log))
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
@@ -0,0 +1,782 @@
+//! Minimal helpers around the GitLab CLI (`glab`).
+//!
+//! Every call goes through `glab api`, which talks to the GitLab REST API with
+//! the credentials `glab auth login` stored for the host. This works the same
+//! for gitlab.com and self-hosted instances.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::Path,
+    process::Command,
+};
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiStatus, MergeStatus, PrCheck, PullRequestInfo};
+use serde::{Deserialize, de::DeserializeOwned};
+use thiserror::Error;
+use url::{Url, form_urlencoded};
+use utils::shell::resolve_executable_path_blocking;
+
+use crate::services::git_host::types::{CreatePrRequest, OpenPrInfo, UnifiedPrComment};
+
+/// A GitLab project identified by its host and full namespace path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabRepoInfo {
+    pub host: String,
+    /// e.g. `group/subgroup/project`
+    pub project_path: String,
+}
+
+impl GitLabRepoInfo {
+    /// Project path encoded for use as `:id` in API endpoints
+    fn api_id(&self) -> String {
+        form_urlencoded::byte_serialize(self.project_path.as_bytes()).collect()
+    }
+
+    fn merge_request_url(&self, iid: i64) -> String {
+        format!(
+            "https://{}/{}/-/merge_requests/{}",
+            self.host, self.project_path, iid
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct GlabProject {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct GlabMergeRequest {
+    iid: i64,
+    web_url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+    #[serde(default)]
+    source_branch: String,
+    #[serde(default)]
+    target_branch: String,
+    head_pipeline: Option<GlabPipeline>,
+}
+
+#[derive(Deserialize)]
+struct GlabPipeline {
+    id: i64,
+    project_id: i64,
+    #[serde(default)]
+    status: String,
+    web_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlabJob {
+    name: String,
+    web_url: Option<String>,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+#[derive(Deserialize)]
+struct GlabDiscussion {
+    #[serde(default)]
+    notes: Vec<GlabNote>,
+}
+
+#[derive(Deserialize)]
+struct GlabNote {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    author: Option<GlabUser>,
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    system: bool,
+    position: Option<GlabNotePosition>,
+}
+
+#[derive(Deserialize)]
+struct GlabUser {
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlabNotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Error)]
+pub enum GlabCliError {
+    #[error("GitLab CLI (`glab`) executable not found or not runnable")]
+    NotAvailable,
+    #[error("GitLab CLI command failed: {0}")]
+    CommandFailed(String),
+    #[error("GitLab CLI authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("GitLab CLI returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GlabCli;
+
+impl GlabCli {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Ensure the GitLab CLI binary is discoverable.
+    fn ensure_available(&self) -> Result<(), GlabCliError> {
+        resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        Ok(())
+    }
+
+    fn run<I, S>(&self, args: I, dir: Option<&Path>) -> Result<String, GlabCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.ensure_available()?;
+        let glab = resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        let mut cmd = Command::new(&glab);
+        if let Some(d) = dir {
+            cmd.current_dir(d);
+        }
+        for arg in args {
+            cmd.arg(arg);
+        }
+        tracing::debug!(
+            "Running GitLab CLI command: {:?} {:?}",
+            glab,
+            cmd.get_args()
+        );
+
+        let output = cmd
+            .output()
+            .map_err(|err| GlabCliError::CommandFailed(err.to_string()))?;
+
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+        let lower = stderr.to_ascii_lowercase();
+        if lower.contains("401")
+            || lower.contains("unauthorized")
+            || lower.contains("glab auth login")
+            || lower.contains("not authenticated")
+            || lower.contains("no token")
+        {
+            return Err(GlabCliError::AuthFailed(stderr));
+        }
+
+        Err(GlabCliError::CommandFailed(stderr))
+    }
+
+    /// Run `glab api` against the host of `repo`
+    fn api(
+        &self,
+        repo: &GitLabRepoInfo,
+        method: &str,
+        endpoint: &str,
+        fields: &[(&str, &str)],
+        paginate: bool,
+    ) -> Result<String, GlabCliError> {
+        let mut args: Vec<OsString> = Vec::with_capacity(8 + fields.len() * 2);
+        args.push(OsString::from("api"));
+        args.push(OsString::from("--hostname"));
+        args.push(OsString::from(&repo.host));
+        args.push(OsString::from("--method"));
+        args.push(OsString::from(method));
+        if paginate {
+            args.push(OsString::from("--paginate"));
+        }
+        for (key, value) in fields {
+            args.push(OsString::from("--raw-field"));
+            args.push(OsString::from(format!("{key}={value}")));
+        }
+        args.push(OsString::from(endpoint));
+        self.run(args, None)
+    }
+
+    /// Work out host and project path from a remote URL.
+    ///
+    /// Supports `https://host/group/repo.git`, `ssh://git@host:2222/group/repo.git`
+    /// and `git@host:group/repo.git`.
+    pub fn parse_remote_url(remote_url: &str) -> Option<GitLabRepoInfo> {
+        let remote_url = remote_url.trim();
+        let (host, path) = match Url::parse(remote_url) {
+            Ok(url) if url.has_host() => (url.host_str()?.to_string(), url.path().to_string()),
+            _ => {
+                let (user_host, path) = remote_url.split_once(':')?;
+                let host = user_host.rsplit('@').next()?;
+                (host.to_string(), path.to_string())
+            }
+        };
+
+        let project_path = path
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .trim_end_matches('/')
+            .to_string();
+        if host.is_empty() || !project_path.contains('/') {
+            return None;
+        }
+
+        Some(GitLabRepoInfo {
+            host: host.to_lowercase(),
+            project_path,
+        })
+    }
+
+    /// Split a merge request URL into its project and IID.
+    ///
+    /// Format: `https://host/group/repo/-/merge_requests/{iid}`
+    pub fn parse_mr_url(mr_url: &str) -> Option<(GitLabRepoInfo, i64)> {
+        Self::parse_project_url(mr_url, "/-/merge_requests/")
+    }
+
+    /// Split a CI job URL into its project and job id.
+    ///
+    /// Format: `https://host/group/repo/-/jobs/{id}`
+    pub fn parse_job_url(job_url: &str) -> Option<(GitLabRepoInfo, i64)> {
+        Self::parse_project_url(job_url, "/-/jobs/")
+    }
+
+    fn parse_project_url(raw: &str, marker: &str) -> Option<(GitLabRepoInfo, i64)> {
+        let url = Url::parse(raw).ok()?;
+        let (project_path, rest) = url.path().split_once(marker)?;
+        let iid = rest.split('/').next()?.parse().ok()?;
+        let project_path = project_path.trim_matches('/');
+        if project_path.is_empty() {
+            return None;
+        }
+
+        Some((
+            GitLabRepoInfo {
+                host: url.host_str()?.to_lowercase(),
+                project_path: project_path.to_string(),
+            },
+            iid,
+        ))
+    }
+
+    fn get_project_id(&self, repo: &GitLabRepoInfo) -> Result<i64, GlabCliError> {
+        let raw = self.api(
+            repo,
+            "GET",
+            &format!("projects/{}", repo.api_id()),
+            &[],
+            false,
+        )?;
+        let project: GlabProject = Self::parse_json(&raw, "project")?;
+        Ok(project.id)
+    }
+
+    /// Open a merge request. For cross-fork requests `source` is the fork that
+    /// holds the head branch and `target` the project it is merged into.
+    pub fn create_mr(
+        &self,
+        request: &CreatePrRequest,
+        source: &GitLabRepoInfo,
+        target: &GitLabRepoInfo,
+    ) -> Result<PullRequestInfo, GlabCliError> {
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let description = request.body.as_deref().unwrap_or("");
+
+        let mut fields = vec![
+            ("source_branch", request.head_branch.clone()),
+            ("target_branch", request.base_branch.clone()),
+            ("title", title),
+            ("description", description.to_string()),
+        ];
+        if source != target {
+            fields.push((
+                "target_project_id",
+                self.get_project_id(target)?.to_string(),
+            ));
+        }
+        let fields: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let raw = self.api(
+            source,
+            "POST",
+            &format!("projects/{}/merge_requests", source.api_id()),
+            &fields,
+            false,
+        )?;
+        Self::parse_mr(&raw)
+    }
+
+    /// Retrieve details for a merge request by URL.
+    pub fn view_mr(&self, mr_url: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let (repo, iid) = Self::parse_mr_url(mr_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!("Could not parse GitLab MR URL: {mr_url}"))
+        })?;
+        let raw = self.api(
+            &repo,
+            "GET",
+            &format!("projects/{}/merge_requests/{}", repo.api_id(), iid),
+            &[],
+            false,
+        )?;
+        let mut mr: GlabMergeRequest = Self::parse_json(&raw, "merge request")?;
+        let pipeline = mr.head_pipeline.take();
+        let mut info = Self::mr_to_info(mr);
+        if let Some(pipeline) = pipeline {
+            let failed_jobs =
+                if Self::map_pipeline_status(&pipeline.status) == Some(CiStatus::Failure) {
+                    self.get_failed_jobs(&repo, &pipeline)?
+                } else {
+                    Vec::new()
+                };
+            (info.ci_status, info.failing_checks) = Self::pipeline_checks(&pipeline, failed_jobs);
+        }
+        Ok(info)
+    }
+
+    /// Change the target branch of a merge request.
+    pub fn update_mr_target(&self, mr_url: &str, target_branch: &str) -> Result<(), GlabCliError> {
+        let (repo, iid) = Self::parse_mr_url(mr_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!("Could not parse GitLab MR URL: {mr_url}"))
+        })?;
+        self.api(
+            &repo,
+            "PUT",
+            &format!("projects/{}/merge_requests/{}", repo.api_id(), iid),
+            &[("target_branch", target_branch)],
+            false,
+        )?;
+        Ok(())
+    }
+
+    /// Log output of a CI job.
+    pub fn get_job_trace(
+        &self,
+        repo: &GitLabRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GlabCliError> {
+        self.api(
+            repo,
+            "GET",
+            &format!("projects/{}/jobs/{}/trace", repo.api_id(), job_id),
+            &[],
+            false,
+        )
+    }
+
+    /// Jobs that failed in a pipeline. The pipeline may belong to the source
+    /// project of a fork, so it is addressed by its own project id.
+    fn get_failed_jobs(
+        &self,
+        repo: &GitLabRepoInfo,
+        pipeline: &GlabPipeline,
+    ) -> Result<Vec<GlabJob>, GlabCliError> {
+        let raw = self.api(
+            repo,
+            "GET",
+            &format!(
+                "projects/{}/pipelines/{}/jobs?scope[]=failed&per_page=100",
+                pipeline.project_id, pipeline.id
+            ),
+            &[],
+            true,
+        )?;
+        Self::parse_pages(&raw, "pipeline jobs")
+    }
+
+    /// List merge requests for a source branch (includes closed/merged).
+    pub fn list_mrs_for_branch(
+        &self,
+        repo: &GitLabRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GlabCliError> {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .append_pair("source_branch", branch)
+            .append_pair("state", "all")
+            .append_pair("per_page", "100")
+            .finish();
+        let raw = self.api(
+            repo,
+            "GET",
+            &format!("projects/{}/merge_requests?{}", repo.api_id(), query),
+            &[],
+            false,
+        )?;
+        let mrs: Vec<GlabMergeRequest> = Self::parse_json(&raw, "merge request list")?;
+        Ok(mrs.into_iter().map(Self::mr_to_info).collect())
+    }
+
+    pub fn list_open_mrs(&self, repo: &GitLabRepoInfo) -> Result<Vec<OpenPrInfo>, GlabCliError> {
+        let raw = self.api(
+            repo,
+            "GET",
+            &format!(
+                "projects/{}/merge_requests?state=opened&per_page=100",
+                repo.api_id()
+            ),
+            &[],
+            true,
+        )?;
+        let mrs: Vec<GlabMergeRequest> = Self::parse_pages(&raw, "merge request list")?;
+        Ok(mrs
+            .into_iter()
+            .map(|mr| OpenPrInfo {
+                number: mr.iid,
+                url: mr.web_url,
+                title: mr.title,
+                head_branch: mr.source_branch,
+                base_branch: mr.target_branch,
+            })
+            .collect())
+    }
+
+    /// Username of the account `glab` is authenticated as on the host of `repo`.
+    pub fn current_user(&self, repo: &GitLabRepoInfo) -> Result<Option<String>, GlabCliError> {
+        let raw = self.api(repo, "GET", "user", &[], false)?;
+        let user: GlabUser = Self::parse_json(&raw, "user")?;
+        Ok(user.username)
+    }
+
+    /// Fetch general notes and diff notes of a merge request.
+    pub fn get_mr_comments(
+        &self,
+        repo: &GitLabRepoInfo,
+        iid: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let raw = self.api(
+            repo,
+            "GET",
+            &format!(
+                "projects/{}/merge_requests/{}/discussions?per_page=100",
+                repo.api_id(),
+                iid
+            ),
+            &[],
+            true,
+        )?;
+        Self::parse_discussions(&raw, &repo.merge_request_url(iid))
+    }
+}
+
+impl GlabCli {
+    fn parse_json<T: DeserializeOwned>(raw: &str, what: &str) -> Result<T, GlabCliError> {
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Failed to parse GitLab {what} response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// `glab api --paginate` prints one JSON array per page back to back
+    fn parse_pages<T: DeserializeOwned>(raw: &str, what: &str) -> Result<Vec<T>, GlabCliError> {
+        let mut items = Vec::new();
+        for page in serde_json::Deserializer::from_str(raw.trim()).into_iter::<Vec<T>>() {
+            items.extend(page.map_err(|err| {
+                GlabCliError::UnexpectedOutput(format!(
+                    "Failed to parse GitLab {what} response: {err}; raw: {raw}"
+                ))
+            })?);
+        }
+        Ok(items)
+    }
+
+    fn parse_mr(raw: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let mr: GlabMergeRequest = Self::parse_json(raw, "merge request")?;
+        Ok(Self::mr_to_info(mr))
+    }
+
+    fn mr_to_info(mr: GlabMergeRequest) -> PullRequestInfo {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: Self::map_gitlab_state(&mr.state),
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+            ci_status: None,
+            failing_checks: Vec::new(),
+        }
+    }
+
+    /// Map a GitLab pipeline status to CiStatus; skipped pipelines count as no CI
+    fn map_pipeline_status(status: &str) -> Option<CiStatus> {
+        match status.to_ascii_lowercase().as_str() {
+            "success" => Some(CiStatus::Success),
+            "failed" | "canceled" => Some(CiStatus::Failure),
+            "skipped" => None,
+            _ => Some(CiStatus::Pending),
+        }
+    }
+
+    fn pipeline_checks(
+        pipeline: &GlabPipeline,
+        failed_jobs: Vec<GlabJob>,
+    ) -> (Option<CiStatus>, Vec<PrCheck>) {
+        let status = Self::map_pipeline_status(&pipeline.status);
+        let mut failing: Vec<PrCheck> = failed_jobs
+            .into_iter()
+            .filter(|job| !job.allow_failure)
+            .map(|job| PrCheck {
+                name: job.name,
+                url: job.web_url,
+            })
+            .collect();
+        // A canceled pipeline has no failed jobs to point at
+        if status == Some(CiStatus::Failure) && failing.is_empty() {
+            failing.push(PrCheck {
+                name: format!("Pipeline #{}", pipeline.id),
+                url: pipeline.web_url.clone(),
+            });
+        }
+        (status, failing)
+    }
+
+    /// Map GitLab merge request state to MergeStatus
+    fn map_gitlab_state(state: &str) -> MergeStatus {
+        match state.to_ascii_lowercase().as_str() {
+            "" | "opened" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" | "locked" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        }
+    }
+
+    fn parse_discussions(raw: &str, mr_url: &str) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let discussions: Vec<GlabDiscussion> = Self::parse_pages(raw, "discussions")?;
+
+        let mut comments: Vec<UnifiedPrComment> = discussions
+            .into_iter()
+            .flat_map(|d| d.notes)
+            // Skip system notes such as "added 1 commit"
+            .filter(|note| !note.system)
+            .map(|note| {
+                let author = note
+                    .author
+                    .and_then(|a| a.username)
+                    .unwrap_or_else(|| "unknown".to_string());
+                let created_at = note.created_at.unwrap_or_else(Utc::now);
+                let url = Some(format!("{mr_url}#note_{}", note.id));
+
+                match note
+                    .position
+                    .and_then(|p| Some((p.new_path.or(p.old_path)?, p.new_line, p.old_line)))
+                {
+                    Some((path, new_line, old_line)) => UnifiedPrComment::Review {
+                        id: note.id,
+                        author,
+                        author_association: None,
+                        body: note.body,
+                        created_at,
+                        url,
+                        path,
+                        line: new_line.or(old_line),
+                        side: Some(if new_line.is_some() { "RIGHT" } else { "LEFT" }.to_string()),
+                        diff_hunk: None,
+                    },
+                    None => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author,
+                        author_association: None,
+                        body: note.body,
+                        created_at,
+                        url,
+                    },
+                }
+            })
+            .collect();
+
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(host: &str, project_path: &str) -> GitLabRepoInfo {
+        GitLabRepoInfo {
+            host: host.to_string(),
+            project_path: project_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        assert_eq!(
+            GlabCli::parse_remote_url("https://gitlab.com/group/repo.git"),
+            Some(repo("gitlab.com", "group/repo"))
+        );
+        assert_eq!(
+            GlabCli::parse_remote_url("git@gitlab.company.com:group/sub/repo.git"),
+            Some(repo("gitlab.company.com", "group/sub/repo"))
+        );
+        assert_eq!(
+            GlabCli::parse_remote_url("ssh://git@git.internal:2222/team/repo.git"),
+            Some(repo("git.internal", "team/repo"))
+        );
+        assert_eq!(GlabCli::parse_remote_url("https://gitlab.com/repo"), None);
+    }
+
+    #[test]
+    fn test_parse_mr_url() {
+        assert_eq!(
+            GlabCli::parse_mr_url("https://gitlab.com/group/sub/repo/-/merge_requests/42"),
+            Some((repo("gitlab.com", "group/sub/repo"), 42))
+        );
+        assert_eq!(
+            GlabCli::parse_mr_url("https://git.internal/team/repo/-/merge_requests/7/diffs"),
+            Some((repo("git.internal", "team/repo"), 7))
+        );
+        assert_eq!(
+            GlabCli::parse_mr_url("https://gitlab.com/group/repo/-/issues/42"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_job_url() {
+        assert_eq!(
+            GlabCli::parse_job_url("https://gitlab.com/group/sub/repo/-/jobs/981"),
+            Some((repo("gitlab.com", "group/sub/repo"), 981))
+        );
+        assert_eq!(
+            GlabCli::parse_job_url("https://gitlab.com/group/repo/-/pipelines/9"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_api_id_encodes_namespace() {
+        assert_eq!(
+            repo("gitlab.com", "group/sub/repo").api_id(),
+            "group%2Fsub%2Frepo"
+        );
+    }
+
+    #[test]
+    fn test_map_gitlab_state() {
+        assert!(matches!(
+            GlabCli::map_gitlab_state("opened"),
+            MergeStatus::Open
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("merged"),
+            MergeStatus::Merged
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("locked"),
+            MergeStatus::Closed
+        ));
+        assert!(matches!(
+            GlabCli::map_gitlab_state("weird"),
+            MergeStatus::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_checks() {
+        let pipeline = |status: &str| GlabPipeline {
+            id: 9,
+            project_id: 3,
+            status: status.to_string(),
+            web_url: Some("https://gitlab.com/g/r/-/pipelines/9".to_string()),
+        };
+        let jobs: Vec<GlabJob> = serde_json::from_str(
+            r#"[
+                {"name": "test", "web_url": "https://gitlab.com/g/r/-/jobs/1"},
+                {"name": "flaky", "allow_failure": true}
+            ]"#,
+        )
+        .unwrap();
+
+        let (status, failing) = GlabCli::pipeline_checks(&pipeline("failed"), jobs);
+        assert_eq!(status, Some(CiStatus::Failure));
+        assert_eq!(
+            failing,
+            vec![PrCheck {
+                name: "test".to_string(),
+                url: Some("https://gitlab.com/g/r/-/jobs/1".to_string()),
+            }]
+        );
+
+        let (status, failing) = GlabCli::pipeline_checks(&pipeline("canceled"), vec![]);
+        assert_eq!(status, Some(CiStatus::Failure));
+        assert_eq!(failing[0].name, "Pipeline #9");
+
+        assert_eq!(
+            GlabCli::pipeline_checks(&pipeline("running"), vec![]),
+            (Some(CiStatus::Pending), vec![])
+        );
+        assert_eq!(
+            GlabCli::pipeline_checks(&pipeline("skipped"), vec![]),
+            (None, vec![])
+        );
+    }
+
+    #[test]
+    fn test_parse_pages() {
+        let raw = r#"[{"id": 1}][{"id": 2}, {"id": 3}]"#;
+        let projects: Vec<GlabProject> = GlabCli::parse_pages(raw, "project").unwrap();
+        assert_eq!(
+            projects.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_parse_discussions() {
+        let raw = r#"[
+            {"notes": [{"id": 3, "body": "Looks good", "author": {"username": "alice"},
+                        "created_at": "2025-01-02T00:00:00Z", "system": false}]},
+            {"notes": [{"id": 2, "body": "added 1 commit", "author": {"username": "bob"},
+                        "created_at": "2025-01-01T00:00:00Z", "system": true}]},
+            {"notes": [{"id": 1, "body": "Rename this", "author": {"username": "bob"},
+                        "created_at": "2025-01-01T00:00:00Z", "system": false,
+                        "position": {"new_path": "src/lib.rs", "old_path": "src/lib.rs",
+                                     "new_line": null, "old_line": 12}}]}
+        ]"#;
+        let comments =
+            GlabCli::parse_discussions(raw, "https://gitlab.com/g/r/-/merge_requests/5").unwrap();
+
+        assert_eq!(comments.len(), 2);
+        match &comments[0] {
+            UnifiedPrComment::Review {
+                id,
+                author,
+                path,
+                line,
+                side,
+                url,
+                ..
+            } => {
+                assert_eq!(*id, 1);
+                assert_eq!(author, "bob");
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(*line, Some(12));
+                assert_eq!(side.as_deref(), Some("LEFT"));
+                assert_eq!(
+                    url.as_deref(),
+                    Some("https://gitlab.com/g/r/-/merge_requests/5#note_1")
+                );
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+        assert!(matches!(
+            &comments[1],
+            UnifiedPrComment::General { id, author, .. } if id == "3" && author == "alice"
+        ));
+    }
+}
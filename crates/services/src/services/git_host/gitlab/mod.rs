@@ -0,0 +1,206 @@
+//! GitLab hosting service implementation.
+
+mod cli;
+
+use std::{path::Path, time::Duration};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+pub use cli::{GitLabRepoInfo, GlabCli, GlabCliError};
+use db::models::merge::{PrCheck, PullRequestInfo};
+use tokio::task;
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+};
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    glab_cli: GlabCli,
+}
+
+impl GitLabProvider {
+    pub fn new() -> Result<Self, GitHostError> {
+        Ok(Self {
+            glab_cli: GlabCli::new(),
+        })
+    }
+
+    fn get_repo_info(&self, remote_url: &str) -> Result<GitLabRepoInfo, GitHostError> {
+        GlabCli::parse_remote_url(remote_url).ok_or_else(|| {
+            GitHostError::Repository(format!(
+                "Could not parse GitLab project from URL: {remote_url}"
+            ))
+        })
+    }
+
+    /// Run a blocking `glab` call with the retry policy shared by all providers
+    async fn with_retry<T, F>(&self, what: &'static str, f: F) -> Result<T, GitHostError>
+    where
+        T: Send + 'static,
+        F: Fn(GlabCli) -> Result<T, GlabCliError> + Clone + Send + 'static,
+    {
+        (|| async {
+            let cli = self.glab_cli.clone();
+            let f = f.clone();
+            task::spawn_blocking(move || f(cli))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitLab CLI for {what}: {err}"
+                    ))
+                })?
+                .map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitLab API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+}
+
+impl From<GlabCliError> for GitHostError {
+    fn from(error: GlabCliError) -> Self {
+        match &error {
+            GlabCliError::AuthFailed(msg) => GitHostError::AuthFailed(msg.clone()),
+            GlabCliError::NotAvailable => GitHostError::CliNotInstalled {
+                provider: ProviderKind::GitLab,
+            },
+            GlabCliError::CommandFailed(msg) => {
+                let lower = msg.to_ascii_lowercase();
+                if lower.contains("403") || lower.contains("forbidden") {
+                    GitHostError::InsufficientPermissions(msg.clone())
+                } else if lower.contains("404") || lower.contains("not found") {
+                    GitHostError::RepoNotFoundOrNoAccess(msg.clone())
+                } else {
+                    GitHostError::PullRequest(msg.clone())
+                }
+            }
+            GlabCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target = self.get_repo_info(remote_url)?;
+        // Cross-fork merge requests are opened on the fork and point at the target project
+        let source = match &request.head_repo_url {
+            Some(head_url) => self.get_repo_info(head_url)?,
+            None => target.clone(),
+        };
+
+        let request = request.clone();
+        let head_branch = request.head_branch.clone();
+        let mr = self
+            .with_retry("MR creation", move |cli| {
+                cli.create_mr(&request, &source, &target)
+            })
+            .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {}",
+            mr.number, head_branch
+        );
+
+        Ok(mr)
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let url = pr_url.to_string();
+        self.with_retry("viewing MR", move |cli| cli.view_mr(&url))
+            .await
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        let branch = branch_name.to_string();
+        self.with_retry("listing MRs", move |cli| {
+            cli.list_mrs_for_branch(&repo_info, &branch)
+        })
+        .await
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        self.with_retry("fetching MR comments", move |cli| {
+            cli.get_mr_comments(&repo_info, pr_number)
+        })
+        .await
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        self.with_retry("listing open MRs", move |cli| cli.list_open_mrs(&repo_info))
+            .await
+    }
+
+    async fn update_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GitHostError> {
+        let url = pr_url.to_string();
+        let base = base_branch.to_string();
+        self.with_retry("retargeting MR", move |cli| {
+            cli.update_mr_target(&url, &base)
+        })
+        .await?;
+        info!("Retargeted GitLab MR {} to {}", pr_url, base_branch);
+        Ok(())
+    }
+
+    async fn current_user(&self, remote_url: &str) -> Result<Option<String>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url)?;
+        self.with_retry("fetching the current user", move |cli| {
+            cli.current_user(&repo_info)
+        })
+        .await
+    }
+
+    async fn get_check_log(&self, check: &PrCheck) -> Result<Option<String>, GitHostError> {
+        let Some((repo_info, job_id)) = check.url.as_deref().and_then(GlabCli::parse_job_url)
+        else {
+            return Ok(None);
+        };
+        self.with_retry("fetching job log", move |cli| {
+            cli.get_job_trace(&repo_info, job_id)
+        })
+        .await
+        .map(Some)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
@@ -2,20 +2,25 @@ mod detection;
 mod types;
 
 pub mod azure;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
 use async_trait::async_trait;
-use db::models::merge::PullRequestInfo;
-use detection::detect_provider_from_url;
+use db::models::merge::{PrCheck, PullRequestInfo};
+use detection::{detect_provider_from_url, hostname};
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment,
-    ProviderKind, ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostError, GiteaHost, OpenPrInfo, PrComment, PrCommentAuthor,
+    PrReviewComment, ProviderKind, ReviewCommentUser, SelfHostedGitHosts, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::AzureDevOpsProvider, gitea::GiteaProvider, github::GitHubProvider,
+    gitlab::GitLabProvider,
+};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -49,6 +54,21 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError>;
 
+    /// Point an open PR at another base branch
+    async fn update_pr_base(&self, _pr_url: &str, _base_branch: &str) -> Result<(), GitHostError> {
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    /// Login of the account the provider acts as, for providers that can tell
+    async fn current_user(&self, _remote_url: &str) -> Result<Option<String>, GitHostError> {
+        Ok(None)
+    }
+
+    /// Log of a failed CI check, for providers that can fetch it
+    async fn get_check_log(&self, _check: &PrCheck) -> Result<Option<String>, GitHostError> {
+        Ok(None)
+    }
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -56,13 +76,30 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
+    Gitea(GiteaProvider),
 }
 
 impl GitHostService {
-    pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
+    pub fn from_url(url: &str, self_hosted: &SelfHostedGitHosts) -> Result<Self, GitHostError> {
+        match detect_provider_from_url(url, self_hosted) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new()?)),
+            ProviderKind::Gitea => {
+                // Instances recognised by name alone are used anonymously over https
+                let host = hostname(&url.to_lowercase()).unwrap_or_default();
+                let gitea_host = self_hosted
+                    .gitea
+                    .iter()
+                    .find(|h| hostname(&h.url.to_lowercase()).as_deref() == Some(host.as_str()))
+                    .cloned()
+                    .unwrap_or_else(|| GiteaHost {
+                        url: format!("https://{host}"),
+                        token: None,
+                    });
+                Ok(Self::Gitea(GiteaProvider::new(&gitea_host)?))
+            }
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
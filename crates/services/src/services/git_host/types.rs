@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use db::models::merge::{CiStatus, PrCheck};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
@@ -8,6 +9,8 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    GitLab,
+    Gitea,
     Unknown,
 }
 
@@ -16,11 +19,33 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
+            ProviderKind::Gitea => write!(f, "Gitea"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+/// Hostnames of self-hosted git servers whose provider can't be told from the
+/// remote URL alone
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SelfHostedGitHosts {
+    #[serde(default)]
+    pub gitlab: Vec<String>,
+    /// Gitea and Forgejo instances, talked to over their REST API
+    #[serde(default)]
+    pub gitea: Vec<GiteaHost>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct GiteaHost {
+    /// Web URL of the instance, e.g. `https://git.company.com`
+    pub url: String,
+    /// Access token sent with every API request
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
@@ -143,3 +168,60 @@ pub struct OpenPrInfo {
     pub head_branch: String,
     pub base_branch: String,
 }
+
+/// Fold the results of individual checks into the combined CI status of a PR
+/// and the checks that failed. Any failure fails the PR; otherwise a check that
+/// is still running keeps it pending.
+pub(crate) fn summarize_checks(
+    checks: impl IntoIterator<Item = (CiStatus, PrCheck)>,
+) -> (Option<CiStatus>, Vec<PrCheck>) {
+    let mut combined = None;
+    let mut failing = Vec::new();
+    for (status, check) in checks {
+        combined = Some(match (combined, status) {
+            (Some(CiStatus::Failure), _) | (_, CiStatus::Failure) => CiStatus::Failure,
+            (Some(CiStatus::Pending), _) | (_, CiStatus::Pending) => CiStatus::Pending,
+            _ => CiStatus::Success,
+        });
+        if status == CiStatus::Failure {
+            failing.push(check);
+        }
+    }
+    (combined, failing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str) -> PrCheck {
+        PrCheck {
+            name: name.to_string(),
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_checks() {
+        assert_eq!(summarize_checks([]), (None, vec![]));
+        assert_eq!(
+            summarize_checks([
+                (CiStatus::Success, check("lint")),
+                (CiStatus::Pending, check("test")),
+            ]),
+            (Some(CiStatus::Pending), vec![])
+        );
+        assert_eq!(
+            summarize_checks([
+                (CiStatus::Failure, check("build")),
+                (CiStatus::Pending, check("test")),
+                (CiStatus::Success, check("lint")),
+            ]),
+            (Some(CiStatus::Failure), vec![check("build")])
+        );
+        assert_eq!(
+            summarize_checks([(CiStatus::Success, check("lint"))]),
+            (Some(CiStatus::Success), vec![])
+        );
+    }
+}
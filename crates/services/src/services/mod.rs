@@ -21,5 +21,9 @@ pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod search;
+pub mod task_schedule;
+pub mod transcript;
+pub mod usage;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -1,9 +1,12 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        merge::{CiStatus, Merge, MergeStatus, PrCheck, PrMerge, PullRequestInfo},
+        pr_follow_up_item::PrFollowUpItem,
+        project::Project,
+        repo::Repo,
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
     },
@@ -11,12 +14,16 @@ use db::{
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
+use tokio::{sync::RwLock, time::interval};
 use tracing::{debug, error, info};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    git_host::{self, GitHostError, GitHostProvider},
+    config::Config,
+    container::{ContainerError, ContainerService, ParentBranchChange},
+    events::EventService,
+    git::GitServiceError,
+    git_host::{self, GitHostError, GitHostProvider, GitHostService, UnifiedPrComment},
 };
 
 #[derive(Debug, Error)]
@@ -27,22 +34,35 @@ enum PrMonitorError {
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
 }
 
-/// Service to monitor PRs and update task status when they are merged
-pub struct PrMonitorService {
+/// Service to monitor PRs, tracking their CI status and updating the task when they are merged
+pub struct PrMonitorService<C> {
     db: DBService,
+    config: Arc<RwLock<Config>>,
+    container: C,
+    events: EventService,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
 }
 
-impl PrMonitorService {
+impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        config: Arc<RwLock<Config>>,
+        container: C,
+        events: EventService,
         analytics: Option<AnalyticsContext>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            config,
+            container,
+            events,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
         };
@@ -91,7 +111,9 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
+        let self_hosted_git_hosts = self.config.read().await.self_hosted_git_hosts.clone();
+        let git_host =
+            git_host::GitHostService::from_url(&pr_merge.pr_info.url, &self_hosted_git_hosts)?;
         let pr_status = git_host.get_pr_status(&pr_merge.pr_info.url).await?;
 
         debug!(
@@ -99,6 +121,32 @@ impl PrMonitorService {
             pr_merge.pr_info.number, pr_status.status
         );
 
+        let ci_changed = Merge::update_ci_status(
+            &self.db.pool,
+            pr_merge.id,
+            pr_status.ci_status,
+            &pr_status.failing_checks,
+        )
+        .await?;
+        if ci_changed {
+            debug!(
+                "PR #{} CI status: {:?}",
+                pr_merge.pr_info.number, pr_status.ci_status
+            );
+            self.events
+                .push_workspace_update(pr_merge.workspace_id)
+                .await?;
+        }
+
+        if matches!(&pr_status.status, MergeStatus::Open)
+            && let Err(e) = self.send_pr_feedback(pr_merge, &git_host, &pr_status).await
+        {
+            error!(
+                "Error sending feedback of PR #{} to the agent: {}",
+                pr_merge.pr_info.number, e
+            );
+        }
+
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
             // Update merge status with the latest information from git host
@@ -120,6 +168,33 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                self.container
+                    .start_unblocked_dependents(workspace.task_id)
+                    .await;
+
+                // Move the PRs stacked on this one onto the branch it went into
+                if let Some(repo) = Repo::find_by_id(&self.db.pool, pr_merge.repo_id).await? {
+                    match self
+                        .container
+                        .git()
+                        .get_branch_oid(&repo.path, &workspace.branch)
+                    {
+                        Ok(old_head) => {
+                            self.container
+                                .restack_children(
+                                    &workspace,
+                                    &repo,
+                                    ParentBranchChange::PrMerged { old_head },
+                                    &self_hosted_git_hosts,
+                                )
+                                .await
+                        }
+                        Err(e) => error!(
+                            "Failed to resolve branch '{}' for restacking: {}",
+                            workspace.branch, e
+                        ),
+                    }
+                }
 
                 // Archive workspace unless pinned
                 if !workspace.pinned {
@@ -145,4 +220,311 @@ impl PrMonitorService {
 
         Ok(())
     }
+
+    /// Start a follow-up with review comments and failed checks the agent
+    /// hasn't seen yet, for projects that opted in
+    async fn send_pr_feedback(
+        &self,
+        pr_merge: &PrMerge,
+        git_host: &GitHostService,
+        pr_status: &PullRequestInfo,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(());
+        };
+        let Some(task) = workspace.parent_task(pool).await? else {
+            return Ok(());
+        };
+        let Some(project) = Project::find_by_id(pool, task.project_id).await? else {
+            return Ok(());
+        };
+        if !project.auto_follow_up_pr || workspace.archived {
+            return Ok(());
+        }
+        let Some(repo) = Repo::find_by_id(pool, pr_merge.repo_id).await? else {
+            return Ok(());
+        };
+
+        let handled = PrFollowUpItem::find_keys_for_merge(pool, pr_merge.id).await?;
+        // Feedback already on a PR when its project opted in counts as handled.
+        // The baseline is taken as of the opt-in: comments written before it
+        // and the checks that had failed by the last check before it.
+        let opt_in = Project::find_auto_follow_up_pr_since(pool, project.id)
+            .await?
+            .filter(|since| pr_merge.created_at < *since)
+            .map(|since| (since, format!("opt-in:{}", since.timestamp_millis())))
+            .filter(|(_, key)| !handled.contains(key));
+
+        let remote = self
+            .container
+            .git()
+            .resolve_remote_for_branch(&repo.path, &pr_merge.target_branch_name)?;
+        let self_login = git_host
+            .current_user(&remote.url)
+            .await
+            .unwrap_or_else(|e| {
+                debug!("Could not resolve the git host user: {}", e);
+                None
+            });
+        let (baseline_comments, comments): (Vec<UnifiedPrComment>, Vec<UnifiedPrComment>) =
+            git_host
+                .get_pr_comments(&repo.path, &remote.url, pr_merge.pr_info.number)
+                .await?
+                .into_iter()
+                .filter(|comment| !handled.contains(&comment_key(comment)))
+                .filter(|comment| {
+                    let author = comment_author(comment);
+                    !is_bot_author(author) && self_login.as_deref() != Some(author)
+                })
+                .partition(|comment| {
+                    opt_in
+                        .as_ref()
+                        .is_some_and(|(since, _)| comment.created_at() < *since)
+                });
+
+        let mut baseline_checks = Vec::new();
+        let mut failed_checks = Vec::new();
+        if pr_status.ci_status == Some(CiStatus::Failure) {
+            for check in &pr_status.failing_checks {
+                if handled.contains(&check_key(check)) {
+                    continue;
+                }
+                if opt_in.is_some() && pr_merge.pr_info.failing_checks.contains(check) {
+                    baseline_checks.push(check_key(check));
+                    continue;
+                }
+                let log = git_host.get_check_log(check).await.unwrap_or_else(|e| {
+                    debug!("Could not fetch log of check {}: {}", check.name, e);
+                    None
+                });
+                failed_checks.push((check.clone(), log));
+            }
+        }
+
+        if let Some((_, opt_in_key)) = opt_in {
+            let keys: Vec<String> = baseline_comments
+                .iter()
+                .map(comment_key)
+                .chain(baseline_checks)
+                .chain([opt_in_key])
+                .collect();
+            PrFollowUpItem::record(pool, pr_merge.id, &keys).await?;
+        }
+
+        let keys: Vec<String> = comments
+            .iter()
+            .map(comment_key)
+            .chain(failed_checks.iter().map(|(check, _)| check_key(check)))
+            .collect();
+
+        if comments.is_empty() && failed_checks.is_empty() {
+            return Ok(());
+        }
+
+        // Keys are only recorded once the turn starts, so feedback waiting
+        // behind a turn that fails is not lost
+        let prompt = pr_feedback_prompt(&comments, &failed_checks);
+        if !self.container.start_follow_up(&workspace, prompt).await? {
+            debug!(
+                "Workspace {} can't take PR feedback right now, retrying on the next check",
+                workspace.id
+            );
+            return Ok(());
+        }
+        PrFollowUpItem::record(pool, pr_merge.id, &keys).await?;
+
+        info!(
+            "Sent {} comments and {} failed checks of PR #{} to workspace {}",
+            comments.len(),
+            failed_checks.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+        Ok(())
+    }
+}
+
+/// Start of the prompts that carry PR feedback, used to push the agent's
+/// changes once it is done
+pub const PR_FOLLOW_UP_PREFIX: &str = "New feedback arrived on the pull request.";
+
+const CHECK_LOG_TAIL_LINES: usize = 100;
+
+fn comment_key(comment: &UnifiedPrComment) -> String {
+    match comment {
+        UnifiedPrComment::General { id, .. } => format!("comment:{id}"),
+        UnifiedPrComment::Review { id, .. } => format!("review:{id}"),
+    }
+}
+
+/// Checks are keyed by their URL, which points at a single run on every provider
+fn check_key(check: &PrCheck) -> String {
+    format!("check:{}", check.url.as_deref().unwrap_or(&check.name))
+}
+
+fn comment_author(comment: &UnifiedPrComment) -> &str {
+    match comment {
+        UnifiedPrComment::General { author, .. } | UnifiedPrComment::Review { author, .. } => {
+            author
+        }
+    }
+}
+
+/// Bot accounts: GitHub apps (`name[bot]`), GitLab project and group access
+/// tokens (`project_1_bot_…`, `group_1_bot_…`) and the usual `-bot` naming
+fn is_bot_author(author: &str) -> bool {
+    let author = author.to_ascii_lowercase();
+    author.ends_with("[bot]")
+        || author.ends_with("-bot")
+        || author.ends_with("_bot")
+        || ((author.starts_with("project_") || author.starts_with("group_"))
+            && author.contains("_bot"))
+}
+
+fn pr_feedback_prompt(
+    comments: &[UnifiedPrComment],
+    failed_checks: &[(PrCheck, Option<String>)],
+) -> String {
+    let mut prompt = format!(
+        "{PR_FOLLOW_UP_PREFIX} Address it with focused changes; they are pushed to the PR when you are done."
+    );
+
+    // Review comments on the same line form a thread
+    let mut threads: Vec<((&str, Option<i64>), Vec<&UnifiedPrComment>)> = Vec::new();
+    let mut general = Vec::new();
+    for comment in comments {
+        match comment {
+            UnifiedPrComment::Review { path, line, .. } => {
+                let location = (path.as_str(), *line);
+                match threads.iter_mut().find(|(key, _)| *key == location) {
+                    Some((_, thread)) => thread.push(comment),
+                    None => threads.push((location, vec![comment])),
+                }
+            }
+            UnifiedPrComment::General { .. } => general.push(comment),
+        }
+    }
+
+    if !threads.is_empty() {
+        prompt.push_str("\n\n## Review comments");
+        for ((path, line), thread) in threads {
+            match line {
+                Some(line) => prompt.push_str(&format!("\n\n### `{path}` line {line}")),
+                None => prompt.push_str(&format!("\n\n### `{path}`")),
+            }
+            for comment in thread {
+                push_comment(&mut prompt, comment);
+            }
+        }
+    }
+
+    if !general.is_empty() {
+        prompt.push_str("\n\n## Comments");
+        for comment in general {
+            push_comment(&mut prompt, comment);
+        }
+    }
+
+    if !failed_checks.is_empty() {
+        prompt.push_str("\n\n## Failing CI checks");
+        for (check, log) in failed_checks {
+            prompt.push_str(&format!("\n\n### {}", check.name));
+            if let Some(url) = &check.url {
+                prompt.push_str(&format!("\n{url}"));
+            }
+            if let Some(log) = log {
+                let lines: Vec<&str> = log.lines().collect();
+                let tail = lines[lines.len().saturating_sub(CHECK_LOG_TAIL_LINES)..].join("\n");
+                prompt.push_str(&format!("\n\nEnd of the log:\n```\n{tail}\n```"));
+            }
+        }
+    }
+
+    prompt
+}
+
+fn push_comment(prompt: &mut String, comment: &UnifiedPrComment) {
+    let (author, body) = match comment {
+        UnifiedPrComment::General { author, body, .. }
+        | UnifiedPrComment::Review { author, body, .. } => (author, body),
+    };
+    prompt.push_str(&format!("\n\n**{author}:**\n{}", body.trim()));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn review(id: i64, path: &str, line: i64, author: &str, body: &str) -> UnifiedPrComment {
+        UnifiedPrComment::Review {
+            id,
+            author: author.to_string(),
+            author_association: None,
+            body: body.to_string(),
+            created_at: Utc::now(),
+            url: None,
+            path: path.to_string(),
+            line: Some(line),
+            side: Some("RIGHT".to_string()),
+            diff_hunk: None,
+        }
+    }
+
+    #[test]
+    fn test_pr_feedback_prompt() {
+        let comments = vec![
+            review(1, "src/lib.rs", 10, "alice", "Rename this"),
+            UnifiedPrComment::General {
+                id: "c1".to_string(),
+                author: "bob".to_string(),
+                author_association: None,
+                body: "Please add tests".to_string(),
+                created_at: Utc::now(),
+                url: None,
+            },
+            review(2, "src/lib.rs", 10, "carol", "Agreed"),
+        ];
+        let log = (1..=150)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let checks = vec![(
+            PrCheck {
+                name: "test".to_string(),
+                url: Some("https://ci/jobs/1".to_string()),
+            },
+            Some(log),
+        )];
+
+        let prompt = pr_feedback_prompt(&comments, &checks);
+        assert!(prompt.starts_with(PR_FOLLOW_UP_PREFIX));
+        assert_eq!(prompt.matches("### `src/lib.rs` line 10").count(), 1);
+        assert!(prompt.contains("**alice:**\nRename this\n\n**carol:**\nAgreed"));
+        assert!(prompt.contains("## Comments\n\n**bob:**\nPlease add tests"));
+        assert!(prompt.contains("### test\nhttps://ci/jobs/1"));
+        assert!(prompt.contains("line 150"));
+        assert!(!prompt.contains("line 50\n"));
+    }
+
+    #[test]
+    fn test_follow_up_keys() {
+        assert_eq!(comment_key(&review(7, "a.rs", 1, "alice", "x")), "review:7");
+        let check = PrCheck {
+            name: "lint".to_string(),
+            url: None,
+        };
+        assert_eq!(check_key(&check), "check:lint");
+    }
+
+    #[test]
+    fn test_is_bot_author() {
+        assert!(is_bot_author("dependabot[bot]"));
+        assert!(is_bot_author("project_42_bot_3f1c"));
+        assert!(is_bot_author("renovate-bot"));
+        assert!(!is_bot_author("alice"));
+        assert!(!is_bot_author("abbot"));
+    }
 }
@@ -0,0 +1,94 @@
+use db::models::search::SearchSource;
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+
+/// Turn free text typed by the user into an FTS5 match expression. Every word
+/// must match, the last one as a prefix so results show up while typing. FTS5
+/// operators in the input are treated as plain words.
+pub fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let (last, rest) = terms.split_last()?;
+
+    let mut expression = rest.join(" ");
+    if !expression.is_empty() {
+        expression.push(' ');
+    }
+    expression.push_str(last);
+    expression.push('*');
+    Some(expression)
+}
+
+/// The part of a normalized log entry worth indexing, if any
+pub fn searchable_entry(entry: &NormalizedEntry) -> Option<(SearchSource, &str)> {
+    let (source, text) = match &entry.entry_type {
+        NormalizedEntryType::AssistantMessage => (SearchSource::AssistantMessage, &entry.content),
+        NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, .. },
+            ..
+        } => (SearchSource::Command, command),
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then_some((source, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_match_expression_quotes_terms() {
+        assert_eq!(
+            match_expression("payment webhook").as_deref(),
+            Some("\"payment\" \"webhook\"*")
+        );
+        assert_eq!(
+            match_expression("  say \"hi\" OR ").as_deref(),
+            Some("\"say\" \"\"\"hi\"\"\" \"OR\"*")
+        );
+        assert_eq!(match_expression("   "), None);
+    }
+
+    #[test]
+    fn test_searchable_entry() {
+        let message = entry(NormalizedEntryType::AssistantMessage, " Fixed the webhook ");
+        assert_eq!(
+            searchable_entry(&message),
+            Some((SearchSource::AssistantMessage, "Fixed the webhook"))
+        );
+
+        let command = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                },
+                status: ToolStatus::Success,
+            },
+            "cargo test",
+        );
+        assert_eq!(
+            searchable_entry(&command),
+            Some((SearchSource::Command, "cargo test"))
+        );
+
+        let thinking = entry(NormalizedEntryType::Thinking, "hmm");
+        assert_eq!(searchable_entry(&thinking), None);
+        let empty = entry(NormalizedEntryType::AssistantMessage, "  ");
+        assert_eq!(searchable_entry(&empty), None);
+    }
+}
@@ -0,0 +1,457 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone,
+    Timelike, Utc,
+};
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        task::{CreateTask, Task},
+        task_schedule::TaskSchedule,
+        workspace::Workspace,
+    },
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CronError {
+    #[error("Expected 5 fields (minute hour day-of-month month day-of-week), got {0}")]
+    FieldCount(usize),
+    #[error("Invalid {field} field '{value}'")]
+    InvalidField { field: &'static str, value: String },
+}
+
+/// Five field cron expression: minute, hour, day of month, month and day of
+/// week. Fields take `*`, numbers, `a-b` ranges, `/n` steps and comma separated
+/// lists. Day of week runs 0-7 with both 0 and 7 meaning Sunday. When both day
+/// fields are restricted a day matching either fires, as in classic cron; a
+/// field starting with `*`, like `*/2`, does not count as restricted, so the
+/// day then has to match both.
+/// `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are accepted too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+/// How far ahead to look for the next firing before giving up, e.g. for
+/// `0 0 30 2 *`
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(CronError::FieldCount(fields.len()));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, "day-of-week", 0, 7)?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days_of_month: parse_field(day_of_month, "day-of-month", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// First firing strictly after `after`, in the server's local time
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut local = after.with_timezone(&Local).naive_local();
+        loop {
+            let next = self.next_after_naive(local)?;
+            // Times skipped by a DST change do not exist locally; move on
+            match Local.from_local_datetime(&next).earliest() {
+                Some(time) => return Some(time.with_timezone(&Utc)),
+                None => local = next,
+            }
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after_naive(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let limit = after + ChronoDuration::days(MAX_LOOKAHEAD_DAYS);
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+
+        while time <= limit {
+            if !matches(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !matches(self.hours, time.hour()) {
+                time = time.date().and_hms_opt(time.hour(), 0, 0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if !matches(self.minutes, time.minute()) {
+                time += ChronoDuration::minutes(1);
+                continue;
+            }
+            return Some(time);
+        }
+
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day_of_month = matches(self.days_of_month, date.day());
+        let day_of_week = matches(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn matches(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn parse_field(field: &str, name: &'static str, min: u32, max: u32) -> Result<u64, CronError> {
+    let invalid = || CronError::InvalidField {
+        field: name,
+        value: field.to_string(),
+    };
+    let number = |value: &str| -> Result<u32, CronError> {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(invalid)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (number(start)?, number(end)?)
+        } else {
+            let start = number(range)?;
+            // `5/15` means every 15 starting at 5
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
+
+#[derive(Debug, Error)]
+enum TaskScheduleError {
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("Invalid schedule: {0}")]
+    Cron(#[from] CronError),
+}
+
+/// Service that starts task attempts when their schedules fire
+pub struct TaskScheduleService<C> {
+    db: DBService,
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> TaskScheduleService<C> {
+    pub async fn spawn(db: DBService, container: C) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            poll_interval: Duration::from_secs(30),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task schedule service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running task schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), TaskScheduleError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.db.pool, now).await?;
+
+        if due.is_empty() {
+            debug!("No task schedules due");
+            return Ok(());
+        }
+
+        for schedule in due {
+            if let Err(e) = self.run_schedule(&schedule, now).await {
+                error!("Error running task schedule {}: {}", schedule.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_schedule(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), TaskScheduleError> {
+        let pool = &self.db.pool;
+        // Computed from now rather than the missed slot so downtime does not
+        // cause a burst of catch-up runs
+        let next_run_at = CronSchedule::from_str(&schedule.cron)?.next_after(now);
+
+        if let Some(workspace_id) = schedule.last_workspace_id
+            && ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool,
+                workspace_id,
+            )
+            .await?
+        {
+            info!(
+                "Skipping run of task schedule {}: previous run in workspace {} is still running",
+                schedule.id, workspace_id
+            );
+            TaskSchedule::set_next_run_at(pool, schedule.id, next_run_at).await?;
+            return Ok(());
+        }
+
+        let workspace = match self.start_scheduled_attempt(schedule).await {
+            Ok(Some(workspace)) => workspace,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                // A failed run waits for the next slot instead of retrying on
+                // every poll
+                TaskSchedule::set_next_run_at(pool, schedule.id, next_run_at).await?;
+                return Err(e);
+            }
+        };
+        TaskSchedule::record_run(pool, schedule.id, now, workspace.id, next_run_at).await?;
+
+        info!(
+            "Task schedule {} started attempt {} for task {}",
+            schedule.id, workspace.id, workspace.task_id
+        );
+        Ok(())
+    }
+
+    /// Start an attempt of the schedule's task, or of a new copy of it. The
+    /// copy is removed again if the attempt cannot be created.
+    async fn start_scheduled_attempt(
+        &self,
+        schedule: &TaskSchedule,
+    ) -> Result<Option<Workspace>, TaskScheduleError> {
+        let pool = &self.db.pool;
+        let Some(template) = Task::find_by_id(pool, schedule.task_id).await? else {
+            return Ok(None);
+        };
+        let task = if schedule.create_new_task {
+            Task::create(
+                pool,
+                &CreateTask::from_title_description(
+                    template.project_id,
+                    template.title.clone(),
+                    template.description.clone(),
+                ),
+                Uuid::new_v4(),
+            )
+            .await?
+        } else {
+            template
+        };
+
+        match self
+            .container
+            .start_task_attempt(
+                &task,
+                schedule.executor_profile_id.0.clone(),
+                &schedule.repos.0,
+            )
+            .await
+        {
+            Ok(workspace) => Ok(Some(workspace)),
+            Err(e) => {
+                if schedule.create_new_task
+                    && let Err(delete_err) = Task::delete(pool, task.id).await
+                {
+                    error!(
+                        "Failed to remove task {} of failed schedule run: {}",
+                        task.id, delete_err
+                    );
+                }
+                Err(e.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> NaiveDateTime {
+        CronSchedule::from_str(expression)
+            .unwrap()
+            .next_after_naive(at(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_weekdays_at_six() {
+        // 2026-02-06 is a Friday
+        assert_eq!(
+            next("0 6 * * 1-5", "2026-02-06 05:59"),
+            at("2026-02-06 06:00")
+        );
+        assert_eq!(
+            next("0 6 * * 1-5", "2026-02-06 06:00"),
+            at("2026-02-09 06:00")
+        );
+    }
+
+    #[test]
+    fn test_steps_lists_and_aliases() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-02-06 10:07"),
+            at("2026-02-06 10:15")
+        );
+        assert_eq!(
+            next("5/20 * * * *", "2026-02-06 10:26"),
+            at("2026-02-06 10:45")
+        );
+        assert_eq!(
+            next("0 9,17 * * *", "2026-02-06 10:00"),
+            at("2026-02-06 17:00")
+        );
+        assert_eq!(next("@monthly", "2026-02-06 10:00"), at("2026-03-01 00:00"));
+        assert_eq!(
+            next("0 0 * * 7", "2026-02-06 10:00"),
+            at("2026-02-08 00:00")
+        );
+    }
+
+    #[test]
+    fn test_day_fields_match_either_when_both_set() {
+        // The 13th or any Friday
+        assert_eq!(
+            next("0 0 13 * 5", "2026-02-06 10:00"),
+            at("2026-02-13 00:00")
+        );
+        assert_eq!(
+            next("0 0 13 * 5", "2026-02-07 10:00"),
+            at("2026-02-13 00:00")
+        );
+        assert_eq!(
+            next("0 0 13 * 5", "2026-02-13 10:00"),
+            at("2026-02-20 00:00")
+        );
+    }
+
+    #[test]
+    fn test_day_step_over_wildcard_is_not_restricted() {
+        // Odd days of the month that are also Mondays, skipping Saturday the 7th
+        assert_eq!(
+            next("0 0 */2 * 1", "2026-02-06 10:00"),
+            at("2026-02-09 00:00")
+        );
+        // The 13th when it falls on a Sunday, Tuesday, Thursday or Saturday
+        assert_eq!(
+            next("0 0 13 * */2", "2026-02-06 10:00"),
+            at("2026-06-13 00:00")
+        );
+    }
+
+    #[test]
+    fn test_impossible_date_never_fires() {
+        let schedule = CronSchedule::from_str("0 0 30 2 *").unwrap();
+        assert_eq!(schedule.next_after_naive(at("2026-02-06 10:00")), None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_expressions() {
+        assert_eq!(
+            CronSchedule::from_str("0 6 * *"),
+            Err(CronError::FieldCount(4))
+        );
+        for expression in [
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(CronSchedule::from_str(expression).is_err(), "{expression}");
+        }
+    }
+}
@@ -0,0 +1,628 @@
+//! Agent transcripts of sessions, exported as Markdown, JSON or HTML.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use executors::logs::{
+    ActionType, CommandExitStatus, FileChange, NormalizedEntry, NormalizedEntryType,
+    ToolResultValueType, ToolStatus,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::container::{ContainerError, ContainerService};
+
+/// Version of the JSON transcript schema, bumped on breaking changes
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Json,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "text/markdown; charset=utf-8",
+            TranscriptFormat::Json => "application/json",
+            TranscriptFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Json => "json",
+            TranscriptFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct Transcript {
+    pub schema_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub sessions: Vec<TranscriptSession>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptSession {
+    pub id: Uuid,
+    pub executor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+/// One coding agent turn with its conversation and the commits it produced
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptTurn {
+    pub execution_process_id: Uuid,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub prompt: Option<String>,
+    pub summary: Option<String>,
+    pub entries: Vec<NormalizedEntry>,
+    pub diffs: Vec<TranscriptDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptDiff {
+    pub repo_name: String,
+    pub from_commit: String,
+    pub to_commit: String,
+    pub unified_diff: String,
+}
+
+impl Transcript {
+    /// Collect the coding agent turns of the given sessions of a workspace,
+    /// reading their stored entries. Logs are only replayed when there are
+    /// none, without recreating the workspace's worktrees.
+    pub async fn build<C: ContainerService + Sync + ?Sized>(
+        container: &C,
+        task: &Task,
+        workspace: &Workspace,
+        sessions: &[Session],
+    ) -> Result<Self, ContainerError> {
+        let pool = &container.db().pool;
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+
+        let mut transcript_sessions = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            let processes = ExecutionProcess::find_by_session_id(pool, session.id, false).await?;
+            let mut turns = Vec::new();
+            for process in processes
+                .into_iter()
+                .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+            {
+                let agent_turn =
+                    CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+                let entries = container
+                    .normalized_entries(&process.id)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(_, entry)| entry)
+                    .collect();
+
+                let repo_states =
+                    ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id)
+                        .await?;
+                let diffs = repo_states
+                    .iter()
+                    .filter_map(|state| {
+                        let repo = repos.iter().find(|repo| repo.id == state.repo_id)?;
+                        let from_commit = state.before_head_commit.clone()?;
+                        let to_commit = state.after_head_commit.clone()?;
+                        if from_commit == to_commit {
+                            return None;
+                        }
+                        // Worktrees share their objects with the main repository
+                        let unified_diff = container
+                            .git()
+                            .get_unified_diff_between(&repo.path, &from_commit, &to_commit)
+                            .inspect_err(|e| {
+                                tracing::warn!(
+                                    "Failed to diff turn {} in {}: {}",
+                                    process.id,
+                                    repo.name,
+                                    e
+                                )
+                            })
+                            .ok()?;
+                        (!unified_diff.is_empty()).then(|| TranscriptDiff {
+                            repo_name: repo.name.clone(),
+                            from_commit,
+                            to_commit,
+                            unified_diff,
+                        })
+                    })
+                    .collect();
+
+                let (prompt, summary) = agent_turn
+                    .map(|turn| (turn.prompt, turn.summary))
+                    .unwrap_or_default();
+                turns.push(TranscriptTurn {
+                    execution_process_id: process.id,
+                    status: process.status,
+                    started_at: process.started_at,
+                    completed_at: process.completed_at,
+                    prompt,
+                    summary,
+                    entries,
+                    diffs,
+                });
+            }
+            transcript_sessions.push(TranscriptSession {
+                id: session.id,
+                executor: session.executor.clone(),
+                created_at: session.created_at,
+                turns,
+            });
+        }
+
+        Ok(Self {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            exported_at: Utc::now(),
+            task_id: task.id,
+            task_title: task.title.clone(),
+            workspace_id: workspace.id,
+            branch: workspace.branch.clone(),
+            sessions: transcript_sessions,
+        })
+    }
+
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, serde_json::Error> {
+        match format {
+            TranscriptFormat::Markdown => Ok(self.to_markdown()),
+            TranscriptFormat::Json => serde_json::to_string_pretty(self),
+            TranscriptFormat::Html => Ok(self.to_html()),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n\n", self.task_title);
+        md.push_str(&format!(
+            "- Workspace: `{}`\n- Branch: `{}`\n- Exported: {}\n",
+            self.workspace_id,
+            self.branch,
+            self.exported_at.to_rfc3339()
+        ));
+        for (session_idx, session) in self.sessions.iter().enumerate() {
+            md.push_str(&format!(
+                "\n## Session {} ({})\n",
+                session_idx + 1,
+                session.executor.as_deref().unwrap_or("unknown agent")
+            ));
+            for (turn_idx, turn) in session.turns.iter().enumerate() {
+                md.push_str(&format!(
+                    "\n### Turn {} ({}, {})\n",
+                    turn_idx + 1,
+                    status_label(&turn.status),
+                    turn.started_at.to_rfc3339()
+                ));
+                for block in turn_blocks(turn) {
+                    md.push('\n');
+                    md.push_str(&block.to_markdown());
+                    md.push('\n');
+                }
+            }
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&self.task_title));
+        body.push_str(&format!(
+            "<ul class=\"meta\"><li>Workspace: <code>{}</code></li><li>Branch: <code>{}</code></li><li>Exported: {}</li></ul>\n",
+            self.workspace_id,
+            escape_html(&self.branch),
+            self.exported_at.to_rfc3339()
+        ));
+        for (session_idx, session) in self.sessions.iter().enumerate() {
+            body.push_str(&format!(
+                "<section class=\"session\">\n<h2>Session {} ({})</h2>\n",
+                session_idx + 1,
+                escape_html(session.executor.as_deref().unwrap_or("unknown agent"))
+            ));
+            for (turn_idx, turn) in session.turns.iter().enumerate() {
+                body.push_str(&format!(
+                    "<article class=\"turn\">\n<h3>Turn {} ({}, {})</h3>\n",
+                    turn_idx + 1,
+                    status_label(&turn.status),
+                    turn.started_at.to_rfc3339()
+                ));
+                for block in turn_blocks(turn) {
+                    body.push_str(&block.to_html());
+                }
+                body.push_str("</article>\n");
+            }
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<main>\n{body}</main>\n</body>\n</html>\n",
+            escape_html(&self.task_title)
+        )
+    }
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:0;background:#fafafa;color:#1f2328}\
+main{max-width:960px;margin:0 auto;padding:24px}\
+.meta{color:#57606a}\
+.turn{border-top:1px solid #d0d7de;margin-top:24px}\
+.entry{margin:12px 0;padding:8px 12px;border-left:3px solid #d0d7de;background:#fff}\
+.entry.user{border-color:#0969da}.entry.assistant{border-color:#1a7f37}\
+.entry.tool{border-color:#8250df}.entry.error{border-color:#cf222e}.entry.diff{border-color:#9a6700}\
+.heading{font-weight:600;margin-bottom:4px}\
+.text{white-space:pre-wrap}\
+pre{background:#f6f8fa;padding:8px;overflow-x:auto}";
+
+/// A rendered piece of a turn, shared by the Markdown and HTML output
+struct Block {
+    kind: &'static str,
+    heading: String,
+    parts: Vec<Part>,
+    collapsed: bool,
+}
+
+enum Part {
+    Text(String),
+    Code { lang: &'static str, content: String },
+}
+
+impl Block {
+    fn new(kind: &'static str, heading: impl Into<String>, parts: Vec<Part>) -> Self {
+        Self {
+            kind,
+            heading: heading.into(),
+            parts,
+            collapsed: false,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Code { lang, content } => fenced(lang, content),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if self.collapsed {
+            format!(
+                "<details>\n<summary>{}</summary>\n\n{parts}\n\n</details>",
+                self.heading
+            )
+        } else if parts.is_empty() {
+            format!("**{}**", self.heading)
+        } else {
+            format!("**{}**\n\n{parts}", self.heading)
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let parts: String = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => format!("<div class=\"text\">{}</div>", escape_html(text)),
+                Part::Code { lang, content } => format!(
+                    "<pre><code class=\"language-{lang}\">{}</code></pre>",
+                    escape_html(content)
+                ),
+            })
+            .collect();
+        let heading = escape_html(&self.heading);
+        if self.collapsed {
+            format!(
+                "<details class=\"entry {}\"><summary class=\"heading\">{heading}</summary>{parts}</details>\n",
+                self.kind
+            )
+        } else {
+            format!(
+                "<div class=\"entry {}\"><div class=\"heading\">{heading}</div>{parts}</div>\n",
+                self.kind
+            )
+        }
+    }
+}
+
+fn turn_blocks(turn: &TranscriptTurn) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    if let Some(prompt) = &turn.prompt {
+        blocks.push(Block::new("user", "User", vec![Part::Text(prompt.clone())]));
+    }
+    blocks.extend(
+        turn.entries
+            .iter()
+            // The prompt is often echoed back as the first entry
+            .filter(|entry| {
+                !(matches!(entry.entry_type, NormalizedEntryType::UserMessage)
+                    && turn.prompt.as_deref() == Some(entry.content.as_str()))
+            })
+            .filter_map(entry_block),
+    );
+    for diff in &turn.diffs {
+        blocks.push(Block::new(
+            "diff",
+            format!(
+                "Changes in {} ({}..{})",
+                diff.repo_name,
+                short_sha(&diff.from_commit),
+                short_sha(&diff.to_commit)
+            ),
+            vec![Part::Code {
+                lang: "diff",
+                content: diff.unified_diff.clone(),
+            }],
+        ));
+    }
+    blocks
+}
+
+fn entry_block(entry: &NormalizedEntry) -> Option<Block> {
+    let text = || vec![Part::Text(entry.content.clone())];
+    let block = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => Block::new("user", "User", text()),
+        NormalizedEntryType::UserFeedback { denied_tool } => {
+            Block::new("user", format!("User feedback on {denied_tool}"), text())
+        }
+        NormalizedEntryType::AssistantMessage => Block::new("assistant", "Assistant", text()),
+        NormalizedEntryType::Thinking => Block {
+            collapsed: true,
+            ..Block::new("thinking", "Thinking", text())
+        },
+        NormalizedEntryType::SystemMessage => Block::new("system", "System", text()),
+        NormalizedEntryType::ErrorMessage { .. } => Block::new("error", "Error", text()),
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+            status,
+        } => Block::new(
+            "tool",
+            format!("Tool: {tool_name} ({})", tool_status_label(status)),
+            action_parts(action_type, &entry.content),
+        ),
+        NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. }
+        | NormalizedEntryType::TokenUsageInfo(_) => return None,
+    };
+    Some(block)
+}
+
+fn action_parts(action: &ActionType, content: &str) -> Vec<Part> {
+    let text = Part::Text;
+    match action {
+        ActionType::FileRead { path } => vec![text(format!("Read {path}"))],
+        ActionType::FileEdit { path, changes } => {
+            let mut parts = vec![text(format!("Edited {path}"))];
+            for change in changes {
+                parts.push(match change {
+                    FileChange::Write { content } => Part::Code {
+                        lang: "",
+                        content: content.clone(),
+                    },
+                    FileChange::Delete => text("Deleted the file".to_string()),
+                    FileChange::Rename { new_path } => text(format!("Renamed to {new_path}")),
+                    FileChange::Edit { unified_diff, .. } => Part::Code {
+                        lang: "diff",
+                        content: unified_diff.clone(),
+                    },
+                });
+            }
+            parts
+        }
+        ActionType::CommandRun { command, result } => {
+            let mut parts = vec![Part::Code {
+                lang: "sh",
+                content: command.clone(),
+            }];
+            if let Some(result) = result {
+                if let Some(output) = result.output.as_ref().filter(|o| !o.trim().is_empty()) {
+                    parts.push(Part::Code {
+                        lang: "",
+                        content: output.clone(),
+                    });
+                }
+                match &result.exit_status {
+                    Some(CommandExitStatus::ExitCode { code }) => {
+                        parts.push(text(format!("Exit code: {code}")))
+                    }
+                    Some(CommandExitStatus::Success { success }) => parts.push(text(
+                        if *success { "Succeeded" } else { "Failed" }.to_string(),
+                    )),
+                    None => {}
+                }
+            }
+            parts
+        }
+        ActionType::Search { query } => vec![text(format!("Searched for {query}"))],
+        ActionType::WebFetch { url } => vec![text(format!("Fetched {url}"))],
+        ActionType::Tool {
+            arguments, result, ..
+        } => {
+            let mut parts = vec![text(content.to_string())];
+            if let Some(arguments) = arguments {
+                parts.push(Part::Code {
+                    lang: "json",
+                    content: serde_json::to_string_pretty(arguments).unwrap_or_default(),
+                });
+            }
+            if let Some(result) = result {
+                parts.push(match (&result.r#type, result.value.as_str()) {
+                    (ToolResultValueType::Markdown, Some(markdown)) => text(markdown.to_string()),
+                    _ => Part::Code {
+                        lang: "json",
+                        content: serde_json::to_string_pretty(&result.value).unwrap_or_default(),
+                    },
+                });
+            }
+            parts
+        }
+        ActionType::TaskCreate { description } => vec![text(format!("Task: {description}"))],
+        ActionType::PlanPresentation { plan } => vec![text(plan.clone())],
+        ActionType::TodoManagement { todos, operation } => {
+            let list = todos
+                .iter()
+                .map(|todo| {
+                    let done = if todo.status == "completed" { "x" } else { " " };
+                    format!("- [{done}] {}", todo.content)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            vec![text(format!("Todos ({operation}):\n{list}"))]
+        }
+        ActionType::Other { description } => vec![text(description.clone())],
+    }
+}
+
+fn tool_status_label(status: &ToolStatus) -> &'static str {
+    match status {
+        ToolStatus::Created => "started",
+        ToolStatus::Success => "succeeded",
+        ToolStatus::Failed => "failed",
+        ToolStatus::Denied { .. } => "denied",
+        ToolStatus::PendingApproval { .. } => "awaiting approval",
+        ToolStatus::TimedOut => "timed out",
+    }
+}
+
+fn status_label(status: &ExecutionProcessStatus) -> &'static str {
+    match status {
+        ExecutionProcessStatus::Running => "running",
+        ExecutionProcessStatus::Completed => "completed",
+        ExecutionProcessStatus::Failed => "failed",
+        ExecutionProcessStatus::Killed => "killed",
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// Fence `content` with more backticks than it contains in a row
+fn fenced(lang: &str, content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{lang}\n{}\n{fence}", content.trim_end_matches('\n'))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn transcript() -> Transcript {
+        let started_at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        Transcript {
+            schema_version: TRANSCRIPT_SCHEMA_VERSION,
+            exported_at: started_at,
+            task_id: Uuid::nil(),
+            task_title: "Fix <parser>".to_string(),
+            workspace_id: Uuid::nil(),
+            branch: "vk/fix-parser".to_string(),
+            sessions: vec![TranscriptSession {
+                id: Uuid::nil(),
+                executor: Some("CLAUDE_CODE".to_string()),
+                created_at: started_at,
+                turns: vec![TranscriptTurn {
+                    execution_process_id: Uuid::nil(),
+                    status: ExecutionProcessStatus::Completed,
+                    started_at,
+                    completed_at: None,
+                    prompt: Some("Fix the parser".to_string()),
+                    summary: None,
+                    entries: vec![
+                        entry(NormalizedEntryType::UserMessage, "Fix the parser"),
+                        entry(
+                            NormalizedEntryType::ToolUse {
+                                tool_name: "Bash".to_string(),
+                                action_type: ActionType::CommandRun {
+                                    command: "cargo test".to_string(),
+                                    result: None,
+                                },
+                                status: ToolStatus::Success,
+                            },
+                            "cargo test",
+                        ),
+                        entry(NormalizedEntryType::AssistantMessage, "Done, see ```x```"),
+                        entry(NormalizedEntryType::Loading, ""),
+                    ],
+                    diffs: vec![TranscriptDiff {
+                        repo_name: "app".to_string(),
+                        from_commit: "1111111111".to_string(),
+                        to_commit: "2222222222".to_string(),
+                        unified_diff: "-old\n+new\n".to_string(),
+                    }],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn markdown_lists_prompt_tools_messages_and_diffs_once() {
+        let md = transcript().to_markdown();
+        assert_eq!(md.matches("Fix the parser").count(), 1);
+        assert!(md.contains("**Tool: Bash (succeeded)**\n\n```sh\ncargo test\n```"));
+        assert!(md.contains("**Assistant**\n\nDone, see ```x```"));
+        assert!(md.contains("**Changes in app (1111111..2222222)**\n\n```diff\n-old\n+new\n```"));
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let html = transcript().to_html();
+        assert!(html.contains("<title>Fix &lt;parser&gt;</title>"));
+        assert!(!html.contains("<parser>"));
+    }
+
+    #[test]
+    fn fences_outgrow_backticks_in_content() {
+        assert_eq!(fenced("", "a ```b``` c"), "````\na ```b``` c\n````");
+    }
+}
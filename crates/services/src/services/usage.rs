@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use db::models::coding_agent_turn::{CodingAgentTurn, CodingAgentTurnUsage};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::config::ModelPrice;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    Task,
+    Workspace,
+    #[default]
+    Project,
+    ExecutorProfile,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct UsageQuery {
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    /// Inclusive lower bound on the turn creation time
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the turn creation time
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct UsageSummary {
+    /// Task, workspace or project ID, or `EXECUTOR` / `EXECUTOR:VARIANT` when
+    /// grouping by executor profile
+    pub key: String,
+    pub turns: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Estimated cost in USD of the turns whose model has a configured price
+    pub cost_usd: f64,
+    /// Turns that are not included in `cost_usd` because no price is configured
+    /// for their model
+    pub unpriced_turns: i64,
+}
+
+/// Aggregate the recorded token usage of coding agent turns matching `query`,
+/// pricing each turn with `prices`.
+pub async fn summarize_usage(
+    pool: &SqlitePool,
+    query: &UsageQuery,
+    prices: &HashMap<String, ModelPrice>,
+) -> Result<Vec<UsageSummary>, sqlx::Error> {
+    let turns = CodingAgentTurn::find_usage(
+        pool,
+        query.project_id,
+        query.task_id,
+        query.workspace_id,
+        query.from,
+        query.to,
+    )
+    .await?;
+
+    Ok(summarize_turns(&turns, query.group_by, prices))
+}
+
+fn group_key(turn: &CodingAgentTurnUsage, group_by: UsageGroupBy) -> String {
+    match group_by {
+        UsageGroupBy::Task => turn.task_id.to_string(),
+        UsageGroupBy::Workspace => turn.workspace_id.to_string(),
+        UsageGroupBy::Project => turn.project_id.to_string(),
+        UsageGroupBy::ExecutorProfile => {
+            let executor = turn.executor.as_deref().unwrap_or("UNKNOWN");
+            match turn.variant.as_deref() {
+                Some(variant) => format!("{executor}:{variant}"),
+                None => executor.to_string(),
+            }
+        }
+    }
+}
+
+/// Find the price for `model`, matching the longest configured key that the
+/// model name starts with so `claude-sonnet-4` also prices dated snapshots.
+fn price_for<'a>(prices: &'a HashMap<String, ModelPrice>, model: &str) -> Option<&'a ModelPrice> {
+    prices
+        .iter()
+        .filter(|(key, _)| model.starts_with(key.as_str()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, price)| price)
+}
+
+fn turn_cost(turn: &CodingAgentTurnUsage, price: &ModelPrice) -> f64 {
+    (turn.input_tokens as f64 * price.input_per_mtok
+        + turn.output_tokens as f64 * price.output_per_mtok
+        + turn.cache_read_tokens as f64 * price.cache_read_per_mtok
+        + turn.cache_write_tokens as f64 * price.cache_write_per_mtok)
+        / 1_000_000.0
+}
+
+fn summarize_turns(
+    turns: &[CodingAgentTurnUsage],
+    group_by: UsageGroupBy,
+    prices: &HashMap<String, ModelPrice>,
+) -> Vec<UsageSummary> {
+    let mut groups: BTreeMap<String, UsageSummary> = BTreeMap::new();
+
+    for turn in turns {
+        let key = group_key(turn, group_by);
+        let summary = groups.entry(key.clone()).or_insert_with(|| UsageSummary {
+            key,
+            ..Default::default()
+        });
+
+        summary.turns += 1;
+        summary.input_tokens += turn.input_tokens;
+        summary.output_tokens += turn.output_tokens;
+        summary.cache_read_tokens += turn.cache_read_tokens;
+        summary.cache_write_tokens += turn.cache_write_tokens;
+
+        match turn
+            .model
+            .as_deref()
+            .and_then(|model| price_for(prices, model))
+        {
+            Some(price) => summary.cost_usd += turn_cost(turn, price),
+            None => summary.unpriced_turns += 1,
+        }
+    }
+
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(model: Option<&str>, executor: &str, variant: Option<&str>) -> CodingAgentTurnUsage {
+        CodingAgentTurnUsage {
+            execution_process_id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            executor: Some(executor.to_string()),
+            variant: variant.map(str::to_string),
+            model: model.map(str::to_string),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn prices() -> HashMap<String, ModelPrice> {
+        HashMap::from([
+            (
+                "claude".to_string(),
+                ModelPrice {
+                    input_per_mtok: 1.0,
+                    output_per_mtok: 1.0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "claude-sonnet".to_string(),
+                ModelPrice {
+                    input_per_mtok: 3.0,
+                    output_per_mtok: 15.0,
+                    ..Default::default()
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_longest_price_prefix_wins() {
+        let prices = prices();
+        let price = price_for(&prices, "claude-sonnet-4-20250514").unwrap();
+        assert_eq!(price.input_per_mtok, 3.0);
+        assert!(price_for(&prices, "gpt-5").is_none());
+    }
+
+    #[test]
+    fn test_summarize_by_executor_profile() {
+        let turns = vec![
+            turn(Some("claude-sonnet-4"), "CLAUDE_CODE", None),
+            turn(Some("claude-sonnet-4"), "CLAUDE_CODE", None),
+            turn(Some("gpt-5"), "CODEX", Some("HIGH")),
+        ];
+
+        let summaries = summarize_turns(&turns, UsageGroupBy::ExecutorProfile, &prices());
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].key, "CLAUDE_CODE");
+        assert_eq!(summaries[0].turns, 2);
+        assert_eq!(summaries[0].input_tokens, 2_000_000);
+        assert!((summaries[0].cost_usd - 9.0).abs() < 1e-9);
+        assert_eq!(summaries[0].unpriced_turns, 0);
+        assert_eq!(summaries[1].key, "CODEX:HIGH");
+        assert_eq!(summaries[1].cost_usd, 0.0);
+        assert_eq!(summaries[1].unpriced_turns, 1);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Json, Router,
+    extract::{Path as AxumPath, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+};
+use db::models::merge::{CiStatus, MergeStatus, PrCheck};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use services::services::git_host::{
+    CreatePrRequest, GitHostError, GitHostProvider, GitHostService, GiteaHost, ProviderKind,
+    SelfHostedGitHosts, UnifiedPrComment,
+};
+
+const TOKEN: &str = "secret-token";
+
+#[derive(Clone, Default)]
+struct StubState {
+    created: Arc<Mutex<Vec<Value>>>,
+    edited: Arc<Mutex<Vec<(i64, Value)>>>,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    state: String,
+    page: usize,
+}
+
+type StubResult = Result<Json<Value>, StatusCode>;
+
+fn authorize(headers: &HeaderMap) -> Result<(), StatusCode> {
+    let expected = format!("token {TOKEN}");
+    match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+        Some(value) if value == expected => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+fn pull(base: &str, number: i64, head: &str, state: &str, merged: bool) -> Value {
+    json!({
+        "number": number,
+        "html_url": format!("{base}/acme/widgets/pulls/{number}"),
+        "title": format!("PR {number}"),
+        "state": state,
+        "merged": merged,
+        "merged_at": if merged { json!("2025-03-01T12:00:00Z") } else { Value::Null },
+        "merge_commit_sha": if merged { json!("abc123") } else { Value::Null },
+        "head": { "ref": head, "sha": format!("sha{number}") },
+        "base": { "ref": "main" },
+    })
+}
+
+async fn create_pull(
+    State(state): State<StubState>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> StubResult {
+    authorize(&headers)?;
+    state.created.lock().unwrap().push(body.clone());
+    Ok(Json(pull(
+        "http://stub",
+        7,
+        body["head"].as_str().unwrap_or_default(),
+        "open",
+        false,
+    )))
+}
+
+async fn list_pulls(headers: HeaderMap, Query(query): Query<ListQuery>) -> StubResult {
+    authorize(&headers)?;
+    if query.page > 1 {
+        return Ok(Json(json!([])));
+    }
+    let pulls = match query.state.as_str() {
+        "open" => vec![pull("http://stub", 7, "feature/a", "open", false)],
+        _ => vec![
+            pull("http://stub", 7, "feature/a", "open", false),
+            pull("http://stub", 5, "feature/a", "closed", true),
+            pull("http://stub", 6, "feature/b", "closed", false),
+        ],
+    };
+    Ok(Json(Value::Array(pulls)))
+}
+
+async fn get_pull(
+    headers: HeaderMap,
+    AxumPath((_, _, index)): AxumPath<(String, String, i64)>,
+) -> StubResult {
+    authorize(&headers)?;
+    match index {
+        5 => Ok(Json(pull("http://stub", 5, "feature/a", "closed", true))),
+        7 => Ok(Json(pull("http://stub", 7, "feature/a", "open", false))),
+        _ => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn edit_pull(
+    State(state): State<StubState>,
+    headers: HeaderMap,
+    AxumPath((_, _, index)): AxumPath<(String, String, i64)>,
+    Json(body): Json<Value>,
+) -> StubResult {
+    authorize(&headers)?;
+    state.edited.lock().unwrap().push((index, body));
+    Ok(Json(pull("http://stub", index, "feature/a", "open", false)))
+}
+
+async fn commit_status(
+    headers: HeaderMap,
+    AxumPath((_, _, sha)): AxumPath<(String, String, String)>,
+) -> StubResult {
+    authorize(&headers)?;
+    assert_eq!(
+        sha, "sha7",
+        "statuses are only fetched for open pull requests"
+    );
+    Ok(Json(json!({
+        "state": "failure",
+        "statuses": [
+            { "context": "ci/lint", "status": "success", "target_url": "" },
+            {
+                "context": "ci/test",
+                "status": "failure",
+                "target_url": "http://stub/acme/widgets/actions/runs/3",
+            },
+            { "context": "ci/deploy", "status": "pending", "target_url": "" },
+        ],
+    })))
+}
+
+async fn issue_comments(headers: HeaderMap) -> StubResult {
+    authorize(&headers)?;
+    Ok(Json(json!([{
+        "id": 11,
+        "body": "Thanks!",
+        "user": { "login": "alice" },
+        "created_at": "2025-03-01T10:00:00Z",
+        "html_url": "http://stub/acme/widgets/pulls/7#issuecomment-11",
+    }])))
+}
+
+async fn reviews(headers: HeaderMap) -> StubResult {
+    authorize(&headers)?;
+    Ok(Json(json!([
+        {
+            "id": 3,
+            "body": "A couple of nits",
+            "state": "REQUEST_CHANGES",
+            "user": { "login": "bob" },
+            "submitted_at": "2025-03-01T09:00:00Z",
+            "comments_count": 1,
+        },
+        {
+            "id": 4,
+            "body": "draft",
+            "state": "PENDING",
+            "user": { "login": "bob" },
+            "comments_count": 2,
+        },
+    ])))
+}
+
+async fn review_comments(
+    headers: HeaderMap,
+    AxumPath((_, _, _, review_id)): AxumPath<(String, String, i64, i64)>,
+) -> StubResult {
+    authorize(&headers)?;
+    assert_eq!(review_id, 3, "pending reviews must not be fetched");
+    Ok(Json(json!([{
+        "id": 21,
+        "body": "Rename this",
+        "user": { "login": "bob" },
+        "created_at": "2025-03-01T08:00:00Z",
+        "path": "src/lib.rs",
+        "position": 42,
+        "original_position": 40,
+        "diff_hunk": "@@ -40,3 +42,3 @@",
+    }])))
+}
+
+/// Serve a fake Gitea API on a random local port and return its base URL
+async fn spawn_stub() -> (String, StubState) {
+    let state = StubState::default();
+    let app = Router::new()
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls",
+            get(list_pulls).post(create_pull),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{index}",
+            get(get_pull).patch(edit_pull),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/commits/{sha}/status",
+            get(commit_status),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/issues/{index}/comments",
+            get(issue_comments),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{index}/reviews",
+            get(reviews),
+        )
+        .route(
+            "/api/v1/repos/{owner}/{repo}/pulls/{index}/reviews/{review_id}/comments",
+            get(review_comments),
+        )
+        .with_state(state.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), state)
+}
+
+fn provider(base_url: &str, token: Option<&str>) -> GitHostService {
+    let self_hosted = SelfHostedGitHosts {
+        gitea: vec![GiteaHost {
+            url: base_url.to_string(),
+            token: token.map(str::to_string),
+        }],
+        ..Default::default()
+    };
+    GitHostService::from_url(&format!("{base_url}/acme/widgets.git"), &self_hosted).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_pr() {
+    let (base_url, state) = spawn_stub().await;
+    let git_host = provider(&base_url, Some(TOKEN));
+    assert_eq!(git_host.provider_kind(), ProviderKind::Gitea);
+
+    let request = CreatePrRequest {
+        title: "Add widgets".to_string(),
+        body: Some("Body".to_string()),
+        head_branch: "feature/a".to_string(),
+        base_branch: "main".to_string(),
+        draft: Some(true),
+        head_repo_url: None,
+    };
+    let pr = git_host
+        .create_pr(
+            Path::new("."),
+            &format!("{base_url}/acme/widgets.git"),
+            &request,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(pr.number, 7);
+    assert!(matches!(pr.status, MergeStatus::Open));
+    let created = state.created.lock().unwrap();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0]["title"], "WIP: Add widgets");
+    assert_eq!(created[0]["head"], "feature/a");
+    assert_eq!(created[0]["base"], "main");
+}
+
+#[tokio::test]
+async fn test_pr_status_and_listing() {
+    let (base_url, _) = spawn_stub().await;
+    let git_host = provider(&base_url, Some(TOKEN));
+    let remote_url = format!("{base_url}/acme/widgets.git");
+
+    let status = git_host
+        .get_pr_status(&format!("{base_url}/acme/widgets/pulls/5"))
+        .await
+        .unwrap();
+    assert!(matches!(status.status, MergeStatus::Merged));
+    assert_eq!(status.merge_commit_sha.as_deref(), Some("abc123"));
+    assert_eq!(status.ci_status, None);
+
+    let for_branch = git_host
+        .list_prs_for_branch(Path::new("."), &remote_url, "feature/a")
+        .await
+        .unwrap();
+    assert_eq!(
+        for_branch.iter().map(|p| p.number).collect::<Vec<_>>(),
+        vec![7, 5]
+    );
+
+    let open = git_host
+        .list_open_prs(Path::new("."), &remote_url)
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].head_branch, "feature/a");
+    assert_eq!(open[0].base_branch, "main");
+}
+
+#[tokio::test]
+async fn test_pr_ci_status() {
+    let (base_url, _) = spawn_stub().await;
+    let git_host = provider(&base_url, Some(TOKEN));
+
+    let status = git_host
+        .get_pr_status(&format!("{base_url}/acme/widgets/pulls/7"))
+        .await
+        .unwrap();
+    assert!(matches!(status.status, MergeStatus::Open));
+    assert_eq!(status.ci_status, Some(CiStatus::Failure));
+    assert_eq!(
+        status.failing_checks,
+        vec![PrCheck {
+            name: "ci/test".to_string(),
+            url: Some("http://stub/acme/widgets/actions/runs/3".to_string()),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_update_pr_base() {
+    let (base_url, state) = spawn_stub().await;
+    let git_host = provider(&base_url, Some(TOKEN));
+
+    git_host
+        .update_pr_base(&format!("{base_url}/acme/widgets/pulls/7"), "main")
+        .await
+        .unwrap();
+
+    let edited = state.edited.lock().unwrap();
+    assert_eq!(edited.len(), 1);
+    assert_eq!(edited[0].0, 7);
+    assert_eq!(edited[0].1, json!({ "base": "main" }));
+}
+
+#[tokio::test]
+async fn test_pr_comments() {
+    let (base_url, _) = spawn_stub().await;
+    let git_host = provider(&base_url, Some(TOKEN));
+
+    let comments = git_host
+        .get_pr_comments(Path::new("."), &format!("{base_url}/acme/widgets.git"), 7)
+        .await
+        .unwrap();
+
+    assert_eq!(comments.len(), 3);
+    match &comments[0] {
+        UnifiedPrComment::Review {
+            id,
+            path,
+            line,
+            side,
+            ..
+        } => {
+            assert_eq!(*id, 21);
+            assert_eq!(path, "src/lib.rs");
+            assert_eq!(*line, Some(42));
+            assert_eq!(side.as_deref(), Some("RIGHT"));
+        }
+        other => panic!("expected a review comment, got {other:?}"),
+    }
+    assert!(matches!(
+        &comments[1],
+        UnifiedPrComment::General { author, body, .. } if author == "bob" && body == "A couple of nits"
+    ));
+    assert!(matches!(
+        &comments[2],
+        UnifiedPrComment::General { author, .. } if author == "alice"
+    ));
+}
+
+#[tokio::test]
+async fn test_missing_token_is_auth_failure() {
+    let (base_url, _) = spawn_stub().await;
+    let git_host = provider(&base_url, None);
+
+    let result = git_host
+        .list_open_prs(Path::new("."), &format!("{base_url}/acme/widgets.git"))
+        .await;
+    assert!(matches!(result, Err(GitHostError::AuthFailed(_))));
+}
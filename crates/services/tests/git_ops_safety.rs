@@ -4,8 +4,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use db::models::merge::MergeStrategy;
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::git::{ConflictOp, GitCli, GitCliError, GitService, GitServiceError};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
@@ -493,6 +494,7 @@ fn merge_does_not_overwrite_main_repo_untracked_files() {
         "feature",
         "main",
         "squash merge",
+        MergeStrategy::Squash,
     );
     assert!(
         res.is_err(),
@@ -536,6 +538,7 @@ fn merge_does_not_touch_tracked_uncommitted_changes_in_base_worktree() {
         "feature",
         "main",
         "squash merge",
+        MergeStrategy::Squash,
     );
     assert!(
         res.is_ok(),
@@ -566,7 +569,14 @@ fn merge_refuses_with_staged_changes_on_base() {
     // main has staged change
     write_file(&repo_path, "staged.txt", "staged\n");
     add_path(&repo_path, "staged.txt");
-    let res = s.merge_changes(&repo_path, &worktree_path, "feature", "main", "squash");
+    let res = s.merge_changes(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "squash",
+        MergeStrategy::Squash,
+    );
     assert!(res.is_err(), "should refuse merge due to staged changes");
     // staged file remains
     let content = std::fs::read_to_string(repo_path.join("staged.txt")).unwrap();
@@ -588,7 +598,14 @@ fn merge_preserves_unstaged_changes_on_base() {
     commit_all(&wt_repo, "feature merged");
 
     let _sha = s
-        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .unwrap();
     // local edit preserved
     let loc = std::fs::read_to_string(repo_path.join("common.txt")).unwrap();
@@ -614,7 +631,14 @@ fn update_ref_does_not_destroy_feature_worktree_dirty_state() {
     write_file(&worktree_path, "dirty.txt", "unstaged\n");
     // merge from feature into main (CLI path updates task ref via update-ref)
     let sha = s
-        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .unwrap();
     // uncommitted change in feature worktree preserved
     let dirty = std::fs::read_to_string(worktree_path.join("dirty.txt")).unwrap();
@@ -642,7 +666,14 @@ fn libgit2_merge_updates_base_ref_in_both_repos() {
 
     // Perform merge (squash) while main repo is NOT on base branch (libgit2 path)
     let sha = s
-        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .expect("merge should succeed via libgit2 path");
 
     // Base branch ref advanced in both main and worktree repositories
@@ -664,7 +695,14 @@ fn libgit2_merge_updates_task_ref_and_feature_head_preserves_dirty() {
 
     // Perform merge (squash) from feature into main; this path uses libgit2
     let sha = s
-        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .expect("merge should succeed via libgit2 path");
 
     // Dirty file preserved in worktree
@@ -683,6 +721,145 @@ fn libgit2_merge_updates_task_ref_and_feature_head_preserves_dirty() {
     assert_eq!(head.oid, sha);
 }
 
+#[test]
+fn rebase_strategy_fast_forwards_checked_out_base() {
+    // CLI path: base branch is checked out in the main repo
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    let feature_head = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeStrategy::Rebase,
+        )
+        .unwrap();
+
+    // Base lands exactly on the task head, keeping the task commits as they are
+    assert_eq!(sha, feature_head);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), feature_head);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        feature_head
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.parent_count(), 1, "history should stay linear");
+    assert_eq!(head.message().unwrap().trim(), "feature commit");
+    // Checked-out base worktree reflects the merged content
+    let feat = std::fs::read_to_string(repo_path.join("feat.txt")).unwrap();
+    assert_eq!(feat, "feat change\n");
+}
+
+#[test]
+fn rebase_strategy_fast_forwards_base_via_libgit2() {
+    // libgit2 path: base branch is not checked out anywhere
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let feature_head = s.get_branch_oid(&worktree_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeStrategy::Rebase,
+        )
+        .expect("fast-forward should succeed via libgit2 path");
+
+    assert_eq!(sha, feature_head);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), feature_head);
+    assert_eq!(
+        s.get_branch_oid(&worktree_path, "main").unwrap(),
+        feature_head
+    );
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        feature_head
+    );
+}
+
+#[test]
+fn merge_commit_strategy_keeps_both_parents_and_dirty_base() {
+    // CLI path with unrelated local edits on the checked-out base
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "common.txt", "local edited\n");
+    let main_before = s.get_branch_oid(&repo_path, "main").unwrap();
+    let feature_head = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "merge feature",
+            MergeStrategy::MergeCommit,
+        )
+        .unwrap();
+
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(commit.parent_count(), 2);
+    assert_eq!(commit.parent_id(0).unwrap().to_string(), main_before);
+    assert_eq!(commit.parent_id(1).unwrap().to_string(), feature_head);
+    assert_eq!(commit.message().unwrap().trim(), "merge feature");
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+    // Local edit preserved, merged file present
+    let loc = std::fs::read_to_string(repo_path.join("common.txt")).unwrap();
+    assert_eq!(loc, "local edited\n");
+    let feat = std::fs::read_to_string(repo_path.join("feat.txt")).unwrap();
+    assert_eq!(feat, "feat change\n");
+}
+
+#[test]
+fn merge_commit_strategy_via_libgit2_keeps_both_parents() {
+    // libgit2 path: base branch is not checked out anywhere
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let main_before = s.get_branch_oid(&repo_path, "main").unwrap();
+    let feature_head = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    let sha = s
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "merge feature",
+            MergeStrategy::MergeCommit,
+        )
+        .expect("merge commit should succeed via libgit2 path");
+
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    assert_eq!(s.get_branch_oid(&worktree_path, "main").unwrap(), sha);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(commit.parent_count(), 2);
+    assert_eq!(commit.parent_id(0).unwrap().to_string(), main_before);
+    assert_eq!(commit.parent_id(1).unwrap().to_string(), feature_head);
+    assert!(commit.tree().unwrap().get_name("feat.txt").is_some());
+}
+
 #[test]
 fn rebase_refuses_to_abort_existing_rebase() {
     let td = TempDir::new().unwrap();
@@ -713,6 +890,55 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn continue_conflicts_refuses_markers_then_finishes_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let s = GitService::new();
+    let _ = s
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+    assert_eq!(
+        s.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+    let (ours, theirs) = s.get_conflict_subjects(&worktree_path, &ConflictOp::Rebase);
+    assert_eq!(ours.as_deref(), Some("new-base change"));
+    assert_eq!(theirs.as_deref(), Some("feature conflicting change"));
+
+    // Staging a file that still has markers is not a resolution
+    add_path(&worktree_path, "conflict.txt");
+    assert_eq!(
+        s.find_conflict_markers(&worktree_path).unwrap(),
+        vec!["conflict.txt".to_string()]
+    );
+    let res = s.continue_conflicts(&worktree_path);
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts { .. })));
+    assert!(s.is_rebase_in_progress(&worktree_path).unwrap());
+
+    write_file(&worktree_path, "conflict.txt", "combined version\n");
+    s.continue_conflicts(&worktree_path)
+        .expect("resolved rebase should continue");
+    assert!(!s.is_rebase_in_progress(&worktree_path).unwrap());
+    let head = Repository::open(&worktree_path)
+        .unwrap()
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(head.message().unwrap().trim(), "feature conflicting change");
+    assert_eq!(
+        head.parent_id(0).unwrap().to_string(),
+        s.get_branch_oid(&repo_path, "new-base").unwrap()
+    );
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -795,6 +1021,7 @@ fn merge_when_base_ahead_and_feature_ahead_fails() {
         "feature",
         "main",
         "squash merge",
+        MergeStrategy::Squash,
     );
 
     assert!(
@@ -827,6 +1054,7 @@ fn merge_conflict_does_not_move_base_ref() {
         "feature",
         "main",
         "squash merge",
+        MergeStrategy::Squash,
     );
 
     assert!(res.is_err(), "conflicting merge should fail");
@@ -870,6 +1098,7 @@ fn merge_delete_vs_modify_conflict_behaves_safely() {
         "feature",
         "main",
         "squash merge",
+        MergeStrategy::Squash,
     );
 
     // Should now fail due to base branch being ahead, not due to merge conflicts
@@ -935,7 +1164,14 @@ fn merge_refreshes_main_worktree_when_on_base() {
 
     // Merge into main (squash) and ensure main worktree is updated since it is on base
     let merge_sha = s
-        .merge_changes(&repo_path, &wt, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &wt,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .unwrap();
     // Since main is on base branch and we use safe CLI merge, both working tree
     // and ref should reflect the merged content.
@@ -1105,7 +1341,14 @@ fn merge_binary_conflict_does_not_move_ref() {
     let _ = s.commit(&repo_path, "main bin").unwrap();
 
     let before = s.get_branch_oid(&repo_path, "main").unwrap();
-    let res = s.merge_changes(&repo_path, &worktree_path, "feature", "main", "merge bin");
+    let res = s.merge_changes(
+        &repo_path,
+        &worktree_path,
+        "feature",
+        "main",
+        "merge bin",
+        MergeStrategy::Squash,
+    );
     assert!(res.is_err(), "binary conflict should fail");
     let after = s.get_branch_oid(&repo_path, "main").unwrap();
     assert_eq!(before, after, "main ref unchanged on conflict");
@@ -1144,6 +1387,7 @@ fn merge_rename_vs_modify_conflict_does_not_move_ref() {
         "feature",
         "main",
         "merge rename",
+        MergeStrategy::Squash,
     );
     match res {
         Err(_) => {
@@ -1199,6 +1443,7 @@ fn merge_leaves_no_staged_changes_on_target_branch() {
             "feature",
             "main",
             "merge feature",
+            MergeStrategy::Squash,
         )
         .expect("merge should succeed");
 
@@ -1269,6 +1514,7 @@ fn worktree_to_worktree_merge_leaves_no_staged_changes() {
         "feature-a",
         "feature-b",
         "merge feature-a into feature-b",
+        MergeStrategy::Squash,
     );
 
     // Verify no staged changes were introduced
@@ -1326,6 +1572,7 @@ fn merge_into_orphaned_branch_uses_libgit2_fallback() {
             "feature",
             "orphaned-feature",
             "merge into orphaned branch",
+            MergeStrategy::Squash,
         )
         .expect("libgit2 merge into orphaned branch should succeed");
 
@@ -1397,6 +1644,7 @@ fn merge_base_ahead_of_task_should_error() {
         "feature",
         "main",
         "attempt merge when base ahead",
+        MergeStrategy::Squash,
     );
 
     // TDD: This test will initially fail because merge currently succeeds
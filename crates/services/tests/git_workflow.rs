@@ -4,8 +4,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use db::models::merge::MergeStrategy;
 use git2::{Repository, build::CheckoutBuilder};
-use services::services::git::{DiffTarget, GitCli, GitService};
+use services::services::git::{DiffTarget, GitCli, GitService, GitServiceError, RevertSelection};
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
 
@@ -404,6 +405,151 @@ fn get_branch_oid_nonexistent_errors() {
     assert!(res.is_err());
 }
 
+#[test]
+fn create_branch_at_commit_points_at_past_commit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "file.txt", "first\n");
+    s.commit(&repo_path, "first").unwrap();
+    let first = s.get_branch_oid(&repo_path, "main").unwrap();
+    write_file(&repo_path, "file.txt", "second\n");
+    s.commit(&repo_path, "second").unwrap();
+
+    s.create_branch_at_commit(&repo_path, "fork", &first)
+        .unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "fork").unwrap(), first);
+    // The current branch is left alone
+    assert_ne!(s.get_branch_oid(&repo_path, "main").unwrap(), first);
+    // Existing branches are never overwritten
+    assert!(
+        s.create_branch_at_commit(&repo_path, "main", &first)
+            .is_err()
+    );
+}
+
+#[test]
+fn revert_worktree_changes_reverts_hunks_and_files_in_a_commit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let lines = |edits: &[(usize, &str)]| -> String {
+        (1..=20)
+            .map(|n| match edits.iter().find(|(line, _)| *line == n) {
+                Some((_, text)) => format!("{text}\n"),
+                None => format!("line {n}\n"),
+            })
+            .collect()
+    };
+    write_file(&repo_path, "code.txt", &lines(&[]));
+    write_file(&repo_path, "gone.txt", "keep me\n");
+    s.commit(&repo_path, "base").unwrap();
+
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(
+        &repo_path,
+        "code.txt",
+        &lines(&[(2, "agent 2"), (18, "agent 18")]),
+    );
+    write_file(&repo_path, "new.txt", "added\n");
+    fs::remove_file(repo_path.join("gone.txt")).unwrap();
+    s.commit(&repo_path, "agent turn").unwrap();
+
+    let base = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let commit = s
+        .revert_worktree_changes(
+            &repo_path,
+            &base,
+            &[
+                RevertSelection {
+                    path: "code.txt".to_string(),
+                    hunks: Some(vec![1]),
+                },
+                RevertSelection {
+                    path: "new.txt".to_string(),
+                    hunks: None,
+                },
+                RevertSelection {
+                    path: "gone.txt".to_string(),
+                    hunks: None,
+                },
+            ],
+            "User revert",
+        )
+        .unwrap()
+        .expect("revert commit");
+
+    assert_eq!(
+        fs::read_to_string(repo_path.join("code.txt")).unwrap(),
+        lines(&[(2, "agent 2")])
+    );
+    assert!(!repo_path.join("new.txt").exists());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("gone.txt")).unwrap(),
+        "keep me\n"
+    );
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), commit);
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+
+    // Reverting again changes nothing, and unknown hunks are rejected
+    let again = s
+        .revert_worktree_changes(
+            &repo_path,
+            &base,
+            &[RevertSelection {
+                path: "new.txt".to_string(),
+                hunks: None,
+            }],
+            "User revert",
+        )
+        .unwrap();
+    assert!(again.is_none());
+    let err = s
+        .revert_worktree_changes(
+            &repo_path,
+            &base,
+            &[RevertSelection {
+                path: "code.txt".to_string(),
+                hunks: Some(vec![5]),
+            }],
+            "User revert",
+        )
+        .unwrap_err();
+    assert!(matches!(err, GitServiceError::InvalidRevertSelection(_)));
+}
+
+#[test]
+fn changed_line_ranges_report_old_side_lines() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let body: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+    write_file(&repo_path, "a.txt", &body);
+    write_file(&repo_path, "b.txt", "untouched\n");
+    s.commit(&repo_path, "base").unwrap();
+    let before = s.get_head_info(&repo_path).unwrap().oid;
+
+    let edited = body
+        .replace("line 3\n", "three\n")
+        .replace("line 4\n", "four\n")
+        .replace("line 8\n", "line 8\ninserted\n");
+    write_file(&repo_path, "a.txt", &edited);
+    s.commit(&repo_path, "edit").unwrap();
+    let after = s.get_head_info(&repo_path).unwrap().oid;
+
+    assert_eq!(
+        s.get_changed_line_ranges(&repo_path, &before, &after, "a.txt")
+            .unwrap(),
+        vec![(3, 4), (8, 9)]
+    );
+    assert!(
+        s.get_changed_line_ranges(&repo_path, &before, &after, "b.txt")
+            .unwrap()
+            .is_empty()
+    );
+}
+
 #[test]
 fn create_unicode_branch_and_list() {
     let td = TempDir::new().unwrap();
@@ -506,7 +652,14 @@ fn squash_merge_libgit2_sets_author_without_user() {
 
     // Merge feature -> main (libgit2 squash)
     let merge_sha = s
-        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .merge_changes(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+        )
         .unwrap();
 
     // The squash commit author should not be the feature commit's author, and must be present.
@@ -17,7 +17,11 @@ pub struct ApprovalRequest {
 }
 
 impl ApprovalRequest {
-    pub fn from_create(request: CreateApprovalRequest, execution_process_id: Uuid) -> Self {
+    pub fn from_create(
+        request: CreateApprovalRequest,
+        execution_process_id: Uuid,
+        timeout_seconds: i64,
+    ) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
@@ -26,7 +30,7 @@ impl ApprovalRequest {
             tool_call_id: request.tool_call_id,
             execution_process_id,
             created_at: now,
-            timeout_at: now + Duration::seconds(APPROVAL_TIMEOUT_SECONDS),
+            timeout_at: now + Duration::seconds(timeout_seconds),
         }
     }
 }
@@ -30,6 +30,20 @@ pub struct Diff {
     pub additions: Option<usize>,
     pub deletions: Option<usize>,
     pub repo_id: Option<Uuid>,
+    /// Hunks of the change in the order reverts number them; empty when a
+    /// side isn't text
+    #[serde(default)]
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Line ranges of one hunk, 1-based as in a unified diff header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -91,6 +105,73 @@ pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     }
 }
 
+/// Lines of context around the hunks that [`revert_hunks`] numbers, matching
+/// git's default.
+pub const REVERT_CONTEXT_LINES: u32 = 3;
+
+fn revert_patch<'a>(old: &'a str, new: &'a str) -> Option<Patch<'a>> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(REVERT_CONTEXT_LINES);
+    Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, Some(&mut opts)).ok()
+}
+
+/// Hunks of the diff from `old` to `new`, indexed the way [`revert_hunks`]
+/// takes them.
+pub fn diff_hunks(old: &str, new: &str) -> Vec<DiffHunk> {
+    let Some(patch) = revert_patch(old, new) else {
+        return Vec::new();
+    };
+    (0..patch.num_hunks())
+        .filter_map(|idx| patch.hunk(idx).ok())
+        .map(|(hunk, _)| DiffHunk {
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+        })
+        .collect()
+}
+
+/// Undo the selected hunks of the diff from `old` to `new` and keep the rest of
+/// `new`. Hunks are numbered in diff order. Returns `None` if an index is out
+/// of range.
+pub fn revert_hunks(old: &str, new: &str, hunks: &[usize]) -> Option<String> {
+    let patch = revert_patch(old, new)?;
+    let count = patch.num_hunks();
+    if hunks.iter().any(|&idx| idx >= count) {
+        return None;
+    }
+
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let mut out = String::with_capacity(new.len());
+    let mut next = 0;
+    for idx in (0..count).filter(|idx| hunks.contains(idx)) {
+        let (hunk, _) = patch.hunk(idx).ok()?;
+        let old_start = hunk_start(hunk.old_start(), hunk.old_lines());
+        let new_start = hunk_start(hunk.new_start(), hunk.new_lines());
+        out.extend(new_lines.get(next..new_start)?.iter().copied());
+        out.extend(
+            old_lines
+                .get(old_start..old_start + hunk.old_lines() as usize)?
+                .iter()
+                .copied(),
+        );
+        next = new_start + hunk.new_lines() as usize;
+    }
+    out.extend(new_lines.get(next..)?.iter().copied());
+    Some(out)
+}
+
+// 0-based index of a hunk's first line; empty ranges point at the line before
+fn hunk_start(start: u32, lines: u32) -> usize {
+    if lines == 0 {
+        start as usize
+    } else {
+        start as usize - 1
+    }
+}
+
 // ensure a line ends with a newline character
 fn ensure_newline(line: &str) -> Cow<'_, str> {
     if line.ends_with('\n') {
@@ -246,3 +327,70 @@ pub fn normalize_unified_diff(file_path: &str, unified_diff: &str) -> String {
     let hunks = extract_unified_diff_hunks(unified_diff);
     concatenate_diff_hunks(file_path, &hunks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(lines: usize, edits: &[(usize, &str)]) -> String {
+        (1..=lines)
+            .map(|n| {
+                edits
+                    .iter()
+                    .find(|(line, _)| *line == n)
+                    .map_or(format!("line {n}\n"), |(_, text)| format!("{text}\n"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reverts_only_selected_hunks() {
+        let old = numbered(20, &[]);
+        let new = numbered(20, &[(2, "changed 2"), (18, "changed 18")]);
+
+        assert_eq!(
+            revert_hunks(&old, &new, &[1]).unwrap(),
+            numbered(20, &[(2, "changed 2")])
+        );
+        assert_eq!(
+            revert_hunks(&old, &new, &[0]).unwrap(),
+            numbered(20, &[(18, "changed 18")])
+        );
+        assert_eq!(revert_hunks(&old, &new, &[0, 1]).unwrap(), old);
+        assert_eq!(revert_hunks(&old, &new, &[]).unwrap(), new);
+    }
+
+    #[test]
+    fn numbers_hunks_like_reverts() {
+        let old = numbered(20, &[]);
+        let new = numbered(20, &[(2, "changed 2"), (18, "changed 18")]);
+        let hunk = |start, lines| DiffHunk {
+            old_start: start,
+            old_lines: lines,
+            new_start: start,
+            new_lines: lines,
+        };
+
+        assert_eq!(diff_hunks(&old, &new), vec![hunk(1, 5), hunk(15, 6)]);
+        assert!(diff_hunks(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn reverts_pure_insertions_and_deletions() {
+        let old = "a\nb\nc\n";
+        assert_eq!(revert_hunks(old, "a\nb\nc\nd\n", &[0]).unwrap(), old);
+        assert_eq!(revert_hunks(old, "a\nc\n", &[0]).unwrap(), old);
+        assert_eq!(revert_hunks("", "new\n", &[0]).unwrap(), "");
+        assert_eq!(revert_hunks(old, "", &[0]).unwrap(), old);
+    }
+
+    #[test]
+    fn keeps_missing_trailing_newline() {
+        assert_eq!(revert_hunks("a\nb", "a\nc", &[0]).unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn rejects_unknown_hunk() {
+        assert!(revert_hunks("a\n", "b\n", &[1]).is_none());
+    }
+}
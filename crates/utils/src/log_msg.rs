@@ -8,6 +8,8 @@ pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
 pub const EV_READY: &str = "ready";
 pub const EV_FINISHED: &str = "finished";
+/// Tells an event stream client to drop what it has and start over
+pub const EV_RESYNC: &str = "resync";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
@@ -67,6 +69,21 @@ impl LogMsg {
         Message::Text(json.into())
     }
 
+    /// Like `to_ws_message_unchecked`, with the message's sequence number as
+    /// a `seq` field that clients pass back to resume after reconnecting
+    pub fn to_sequenced_ws_message(&self, seq: Option<u64>) -> Message {
+        let Some(seq) = seq else {
+            return self.to_ws_message_unchecked();
+        };
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(mut object)) => {
+                object.insert("seq".to_string(), seq.into());
+                Message::Text(serde_json::Value::Object(object).to_string().into())
+            }
+            _ => self.to_ws_message_unchecked(),
+        }
+    }
+
     /// Rough size accounting for your byte‑budgeted history.
     pub fn approx_bytes(&self) -> usize {
         const OVERHEAD: usize = 8;
@@ -5,8 +5,9 @@ use std::{
 
 use axum::response::sse::Event;
 use futures::{StreamExt, TryStreamExt, future};
+use thiserror::Error;
 use tokio::{sync::broadcast, task::JoinHandle};
-use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 
 use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
 
@@ -22,6 +23,33 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Sequence number the next pushed message gets; numbers start at 1
+    next_seq: u64,
+}
+
+impl Inner {
+    fn first_retained_seq(&self) -> u64 {
+        self.next_seq - self.history.len() as u64
+    }
+}
+
+/// A message with its position in its store, which clients resume after
+pub type SequencedLogMsg = (u64, LogMsg);
+
+/// Ends a sequenced stream whose subscriber fell so far behind that live
+/// messages were dropped for it. It has to resume after the last sequence
+/// number it got, or start over when those are gone too.
+#[derive(Debug, Error)]
+#[error("subscriber fell {missed} messages behind")]
+pub struct StreamLagged {
+    pub missed: u64,
+}
+
+impl StreamLagged {
+    pub fn is(err: &std::io::Error) -> bool {
+        err.get_ref()
+            .is_some_and(|inner| inner.is::<StreamLagged>())
+    }
 }
 
 pub struct MsgStore {
@@ -42,13 +70,13 @@ impl MsgStore {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                next_seq: 1,
             }),
             sender,
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
-        let _ = self.sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
         let mut inner = self.inner.write().unwrap();
@@ -59,8 +87,12 @@ impl MsgStore {
                 break;
             }
         }
+        // Sent under the lock so a subscriber's first live message directly
+        // follows the history it read
+        let _ = self.sender.send(msg.clone()); // live listeners
         inner.history.push_back(StoredMsg { msg, bytes });
         inner.total_bytes = inner.total_bytes.saturating_add(bytes);
+        inner.next_seq += 1;
     }
 
     // Convenience
@@ -97,15 +129,94 @@ impl MsgStore {
             .collect()
     }
 
-    /// History then live, as `LogMsg`.
+    /// Sequence number of the last pushed message, 0 before the first one.
+    pub fn last_seq(&self) -> u64 {
+        self.inner.read().unwrap().next_seq - 1
+    }
+
+    /// Whether messages were dropped from the front of history to stay
+    /// within its byte budget.
+    pub fn has_evicted(&self) -> bool {
+        self.inner.read().unwrap().first_retained_seq() > 1
+    }
+
+    /// History then live, as `LogMsg`. Live messages a slow subscriber missed
+    /// are skipped.
     pub fn history_plus_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
-        let (history, rx) = (self.get_history(), self.get_receiver());
+        let inner = self.inner.read().unwrap();
+        self.sequenced_stream_from(&inner, inner.first_retained_seq(), false)
+            .map_ok(|(_, msg)| msg)
+            .boxed()
+    }
+
+    /// History then live, with sequence numbers. Ends with a `StreamLagged`
+    /// error when the subscriber misses live messages.
+    pub fn sequenced_history_plus_stream(
+        &self,
+    ) -> futures::stream::BoxStream<'static, Result<SequencedLogMsg, std::io::Error>> {
+        let inner = self.inner.read().unwrap();
+        self.sequenced_stream_from(&inner, inner.first_retained_seq(), true)
+    }
+
+    /// Messages after sequence number `after`, then live, ending like
+    /// `sequenced_history_plus_stream`. `None` when some of them are no longer
+    /// in history, or `after` is not a number this store has handed out.
+    pub fn stream_since(
+        &self,
+        after: u64,
+    ) -> Option<futures::stream::BoxStream<'static, Result<SequencedLogMsg, std::io::Error>>> {
+        let inner = self.inner.read().unwrap();
+        if after < inner.first_retained_seq().saturating_sub(1) || after >= inner.next_seq {
+            return None;
+        }
+        Some(self.sequenced_stream_from(&inner, after + 1, true))
+    }
+
+    fn sequenced_stream_from(
+        &self,
+        inner: &Inner,
+        from: u64,
+        end_on_lag: bool,
+    ) -> futures::stream::BoxStream<'static, Result<SequencedLogMsg, std::io::Error>> {
+        let first = inner.first_retained_seq();
+        let history: Vec<SequencedLogMsg> = inner
+            .history
+            .iter()
+            .enumerate()
+            .skip(from.saturating_sub(first) as usize)
+            .map(|(i, stored)| (first + i as u64, stored.msg.clone()))
+            .collect();
+        // Subscribing under the lock lines live messages up with history
+        let rx = self.get_receiver();
+        let next_seq = inner.next_seq;
 
         let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
         let live = BroadcastStream::new(rx)
-            .filter_map(|res| async move { res.ok().map(Ok::<_, std::io::Error>) });
+            .scan(Some(next_seq), move |state, res| {
+                let Some(seq) = state else {
+                    return future::ready(None);
+                };
+                let item = match res {
+                    Ok(msg) => {
+                        *seq += 1;
+                        Some(Ok::<_, std::io::Error>((*seq - 1, msg)))
+                    }
+                    // Messages a slow subscriber missed still take up numbers
+                    Err(BroadcastStreamRecvError::Lagged(missed)) if !end_on_lag => {
+                        *seq += missed;
+                        None
+                    }
+                    // Resuming by sequence number can't step over a gap
+                    Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                        *state = None;
+                        Some(Err(std::io::Error::other(StreamLagged { missed })))
+                    }
+                };
+                future::ready(Some(item))
+            })
+            .filter_map(future::ready);
 
         Box::pin(hist.chain(live))
     }
@@ -175,3 +286,64 @@ impl MsgStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdout(store: &MsgStore, count: usize) {
+        for i in 0..count {
+            store.push_stdout(format!("line {i}"));
+        }
+    }
+
+    async fn collect(
+        stream: futures::stream::BoxStream<'static, Result<SequencedLogMsg, std::io::Error>>,
+    ) -> Vec<u64> {
+        stream.map(|item| item.unwrap().0).collect::<Vec<_>>().await
+    }
+
+    #[tokio::test]
+    async fn stream_since_resumes_after_sequence_number() {
+        let store = MsgStore::new();
+        stdout(&store, 3);
+        let history = store.stream_since(1).unwrap();
+        let all = store.sequenced_history_plus_stream();
+        stdout(&store, 1);
+        assert_eq!(store.last_seq(), 4);
+        drop(store);
+
+        assert_eq!(collect(history).await, vec![2, 3, 4]);
+        assert_eq!(collect(all).await, vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn stream_since_rejects_evicted_and_unknown_numbers() {
+        let store = MsgStore::new();
+        stdout(&store, 2);
+        assert!(store.stream_since(2).is_some());
+        assert!(store.stream_since(3).is_none());
+
+        // Fill history past its byte budget so the first messages go
+        store.push_stdout("x".repeat(HISTORY_BYTES - 16));
+        assert!(store.has_evicted());
+        assert!(store.stream_since(0).is_none());
+        assert!(store.stream_since(store.last_seq() - 1).is_some());
+    }
+
+    #[tokio::test]
+    async fn sequenced_streams_end_when_the_subscriber_lags() {
+        let store = MsgStore::new();
+        let sequenced = store.sequenced_history_plus_stream();
+        let plain = store.history_plus_stream();
+        // One more than the live channel holds
+        stdout(&store, 10_001);
+        drop(store);
+
+        let items: Vec<_> = sequenced.collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(matches!(&items[0], Err(e) if StreamLagged::is(e)));
+        // Readers that don't resume by number skip the gap and go on
+        assert_eq!(plain.count().await, 10_000);
+    }
+}
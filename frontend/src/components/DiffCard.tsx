@@ -1,12 +1,13 @@
-import { Diff } from 'shared/types';
+import { Diff, RevertSelection } from 'shared/types';
 import { DiffModeEnum, DiffView, SplitSide } from '@git-diff-view/react';
 import { generateDiffFile, type DiffFile } from '@git-diff-view/file';
-import { useMemo } from 'react';
+import { useMemo, useState } from 'react';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { getHighLightLanguageFromPath } from '@/utils/extToLanguage';
 import { getActualTheme } from '@/utils/theme';
 import { stripLineEnding } from '@/utils/string';
 import { Button } from '@/components/ui/button';
+import { Alert, AlertDescription } from '@/components/ui/alert';
 import { DiffSide } from '@/types/diff';
 import {
   ChevronRight,
@@ -19,6 +20,7 @@ import {
   Key,
   ExternalLink,
   MessageSquare,
+  Undo2,
 } from 'lucide-react';
 import '@/styles/diff-style-overrides.css';
 import { attemptsApi } from '@/lib/api';
@@ -36,6 +38,13 @@ import {
   useWrapTextDiff,
 } from '@/stores/useDiffViewStore';
 import { useProject } from '@/contexts/ProjectContext';
+import { ConfirmDialog } from '@/components/dialogs/shared/ConfirmDialog';
+
+// Review comment and/or the hunk ending on a line
+type ExtendLineData = {
+  comment?: ReviewComment;
+  hunk?: number;
+};
 
 type Props = {
   diff: Diff;
@@ -88,6 +97,7 @@ export default function DiffCard({
   const ignoreWhitespace = useIgnoreWhitespaceDiff();
   const wrapText = useWrapTextDiff();
   const { projectId } = useProject();
+  const [revertError, setRevertError] = useState<string | null>(null);
 
   const oldName = diff.oldPath || undefined;
   const newName = diff.newPath || oldName || 'unknown';
@@ -154,25 +164,48 @@ export default function DiffCard({
     [comments, filePath]
   );
 
-  // Transform comments to git-diff-view extendData format
+  // Hunks can be reverted one by one unless the file moved, since the
+  // backend numbers them against the base version at the same path
+  const hunkPath = diff.newPath || diff.oldPath;
+  const canRevertHunks =
+    !!diff.repoId &&
+    !!hunkPath &&
+    (diff.change === 'modified' ||
+      diff.change === 'added' ||
+      diff.change === 'deleted');
+
+  // Transform comments and hunk ends to git-diff-view extendData format
   const extendData = useMemo(() => {
-    const oldFileData: Record<string, { data: ReviewComment }> = {};
-    const newFileData: Record<string, { data: ReviewComment }> = {};
+    const oldFileData: Record<string, { data: ExtendLineData }> = {};
+    const newFileData: Record<string, { data: ExtendLineData }> = {};
 
     commentsForFile.forEach((comment) => {
       const lineKey = String(comment.lineNumber);
       if (comment.side === DiffSide.Old) {
-        oldFileData[lineKey] = { data: comment };
+        oldFileData[lineKey] = { data: { comment } };
       } else {
-        newFileData[lineKey] = { data: comment };
+        newFileData[lineKey] = { data: { comment } };
       }
     });
 
+    if (canRevertHunks) {
+      diff.hunks.forEach((hunk, index) => {
+        const [fileData, lastLine] =
+          hunk.newLines > 0
+            ? [newFileData, hunk.newStart + hunk.newLines - 1]
+            : [oldFileData, hunk.oldStart + hunk.oldLines - 1];
+        const lineKey = String(lastLine);
+        fileData[lineKey] = {
+          data: { ...fileData[lineKey]?.data, hunk: index },
+        };
+      });
+    }
+
     return {
       oldFile: oldFileData,
       newFile: newFileData,
     };
-  }, [commentsForFile]);
+  }, [commentsForFile, canRevertHunks, diff.hunks]);
 
   const handleAddWidgetClick = (lineNumber: number, side: SplitSide) => {
     const diffSide = side === SplitSide.old ? DiffSide.Old : DiffSide.New;
@@ -209,9 +242,28 @@ export default function DiffCard({
     );
   };
 
-  const renderExtendLine = (lineData: { data: ReviewComment }) => {
+  const renderExtendLine = (lineData: { data: ExtendLineData }) => {
+    const { comment, hunk } = lineData.data;
     return (
-      <ReviewCommentRenderer comment={lineData.data} projectId={projectId} />
+      <>
+        {comment && (
+          <ReviewCommentRenderer comment={comment} projectId={projectId} />
+        )}
+        {hunk !== undefined && (
+          <div className="flex justify-end px-4 py-1">
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => handleRevertHunk(hunk)}
+              className="h-6 px-2 text-xs"
+              title="Revert this change"
+            >
+              <Undo2 className="h-3 w-3 mr-1" aria-hidden />
+              Revert change
+            </Button>
+          </div>
+        )}
+      </>
     );
   };
 
@@ -265,6 +317,56 @@ export default function DiffCard({
     }
   };
 
+  // Diff paths are prefixed with the repo directory
+  const repoPath = (path: string) => path.slice(path.indexOf('/') + 1);
+
+  const revert = async (
+    title: string,
+    message: string,
+    files: RevertSelection[]
+  ) => {
+    if (!selectedAttempt?.id || !diff.repoId) return;
+    const result = await ConfirmDialog.show({
+      title,
+      message,
+      confirmText: 'Revert',
+      variant: 'destructive',
+    });
+    if (result !== 'confirmed') return;
+
+    setRevertError(null);
+    try {
+      await attemptsApi.revertChanges(selectedAttempt.id, {
+        repo_id: diff.repoId,
+        files,
+      });
+    } catch (err) {
+      setRevertError(
+        err instanceof Error ? err.message : 'Failed to revert changes'
+      );
+    }
+  };
+
+  const handleRevert = () => {
+    const paths = [diff.oldPath, diff.newPath].filter(
+      (path, idx, all): path is string => !!path && all.indexOf(path) === idx
+    );
+    revert(
+      'Revert file',
+      `Revert all changes to ${newName} and commit the result?`,
+      paths.map((path) => ({ path: repoPath(path) }))
+    );
+  };
+
+  const handleRevertHunk = (hunk: number) => {
+    if (!hunkPath) return;
+    revert(
+      'Revert change',
+      `Revert this change to ${newName} and commit the result?`,
+      [{ path: repoPath(hunkPath), hunks: [hunk] }]
+    );
+  };
+
   const expandable = true;
 
   return (
@@ -300,8 +402,30 @@ export default function DiffCard({
         >
           <ExternalLink className="h-3 w-3" aria-hidden />
         </Button>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={(e) => {
+            e.stopPropagation();
+            handleRevert();
+          }}
+          className="h-6 w-6 p-0 ml-1"
+          title="Revert file"
+          disabled={!diff.repoId}
+        >
+          <Undo2 className="h-3 w-3" aria-hidden />
+        </Button>
       </div>
 
+      {revertError && (
+        <Alert
+          variant="destructive"
+          className="rounded-none border-x-0 border-t-0"
+        >
+          <AlertDescription>{revertError}</AlertDescription>
+        </Alert>
+      )}
+
       {expanded && diffFile && (
         <div>
           <DiffView
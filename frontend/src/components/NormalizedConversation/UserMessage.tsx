@@ -5,6 +5,10 @@ import type { WorkspaceWithSession } from '@/types/attempt';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useAttemptExecution } from '@/hooks/useAttemptExecution';
+import { useForkAttempt } from '@/hooks/useForkAttempt';
+import { useNavigateWithSearch } from '@/hooks';
+import { useProject } from '@/contexts/ProjectContext';
+import { paths } from '@/lib/paths';
 import { RetryEditorInline } from './RetryEditorInline';
 
 const UserMessage = ({
@@ -21,6 +25,16 @@ const UserMessage = ({
   const { activeRetryProcessId, setActiveRetryProcessId, isProcessGreyed } =
     useRetryUi();
   const { isAttemptRunning } = useAttemptExecution(taskAttempt?.id);
+  const { projectId } = useProject();
+  const navigate = useNavigateWithSearch();
+  const { forkAttempt, isForking } = useForkAttempt({
+    attemptId: taskAttempt?.id,
+    onSuccess: ({ workspace }) => {
+      if (projectId) {
+        navigate(paths.attempt(projectId, workspace.task_id, workspace.id));
+      }
+    },
+  });
 
   const canFork = !!(
     taskAttempt?.session?.executor &&
@@ -35,6 +49,13 @@ const UserMessage = ({
     setActiveRetryProcessId(executionProcessId);
   };
 
+  const startFork = () => {
+    if (!executionProcessId || isForking) return;
+    forkAttempt(executionProcessId).catch((err) =>
+      console.error('Failed to fork attempt:', err)
+    );
+  };
+
   const onCancelled = () => {
     setIsEditing(false);
     setActiveRetryProcessId(null);
@@ -51,6 +72,8 @@ const UserMessage = ({
 
   // Only show retry button when allowed (has process, can fork, not running)
   const canRetry = executionProcessId && canFork && !isAttemptRunning;
+  // Forking leaves this attempt untouched, so any agent can fork from a turn
+  const canForkWorkspace = !!executionProcessId && !isAttemptRunning;
 
   return (
     <div className={`py-2 ${greyed ? 'opacity-50 pointer-events-none' : ''}`}>
@@ -70,6 +93,7 @@ const UserMessage = ({
               className="whitespace-pre-wrap break-words flex flex-col gap-1 font-light"
               taskAttemptId={taskAttempt?.id}
               onEdit={canRetry ? startRetry : undefined}
+              onFork={canForkWorkspace ? startFork : undefined}
             />
           )}
         </div>
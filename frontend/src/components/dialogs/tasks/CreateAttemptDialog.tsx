@@ -9,6 +9,8 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
 import RepoBranchSelector from '@/components/tasks/RepoBranchSelector';
 import { ExecutorProfileSelector } from '@/components/settings';
 import { useAttemptCreation } from '@/hooks/useAttemptCreation';
@@ -50,6 +52,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
 
     const [userSelectedProfile, setUserSelectedProfile] =
       useState<ExecutorProfileId | null>(null);
+    const [stackOnParent, setStackOnParent] = useState(false);
 
     const { data: attempts = [], isLoading: isLoadingAttempts } =
       useTaskAttemptsWithSessions(taskId, {
@@ -94,6 +97,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
     useEffect(() => {
       if (!modal.visible) {
         setUserSelectedProfile(null);
+        setStackOnParent(false);
         resetBranchSelection();
       }
     }, [modal.visible, resetBranchSelection]);
@@ -152,6 +156,7 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
         await createAttempt({
           profile: effectiveProfile,
           repos,
+          stackOnParent: parentAttempt ? stackOnParent : undefined,
         });
 
         modal.hide();
@@ -199,6 +204,28 @@ const CreateAttemptDialogImpl = NiceModal.create<CreateAttemptDialogProps>(
               className="space-y-2"
             />
 
+            {parentAttempt && (
+              <div className="flex items-start space-x-2">
+                <Checkbox
+                  id="stack-on-parent"
+                  checked={stackOnParent}
+                  onCheckedChange={(checked: boolean) =>
+                    setStackOnParent(checked)
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="stack-on-parent" className="cursor-pointer">
+                    {t('createAttemptDialog.stackOnParent')}
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    {t('createAttemptDialog.stackOnParentHelper', {
+                      branch: parentAttempt.branch,
+                    })}
+                  </p>
+                </div>
+              </div>
+            )}
+
             {error && (
               <div className="text-sm text-destructive">
                 {t('createAttemptDialog.error')}
@@ -11,6 +11,7 @@ export type Props = Readonly<{
   onAbort: () => void;
   op?: ConflictOp | null;
   onResolve?: () => void;
+  onResolveWithAgent?: () => void;
   enableResolve: boolean;
   enableAbort: boolean;
 }>;
@@ -45,6 +46,7 @@ export function ConflictBanner({
   onAbort,
   op,
   onResolve,
+  onResolveWithAgent,
   enableResolve,
   enableAbort,
 }: Props) {
@@ -105,6 +107,17 @@ export function ConflictBanner({
             Resolve conflicts
           </Button>
         )}
+        {onResolveWithAgent && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={onResolveWithAgent}
+            disabled={!enableResolve}
+            className="border-warning/40 text-warning-foreground hover:bg-warning/10 dark:text-warning/90"
+          >
+            Resolve with agent
+          </Button>
+        )}
         <Button
           size="sm"
           variant="outline"
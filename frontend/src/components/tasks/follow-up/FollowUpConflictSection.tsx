@@ -30,7 +30,10 @@ export function FollowUpConflictSection({
   const op = repoWithConflicts?.conflict_op ?? null;
   const openInEditor = useOpenInEditor(workspaceId);
   const repoId = repoWithConflicts?.repo_id;
-  const { abortConflicts } = useAttemptConflicts(workspaceId, repoId);
+  const { abortConflicts, resolveConflictsWithAgent } = useAttemptConflicts(
+    workspaceId,
+    repoId
+  );
 
   // write using setAborting and read through abortingRef in async handlers
   const [aborting, setAborting] = useState(false);
@@ -49,6 +52,14 @@ export function FollowUpConflictSection({
         conflictedFiles={repoWithConflicts.conflicted_files || []}
         op={op}
         onResolve={onResolve}
+        onResolveWithAgent={async () => {
+          if (!workspaceId || !enableResolve || abortingRef.current) return;
+          try {
+            await resolveConflictsWithAgent();
+          } catch (e) {
+            console.error('Failed to hand conflicts to the agent', e);
+          }
+        }}
         enableResolve={enableResolve && !aborting}
         onOpenEditor={() => {
           if (!workspaceId) return;
@@ -6,7 +6,7 @@ import { GitBranchIcon, SpinnerIcon } from '@phosphor-icons/react';
 import { useRepoBranches } from '@/hooks/useRepoBranches';
 import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { repoApi } from '@/lib/api';
-import type { Repo, UpdateRepo } from 'shared/types';
+import type { MergeStrategy, Repo, UpdateRepo } from 'shared/types';
 import { SearchableDropdownContainer } from '../../containers/SearchableDropdownContainer';
 import {
   DropdownMenu,
@@ -21,6 +21,7 @@ import {
   SettingsInput,
   SettingsTextarea,
   SettingsCheckbox,
+  SettingsSelect,
   SettingsSaveBar,
 } from './SettingsComponents';
 
@@ -30,8 +31,11 @@ interface RepoScriptsFormState {
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
+  verification_script: string;
   copy_files: string;
   dev_server_script: string;
+  merge_strategy: MergeStrategy;
+  merge_commit_template: string;
 }
 
 function repoToFormState(repo: Repo): RepoScriptsFormState {
@@ -41,8 +45,11 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     setup_script: repo.setup_script ?? '',
     parallel_setup_script: repo.parallel_setup_script,
     cleanup_script: repo.cleanup_script ?? '',
+    verification_script: repo.verification_script ?? '',
     copy_files: repo.copy_files ?? '',
     dev_server_script: repo.dev_server_script ?? '',
+    merge_strategy: repo.merge_strategy,
+    merge_commit_template: repo.merge_commit_template ?? '',
   };
 }
 
@@ -91,6 +98,15 @@ export function ReposSettingsSection() {
   // Get OS-appropriate script placeholders
   const placeholders = useScriptPlaceholders();
 
+  const mergeStrategyOptions: { value: MergeStrategy; label: string }[] = [
+    { value: 'squash', label: t('settings.repos.merge.strategy.squash') },
+    { value: 'rebase', label: t('settings.repos.merge.strategy.rebase') },
+    {
+      value: 'merge_commit',
+      label: t('settings.repos.merge.strategy.mergeCommit'),
+    },
+  ];
+
   // Check for unsaved changes
   const hasUnsavedChanges = useMemo(() => {
     if (!draft || !selectedRepo) return false;
@@ -153,9 +169,12 @@ export function ReposSettingsSection() {
         default_target_branch: draft.default_target_branch.trim() || null,
         setup_script: draft.setup_script.trim() || null,
         cleanup_script: draft.cleanup_script.trim() || null,
+        verification_script: draft.verification_script.trim() || null,
         copy_files: draft.copy_files.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
+        merge_strategy: draft.merge_strategy,
+        merge_commit_template: draft.merge_commit_template.trim() || null,
       };
 
       const updatedRepo = await repoApi.update(selectedRepo.id, updateData);
@@ -345,6 +364,38 @@ export function ReposSettingsSection() {
             </SettingsField>
           </SettingsCard>
 
+          {/* Merge settings */}
+          <SettingsCard
+            title={t('settings.repos.merge.title')}
+            description={t('settings.repos.merge.description')}
+          >
+            <SettingsField
+              label={t('settings.repos.merge.strategy.label')}
+              description={t('settings.repos.merge.strategy.helper')}
+            >
+              <SettingsSelect
+                value={draft.merge_strategy}
+                options={mergeStrategyOptions}
+                onChange={(value) => updateDraft({ merge_strategy: value })}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.merge.template.label')}
+              description={t('settings.repos.merge.template.helper')}
+            >
+              <SettingsTextarea
+                value={draft.merge_commit_template}
+                onChange={(value) =>
+                  updateDraft({ merge_commit_template: value })
+                }
+                placeholder={t('settings.repos.merge.template.placeholder')}
+                rows={3}
+                monospace
+              />
+            </SettingsField>
+          </SettingsCard>
+
           {/* Scripts settings */}
           <SettingsCard
             title={t('settings.repos.scripts.title')}
@@ -397,6 +448,19 @@ export function ReposSettingsSection() {
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.scripts.verification.label')}
+              description={t('settings.repos.scripts.verification.helper')}
+            >
+              <SettingsTextarea
+                value={draft.verification_script}
+                onChange={(value) =>
+                  updateDraft({ verification_script: value })
+                }
+                monospace
+              />
+            </SettingsField>
+
             <SettingsField
               label={t('settings.repos.scripts.copyFiles.label')}
               description={t('settings.repos.scripts.copyFiles.helper')}
@@ -64,6 +64,7 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
+        ep.run_reason === 'verificationscript' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -286,6 +287,9 @@ export const useConversationHistory = ({
               case 'CleanupScript':
                 toolName = 'Cleanup Script';
                 break;
+              case 'VerificationScript':
+                toolName = 'Verification Script';
+                break;
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
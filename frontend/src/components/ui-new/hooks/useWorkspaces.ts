@@ -27,6 +27,7 @@ export interface SidebarWorkspace {
   latestProcessCompletedAt?: string;
   latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
   prStatus?: 'open' | 'merged' | 'closed' | 'unknown';
+  ciStatus?: 'pending' | 'success' | 'failure';
 }
 
 // Keep the old export name for backwards compatibility
@@ -71,6 +72,8 @@ function toSidebarWorkspace(
     latestProcessCompletedAt: summary?.latest_process_completed_at ?? undefined,
     latestProcessStatus: summary?.latest_process_status ?? undefined,
     prStatus: summary?.pr_status ?? undefined,
+    // CI status is pushed on the stream as checks finish
+    ciStatus: ws.pr_ci_status ?? undefined,
   };
 }
 
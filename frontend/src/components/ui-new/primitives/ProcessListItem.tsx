@@ -25,6 +25,7 @@ const RUN_REASON_LABELS: Record<ExecutionProcessRunReason, string> = {
   codingagent: 'Coding Agent',
   setupscript: 'Setup Script',
   cleanupscript: 'Cleanup Script',
+  verificationscript: 'Verification Script',
   devserver: 'Dev Server',
 };
 
@@ -33,6 +34,7 @@ const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
     codingagent: CodeIcon,
     setupscript: GearIcon,
     cleanupscript: GearIcon,
+    verificationscript: GearIcon,
     devserver: GlobeIcon,
   };
 
@@ -7,6 +7,8 @@ import {
   CircleIcon,
   GitPullRequestIcon,
   DotsThreeIcon,
+  CheckCircleIcon,
+  XCircleIcon,
 } from '@phosphor-icons/react';
 import { useTranslation } from 'react-i18next';
 import { cn } from '@/lib/utils';
@@ -29,6 +31,7 @@ interface WorkspaceSummaryProps {
   latestProcessCompletedAt?: string;
   latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
   prStatus?: 'open' | 'merged' | 'closed' | 'unknown';
+  ciStatus?: 'pending' | 'success' | 'failure';
   onClick?: () => void;
   className?: string;
   summary?: boolean;
@@ -51,6 +54,7 @@ export function WorkspaceSummary({
   latestProcessCompletedAt,
   latestProcessStatus,
   prStatus,
+  ciStatus,
   onClick,
   className,
   summary = false,
@@ -158,6 +162,20 @@ export function WorkspaceSummary({
               />
             )}
 
+            {/* CI status of the open PR */}
+            {prStatus === 'open' && ciStatus === 'failure' && (
+              <XCircleIcon
+                className="size-icon-xs text-error shrink-0"
+                weight="fill"
+              />
+            )}
+            {prStatus === 'open' && ciStatus === 'success' && (
+              <CheckCircleIcon
+                className="size-icon-xs text-success shrink-0"
+                weight="fill"
+              />
+            )}
+
             {/* Pin icon */}
             {isPinned && (
               <PushPinIcon
@@ -73,6 +73,7 @@ function WorkspaceList({
           latestProcessCompletedAt={workspace.latestProcessCompletedAt}
           latestProcessStatus={workspace.latestProcessStatus}
           prStatus={workspace.prStatus}
+          ciStatus={workspace.ciStatus}
           onClick={() => onSelectWorkspace(workspace.id)}
         />
       ))}
@@ -197,6 +198,7 @@ export function WorkspacesSidebar({
                   latestProcessCompletedAt={workspace.latestProcessCompletedAt}
                   latestProcessStatus={workspace.latestProcessStatus}
                   prStatus={workspace.prStatus}
+                  ciStatus={workspace.ciStatus}
                   onClick={() => onSelectWorkspace(workspace.id)}
                 />
               ))
@@ -310,6 +312,7 @@ export function WorkspacesSidebar({
                 latestProcessCompletedAt={workspace.latestProcessCompletedAt}
                 latestProcessStatus={workspace.latestProcessStatus}
                 prStatus={workspace.prStatus}
+                ciStatus={workspace.ciStatus}
                 onClick={() => onSelectWorkspace(workspace.id)}
               />
             ))}
@@ -9,7 +9,7 @@ import {
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu';
 import { MoreHorizontal } from 'lucide-react';
-import type { TaskWithAttemptStatus } from 'shared/types';
+import type { TaskWithAttemptStatus, TranscriptFormat } from 'shared/types';
 import { useOpenInEditor } from '@/hooks/useOpenInEditor';
 import { DeleteTaskConfirmationDialog } from '@/components/dialogs/tasks/DeleteTaskConfirmationDialog';
 import { ViewProcessesDialog } from '@/components/dialogs/tasks/ViewProcessesDialog';
@@ -19,6 +19,7 @@ import { GitActionsDialog } from '@/components/dialogs/tasks/GitActionsDialog';
 import { EditBranchNameDialog } from '@/components/dialogs/tasks/EditBranchNameDialog';
 import { useProject } from '@/contexts/ProjectContext';
 import { openTaskForm } from '@/lib/openTaskForm';
+import { attemptsApi } from '@/lib/api';
 
 import { useNavigate } from 'react-router-dom';
 import { WorkspaceWithSession } from '@/types/attempt';
@@ -128,6 +129,15 @@ export function ActionsDropdown({ task, attempt }: ActionsDropdownProps) {
     });
   };
 
+  const handleExportTranscript = (
+    e: React.MouseEvent,
+    format: TranscriptFormat
+  ) => {
+    e.stopPropagation();
+    if (!attempt?.id) return;
+    window.open(attemptsApi.getTranscriptUrl(attempt.id, format), '_blank');
+  };
+
   return (
     <>
       <DropdownMenu>
@@ -185,6 +195,18 @@ export function ActionsDropdown({ task, attempt }: ActionsDropdownProps) {
               >
                 {t('actionsMenu.editBranchName')}
               </DropdownMenuItem>
+              <DropdownMenuItem
+                disabled={!attempt?.id}
+                onClick={(e) => handleExportTranscript(e, 'markdown')}
+              >
+                {t('actionsMenu.exportMarkdown')}
+              </DropdownMenuItem>
+              <DropdownMenuItem
+                disabled={!attempt?.id}
+                onClick={(e) => handleExportTranscript(e, 'html')}
+              >
+                {t('actionsMenu.exportHtml')}
+              </DropdownMenuItem>
               <DropdownMenuSeparator />
             </>
           )}
@@ -42,7 +42,7 @@ import { TablePlugin } from '@lexical/react/LexicalTablePlugin';
 import { EditorState } from 'lexical';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
-import { Check, Clipboard, Pencil, Trash2 } from 'lucide-react';
+import { Check, Clipboard, GitFork, Pencil, Trash2 } from 'lucide-react';
 import { writeClipboardViaBridge } from '@/vscode/bridge';
 import type { SendMessageShortcut } from 'shared/types';
 import type { BaseCodingAgent } from 'shared/types';
@@ -79,6 +79,8 @@ type WysiwygProps = {
   localImages?: LocalImageMetadata[];
   /** Optional edit callback - shows edit button in read-only mode when provided */
   onEdit?: () => void;
+  /** Optional fork callback - shows fork button in read-only mode when provided */
+  onFork?: () => void;
   /** Optional delete callback - shows delete button in read-only mode when provided */
   onDelete?: () => void;
   /** Auto-focus the editor on mount */
@@ -108,6 +110,7 @@ function WYSIWYGEditor({
   repoId,
   localImages,
   onEdit,
+  onFork,
   onDelete,
   autoFocus = false,
   findMatchingDiffPath,
@@ -340,6 +343,20 @@ function WYSIWYGEditor({
                 <Pencil className="w-4 h-4 text-muted-foreground" />
               </Button>
             )}
+            {/* Fork button - only if onFork provided */}
+            {onFork && (
+              <Button
+                type="button"
+                aria-label="Fork from here"
+                title="Fork from here"
+                variant="icon"
+                size="icon"
+                onClick={onFork}
+                className="pointer-events-auto p-2 bg-muted h-8 w-8"
+              >
+                <GitFork className="w-4 h-4 text-muted-foreground" />
+              </Button>
+            )}
             {/* Delete button - only if onDelete provided */}
             {onDelete && (
               <Button
@@ -50,7 +50,8 @@ export const ExecutionProcessesProvider: React.FC<{
         (process) =>
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'setupscript' ||
-            process.run_reason === 'cleanupscript') &&
+            process.run_reason === 'cleanupscript' ||
+            process.run_reason === 'verificationscript') &&
           process.status === 'running'
       ),
     [visible]
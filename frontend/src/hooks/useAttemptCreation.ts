@@ -10,6 +10,7 @@ import type {
 type CreateAttemptArgs = {
   profile: ExecutorProfileId;
   repos: WorkspaceRepoInput[];
+  stackOnParent?: boolean;
 };
 
 type UseAttemptCreationArgs = {
@@ -24,11 +25,12 @@ export function useAttemptCreation({
   const queryClient = useQueryClient();
 
   const mutation = useMutation({
-    mutationFn: ({ profile, repos }: CreateAttemptArgs) =>
+    mutationFn: ({ profile, repos, stackOnParent }: CreateAttemptArgs) =>
       attemptsApi.create({
         task_id: taskId,
         executor_profile_id: profile,
         repos,
+        stack_on_parent: stackOnParent,
       }),
     onSuccess: (newAttempt: Workspace) => {
       queryClient.setQueryData(
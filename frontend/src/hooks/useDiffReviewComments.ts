@@ -0,0 +1,95 @@
+import { useCallback, useMemo, useState } from 'react';
+import {
+  ScratchType,
+  type DiffReviewComment,
+  type ReviewCommentsData,
+} from 'shared/types';
+import { attemptsApi } from '@/lib/api';
+import { useScratch } from './useScratch';
+
+export type NewDiffReviewComment = Omit<DiffReviewComment, 'id' | 'status'>;
+
+export interface UseDiffReviewCommentsResult {
+  comments: DiffReviewComment[];
+  pendingCount: number;
+  isLoading: boolean;
+  isSending: boolean;
+  addComment: (comment: NewDiffReviewComment) => Promise<void>;
+  updateComment: (id: string, text: string) => Promise<void>;
+  deleteComment: (id: string) => Promise<void>;
+  sendReview: () => Promise<void>;
+}
+
+/**
+ * Hook for line-anchored review comments on a workspace diff, stored in
+ * scratch memory. Sending turns the pending ones into a single follow-up.
+ */
+export function useDiffReviewComments(
+  workspaceId: string | undefined
+): UseDiffReviewCommentsResult {
+  const { scratch, updateScratch, isLoading } = useScratch(
+    ScratchType.REVIEW_COMMENTS,
+    workspaceId ?? '',
+    { enabled: !!workspaceId }
+  );
+  const [isSending, setIsSending] = useState(false);
+
+  const comments = useMemo(() => {
+    const data: ReviewCommentsData | undefined =
+      scratch?.payload?.type === 'REVIEW_COMMENTS'
+        ? scratch.payload.data
+        : undefined;
+    return data?.comments ?? [];
+  }, [scratch]);
+
+  const save = useCallback(
+    async (next: DiffReviewComment[]) => {
+      if (!workspaceId) return;
+      await updateScratch({
+        payload: { type: 'REVIEW_COMMENTS', data: { comments: next } },
+      });
+    },
+    [workspaceId, updateScratch]
+  );
+
+  const addComment = useCallback(
+    (comment: NewDiffReviewComment) =>
+      save([
+        ...comments,
+        { ...comment, id: crypto.randomUUID(), status: 'pending' },
+      ]),
+    [comments, save]
+  );
+
+  const updateComment = useCallback(
+    (id: string, text: string) =>
+      save(comments.map((c) => (c.id === id ? { ...c, text } : c))),
+    [comments, save]
+  );
+
+  const deleteComment = useCallback(
+    (id: string) => save(comments.filter((c) => c.id !== id)),
+    [comments, save]
+  );
+
+  const sendReview = useCallback(async () => {
+    if (!workspaceId) return;
+    setIsSending(true);
+    try {
+      await attemptsApi.sendReview(workspaceId);
+    } finally {
+      setIsSending(false);
+    }
+  }, [workspaceId]);
+
+  return {
+    comments,
+    pendingCount: comments.filter((c) => c.status === 'pending').length,
+    isLoading,
+    isSending,
+    addComment,
+    updateComment,
+    deleteComment,
+    sendReview,
+  };
+}
@@ -0,0 +1,34 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import { workspaceSummaryKeys } from '@/components/ui-new/hooks/useWorkspaces';
+import type { ForkTaskAttemptResponse, Workspace } from 'shared/types';
+
+type UseForkAttemptArgs = {
+  attemptId?: string;
+  onSuccess?: (result: ForkTaskAttemptResponse) => void;
+};
+
+export function useForkAttempt({ attemptId, onSuccess }: UseForkAttemptArgs) {
+  const queryClient = useQueryClient();
+
+  const mutation = useMutation({
+    mutationFn: (processId: string) => {
+      if (!attemptId) throw new Error('No attempt to fork');
+      return attemptsApi.fork(attemptId, { process_id: processId });
+    },
+    onSuccess: (result: ForkTaskAttemptResponse) => {
+      queryClient.setQueryData(
+        ['taskAttempts', result.workspace.task_id],
+        (old: Workspace[] = []) => [result.workspace, ...old]
+      );
+      queryClient.invalidateQueries({ queryKey: workspaceSummaryKeys.all });
+      onSuccess?.(result);
+    },
+  });
+
+  return {
+    forkAttempt: mutation.mutateAsync,
+    isForking: mutation.isPending,
+    error: mutation.error,
+  };
+}
@@ -1,9 +1,13 @@
 import { useMutation, useQueryClient } from '@tanstack/react-query';
 import { attemptsApi } from '@/lib/api';
+import type { MergeStrategy } from 'shared/types';
 import { repoBranchKeys } from './useRepoBranches';
 
 type MergeParams = {
   repoId: string;
+  // Fall back to the repository's merge settings when omitted
+  strategy?: MergeStrategy;
+  commitMessage?: string;
 };
 
 export function useMerge(
@@ -18,6 +22,8 @@ export function useMerge(
       if (!attemptId) return Promise.resolve();
       return attemptsApi.merge(attemptId, {
         repo_id: params.repoId,
+        strategy: params.strategy,
+        commit_message: params.commitMessage,
       });
     },
     onSuccess: () => {
@@ -15,6 +15,8 @@ import {
   DirectoryEntry,
   ExecutionProcess,
   ExecutionProcessRepoState,
+  ExecutionStart,
+  NormalizedEntriesPage,
   GitBranch,
   Project,
   Repo,
@@ -82,6 +84,13 @@ import {
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ResolveConflictsRequest,
+  RevertChangesRequest,
+  RevertChangesResponse,
+  ForkTaskAttemptRequest,
+  ForkTaskAttemptResponse,
+  SendReviewResponse,
+  TranscriptFormat,
   Session,
   Workspace,
   StartReviewRequest,
@@ -400,23 +409,27 @@ export const sessionsApi = {
   followUp: async (
     sessionId: string,
     data: CreateFollowUpAttempt
-  ): Promise<ExecutionProcess> => {
+  ): Promise<ExecutionStart> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/follow-up`, {
       method: 'POST',
       body: JSON.stringify(data),
     });
-    return handleApiResponse<ExecutionProcess>(response);
+    return handleApiResponse<ExecutionStart>(response);
   },
 
   startReview: async (
     sessionId: string,
     data: StartReviewRequest
-  ): Promise<ExecutionProcess> => {
+  ): Promise<ExecutionStart> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/review`, {
       method: 'POST',
       body: JSON.stringify(data),
     });
-    return handleApiResponse<ExecutionProcess, ReviewError>(response);
+    return handleApiResponse<ExecutionStart, ReviewError>(response);
+  },
+
+  getTranscriptUrl: (sessionId: string, format: TranscriptFormat): string => {
+    return `/api/sessions/${sessionId}/transcript?format=${format}`;
   },
 };
 
@@ -650,6 +663,62 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  revertChanges: async (
+    attemptId: string,
+    data: RevertChangesRequest
+  ): Promise<RevertChangesResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/revert`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RevertChangesResponse>(response);
+  },
+
+  fork: async (
+    attemptId: string,
+    data: ForkTaskAttemptRequest
+  ): Promise<ForkTaskAttemptResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/fork`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ForkTaskAttemptResponse>(response);
+  },
+
+  sendReview: async (attemptId: string): Promise<SendReviewResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/review/send`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<SendReviewResponse>(response);
+  },
+
+  getTranscriptUrl: (attemptId: string, format: TranscriptFormat): string => {
+    return `/api/task-attempts/${attemptId}/transcript?format=${format}`;
+  },
+
   createPR: async (
     attemptId: string,
     data: CreatePrApiRequest
@@ -761,6 +830,37 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcessRepoState[]>(response);
   },
 
+  /**
+   * Page through the normalized conversation of a process. Without a cursor
+   * this returns the latest entries; pass `before` with the first
+   * `entry_index` of a page to load the entries preceding it.
+   */
+  getNormalizedEntries: async (
+    processId: string,
+    opts: {
+      before?: number;
+      after?: number;
+      limit?: number;
+      entryTypes?: string[];
+      actionTypes?: string[];
+    } = {}
+  ): Promise<NormalizedEntriesPage> => {
+    const params = new URLSearchParams();
+    if (opts.before !== undefined) params.set('before', String(opts.before));
+    if (opts.after !== undefined) params.set('after', String(opts.after));
+    if (opts.limit !== undefined) params.set('limit', String(opts.limit));
+    if (opts.entryTypes?.length) {
+      params.set('entry_types', opts.entryTypes.join(','));
+    }
+    if (opts.actionTypes?.length) {
+      params.set('action_types', opts.actionTypes.join(','));
+    }
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/normalized-entries?${params.toString()}`
+    );
+    return handleApiResponse<NormalizedEntriesPage>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
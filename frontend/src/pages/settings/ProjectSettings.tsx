@@ -19,6 +19,7 @@ import {
   SelectValue,
 } from '@/components/ui/select';
 import { Label } from '@/components/ui/label';
+import { Checkbox } from '@/components/ui/checkbox';
 import { Input } from '@/components/ui/input';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Loader2, Plus, Trash2 } from 'lucide-react';
@@ -34,11 +35,13 @@ import type { Project, Repo, UpdateProject } from 'shared/types';
 
 interface ProjectFormState {
   name: string;
+  auto_follow_up_pr: boolean;
 }
 
 function projectToFormState(project: Project): ProjectFormState {
   return {
     name: project.name,
+    auto_follow_up_pr: project.auto_follow_up_pr,
   };
 }
 
@@ -296,6 +299,7 @@ export function ProjectSettings() {
     try {
       const updateData: UpdateProject = {
         name: draft.name.trim(),
+        auto_follow_up_pr: draft.auto_follow_up_pr,
       };
 
       updateProject.mutate({
@@ -429,6 +433,24 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="auto-follow-up-pr"
+                  checked={draft.auto_follow_up_pr}
+                  onCheckedChange={(checked: boolean) =>
+                    updateDraft({ auto_follow_up_pr: checked })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="auto-follow-up-pr" className="cursor-pointer">
+                    {t('settings.projects.general.autoFollowUpPr.label')}
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    {t('settings.projects.general.autoFollowUpPr.helper')}
+                  </p>
+                </div>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedChanges ? (
@@ -3,6 +3,9 @@ import { applyPatch, type Operation } from 'rfc6902';
 
 type PatchContainer<E = unknown> = { entries: E[] };
 
+const MAX_RECONNECT_ATTEMPTS = 5;
+const RECONNECT_BASE_DELAY_MS = 500;
+
 export interface StreamOptions<E = unknown> {
   initial?: PatchContainer<E>;
   /** called after each successful patch application */
@@ -28,26 +31,33 @@ interface StreamController<E = unknown> {
 
 /**
  * Connect to a WebSocket endpoint that emits JSON messages containing:
- *   {"JsonPatch": [{"op": "add", "path": "/entries/0", "value": {...}}, ...]}
+ *   {"JsonPatch": [{"op": "add", "path": "/entries/0", "value": {...}}, ...],
+ *    "seq": 12}
  *   {"Finished": ""}
  *
  * Maintains an in-memory { entries: [] } snapshot and returns a controller.
+ * When the connection drops before "finished", it reconnects with the `seq`
+ * of the last applied patch so the server only sends what was missed.
  */
 export function streamJsonPatchEntries<E = unknown>(
   url: string,
   opts: StreamOptions<E> = {}
 ): StreamController<E> {
   let connected = false;
-  let snapshot: PatchContainer<E> = structuredClone(
-    opts.initial ?? ({ entries: [] } as PatchContainer<E>)
-  );
+  const initial = opts.initial ?? ({ entries: [] } as PatchContainer<E>);
+  let snapshot: PatchContainer<E> = structuredClone(initial);
 
   const subscribers = new Set<(entries: E[]) => void>();
   if (opts.onEntries) subscribers.add(opts.onEntries);
 
   // Convert HTTP endpoint to WebSocket endpoint
   const wsUrl = url.replace(/^http/, 'ws');
-  const ws = new WebSocket(wsUrl);
+  let ws: WebSocket;
+  let lastSeq: number | undefined;
+  let finished = false;
+  let closed = false;
+  let reconnectAttempts = 0;
+  let reconnectTimer: ReturnType<typeof setTimeout> | undefined;
 
   const notify = () => {
     for (const cb of subscribers) {
@@ -65,6 +75,7 @@ export function streamJsonPatchEntries<E = unknown>(
 
       // Handle JsonPatch messages (from LogMsg::to_ws_message)
       if (msg.JsonPatch) {
+        reconnectAttempts = 0;
         const raw = msg.JsonPatch as Operation[];
         const ops = dedupeOps(raw);
 
@@ -73,11 +84,13 @@ export function streamJsonPatchEntries<E = unknown>(
         applyPatch(next as unknown as object, ops);
 
         snapshot = next;
+        if (typeof msg.seq === 'number') lastSeq = msg.seq;
         notify();
       }
 
       // Handle Finished messages
       if (msg.finished !== undefined) {
+        finished = true;
         opts.onFinished?.(snapshot.entries);
         ws.close();
       }
@@ -86,21 +99,45 @@ export function streamJsonPatchEntries<E = unknown>(
     }
   };
 
-  ws.addEventListener('open', () => {
-    connected = true;
-    opts.onConnect?.();
-  });
+  const scheduleReconnect = () => {
+    if (finished || closed || reconnectAttempts >= MAX_RECONNECT_ATTEMPTS) {
+      return;
+    }
+    const delay = RECONNECT_BASE_DELAY_MS * 2 ** reconnectAttempts;
+    reconnectAttempts += 1;
+    reconnectTimer = setTimeout(connect, delay);
+  };
+
+  function connect() {
+    reconnectTimer = undefined;
+    let target = wsUrl;
+    if (lastSeq !== undefined) {
+      target += `${wsUrl.includes('?') ? '&' : '?'}since=${lastSeq}`;
+    } else {
+      // Without a position to resume from, the server starts over
+      snapshot = structuredClone(initial);
+    }
+    ws = new WebSocket(target);
+
+    ws.addEventListener('open', () => {
+      connected = true;
+      opts.onConnect?.();
+    });
 
-  ws.addEventListener('message', handleMessage);
+    ws.addEventListener('message', handleMessage);
 
-  ws.addEventListener('error', (err) => {
-    connected = false;
-    opts.onError?.(err);
-  });
+    ws.addEventListener('error', (err) => {
+      connected = false;
+      opts.onError?.(err);
+    });
+
+    ws.addEventListener('close', () => {
+      connected = false;
+      scheduleReconnect();
+    });
+  }
 
-  ws.addEventListener('close', () => {
-    connected = false;
-  });
+  connect();
 
   return {
     getEntries(): E[] {
@@ -119,6 +156,8 @@ export function streamJsonPatchEntries<E = unknown>(
       return () => subscribers.delete(cb);
     },
     close(): void {
+      closed = true;
+      clearTimeout(reconnectTimer);
       ws.close();
       subscribers.clear();
       connected = false;
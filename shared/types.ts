@@ -4,11 +4,19 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, 
+/**
+ * Coding agents of this project allowed to run at once, unlimited when unset
+ */
+max_concurrent_coding_agents: number | null, 
+/**
+ * Send new PR review comments and CI failures back to the agent
+ */
+auto_follow_up_pr: boolean, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type UpdateProject = { name: string | null, max_concurrent_coding_agents?: number | null, auto_follow_up_pr?: boolean, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -18,9 +26,79 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+/**
+ * Where the text of a search hit comes from
+ */
+export type SearchSource = "task" | "prompt" | "summary" | "assistant_message" | "command";
+
+export type SearchHit = { source: SearchSource, task_id: string, task_title: string, project_id: string, 
+/**
+ * `None` for task hits
+ */
+workspace_id: string | null, 
+/**
+ * `None` for task hits
+ */
+execution_process_id: string | null, 
+/**
+ * Matching text with matches wrapped in `<mark>` tags; the text itself is
+ * not escaped
+ */
+snippet: string, 
+/**
+ * Lower is better
+ */
+rank: number, };
+
+/**
+ * Space a table takes in the database file, its indexes included
+ */
+export type TableSize = { name: string, bytes: bigint, };
+
+/**
+ * Stored log payload of a project's execution processes
+ */
+export type ProjectStorageSize = { project_id: string, project_name: string, 
+/**
+ * Raw logs, counting compressed chunks at their compressed size
+ */
+log_bytes: bigint, normalized_entry_bytes: bigint, };
+
+export type DatabaseSize = { 
+/**
+ * Size of the database file
+ */
+total_bytes: bigint, 
+/**
+ * Unused pages in the file, reused before the file grows
+ */
+free_bytes: bigint, 
+/**
+ * Largest first. Empty when SQLite was built without the dbstat table,
+ * which leaves only the totals.
+ */
+tables: Array<TableSize>, 
+/**
+ * Largest first
+ */
+projects: Array<ProjectStorageSize>, };
+
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, 
+/**
+ * Run after every coding agent turn; failures are fed back to the agent
+ */
+verification_script: string | null, 
+/**
+ * How direct merges land task branches on the base branch
+ */
+merge_strategy: MergeStrategy, 
+/**
+ * Commit message of direct merges, with `{title}`, `{description}`,
+ * `{task_id}`, `{short_id}` and `{branch}` placeholders
+ */
+merge_commit_template: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, verification_script?: string | null, merge_strategy?: MergeStrategy | null, merge_commit_template?: string | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
@@ -30,7 +108,20 @@ export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string,
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, 
+/**
+ * Run after every coding agent turn; failures are fed back to the agent
+ */
+verification_script: string | null, 
+/**
+ * How direct merges land task branches on the base branch
+ */
+merge_strategy: MergeStrategy, 
+/**
+ * Commit message of direct merges, with `{title}`, `{description}`,
+ * `{task_id}`, `{short_id}` and `{branch}` placeholders
+ */
+merge_commit_template: string | null, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -42,14 +133,58 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, 
+/**
+ * An attempt is waiting for a free coding agent slot
+ */
+has_queued_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
+export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, 
+/**
+ * Workspaces stacked with the current one, bottom first; empty when it isn't stacked
+ */
+stack: Array<StackedWorkspace>, };
+
+export type StackedWorkspace = { task: Task, workspace_id: string, branch: string, 
+/**
+ * 0 for the bottom of the stack, which is based on a regular branch
+ */
+depth: number, };
 
 export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
+/**
+ * Attempt to start automatically once every blocker of the task is done
+ */
+export type TaskAutoStart = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, created_at: string, };
+
+/**
+ * Cron schedule that starts a fresh attempt of a task whenever it fires
+ */
+export type TaskSchedule = { id: string, task_id: string, 
+/**
+ * Five field cron expression evaluated in the server's local time
+ */
+cron: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, 
+/**
+ * Treat the task as a template and run each firing as a new task
+ */
+create_new_task: boolean, enabled: boolean, 
+/**
+ * `None` while disabled
+ */
+next_run_at: string | null, last_run_at: string | null, 
+/**
+ * Attempt started by the last run
+ */
+last_workspace_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { task_id: string, cron: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, create_new_task: boolean, enabled: boolean, };
+
+export type UpdateTaskSchedule = { cron: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, create_new_task: boolean, enabled: boolean, };
+
 export type DraftFollowUpData = { message: string, executor_profile_id: ExecutorProfileId, };
 
 export type DraftWorkspaceData = { message: string, project_id: string | null, repos: Array<DraftWorkspaceRepo>, selected_profile: ExecutorProfileId | null, };
@@ -60,9 +195,27 @@ export type PreviewSettingsData = { url: string, screen_size: string | null, res
 
 export type WorkspaceNotesData = { content: string, };
 
-export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData } | { "type": "DRAFT_WORKSPACE", "data": DraftWorkspaceData } | { "type": "PREVIEW_SETTINGS", "data": PreviewSettingsData } | { "type": "WORKSPACE_NOTES", "data": WorkspaceNotesData };
+export type ReviewCommentsData = { comments: Array<DiffReviewComment>, };
 
-export enum ScratchType { DRAFT_TASK = "DRAFT_TASK", DRAFT_FOLLOW_UP = "DRAFT_FOLLOW_UP", DRAFT_WORKSPACE = "DRAFT_WORKSPACE", PREVIEW_SETTINGS = "PREVIEW_SETTINGS", WORKSPACE_NOTES = "WORKSPACE_NOTES" }
+export type DiffReviewComment = { id: string, repo_id: string, 
+/**
+ * Path relative to the repository root
+ */
+file_path: string, 
+/**
+ * First commented line (1-based) in the workspace's version of the file
+ */
+start_line: number, 
+/**
+ * Last commented line, inclusive
+ */
+end_line: number, text: string, status: ReviewCommentStatus, };
+
+export type ReviewCommentStatus = "pending" | "sent" | "resolved";
+
+export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData } | { "type": "DRAFT_WORKSPACE", "data": DraftWorkspaceData } | { "type": "PREVIEW_SETTINGS", "data": PreviewSettingsData } | { "type": "WORKSPACE_NOTES", "data": WorkspaceNotesData } | { "type": "REVIEW_COMMENTS", "data": ReviewCommentsData };
+
+export enum ScratchType { DRAFT_TASK = "DRAFT_TASK", DRAFT_FOLLOW_UP = "DRAFT_FOLLOW_UP", DRAFT_WORKSPACE = "DRAFT_WORKSPACE", PREVIEW_SETTINGS = "PREVIEW_SETTINGS", WORKSPACE_NOTES = "WORKSPACE_NOTES", REVIEW_COMMENTS = "REVIEW_COMMENTS" }
 
 export type Scratch = { id: string, payload: ScratchPayload, created_at: string, updated_at: string, };
 
@@ -76,7 +229,11 @@ export type CreateImage = { file_path: string, original_name: string, mime_type:
 
 export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
 
-export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, 
+/**
+ * CI status of the workspace's latest PR
+ */
+pr_ci_status: CiStatus | null, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
@@ -90,19 +247,49 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "verificationscript" | "codingagent" | "devserver";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type IndexedNormalizedEntry = { entry_index: number, entry: NormalizedEntry, };
+
+export type NormalizedEntriesPage = { 
+/**
+ * In conversation order
+ */
+entries: Array<IndexedNormalizedEntry>, 
+/**
+ * Whether more entries match past this page, in the direction it was
+ * read: earlier ones unless the query had an `after` cursor
+ */
+has_more: boolean, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
-export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, merge_strategy: MergeStrategy, created_at: string, };
+
+export type MergeStrategy = "squash" | "rebase" | "merge_commit";
 
 export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
-export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
+export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, 
+/**
+ * Combined status of the CI checks on the PR head, if any are configured
+ */
+ci_status: CiStatus | null, 
+/**
+ * Checks that failed on the PR head
+ */
+failing_checks: Array<PrCheck>, };
+
+export type CiStatus = "pending" | "success" | "failure";
+
+/**
+ * A CI check (check run, status context or pipeline job) reported by the git host
+ */
+export type PrCheck = { name: string, url: string | null, };
 
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
@@ -110,6 +297,56 @@ export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue,
 
 export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
 
+export type ApprovalRuleAction = "approve" | "deny" | "ask";
+
+export type ApprovalRuleMatcher = { "type": "file_edit", path_glob: string, } | { "type": "command_run", command_regex: string, } | { "type": "web_fetch", host: string, };
+
+export type ApprovalRule = { 
+/**
+ * Shown in the conversation log when the rule decides a tool call
+ */
+name: string, action: ApprovalRuleAction, 
+/**
+ * Exact tool name reported by the agent; any tool when unset
+ */
+tool_name: string | null, 
+/**
+ * Any action when unset
+ */
+matcher: ApprovalRuleMatcher | null, };
+
+export type ApprovalPolicy = { rules: Array<ApprovalRule>, 
+/**
+ * Applied to tool calls no rule matches
+ */
+default_action: ApprovalRuleAction, 
+/**
+ * How long to wait for a human before the request times out
+ */
+timeout_seconds: bigint, };
+
+export type ApprovalPolicyDecision = { 
+/**
+ * Name of the matched rule, `None` when the policy default applied
+ */
+rule: string | null, action: ApprovalRuleAction, };
+
+export type ApprovalRecordStatus = "pending" | "approved" | "denied" | "timed_out" | "interrupted";
+
+export type Approval = { id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_metadata: { tool_call_id: string }, status: ApprovalRecordStatus, 
+/**
+ * Denial reason given by the user or the approval policy
+ */
+reason: string | null, 
+/**
+ * Approval policy rule that decided the request without asking
+ */
+policy_rule: string | null, 
+/**
+ * Follow-up process started to re-ask after an interruption
+ */
+resumed_execution_process_id: string | null, created_at: string, timeout_at: string, responded_at: string | null, };
+
 export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
  * True when file contents are intentionally omitted (e.g., too large)
@@ -118,7 +355,17 @@ contentOmitted: boolean,
 /**
  * Optional precomputed stats for omitted content
  */
-additions: number | null, deletions: number | null, repoId: string | null, };
+additions: number | null, deletions: number | null, repoId: string | null, 
+/**
+ * Hunks of the change in the order reverts number them; empty when a
+ * side isn't text
+ */
+hunks: Array<DiffHunk>, };
+
+/**
+ * Line ranges of one hunk, 1-based as in a unified diff header
+ */
+export type DiffHunk = { oldStart: number, oldLines: number, newStart: number, newLines: number, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
@@ -216,7 +463,15 @@ export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: st
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
 
-export type MergeTaskAttemptRequest = { repo_id: string, };
+export type MergeTaskAttemptRequest = { repo_id: string, 
+/**
+ * Overrides the repository's configured merge strategy
+ */
+strategy?: MergeStrategy, 
+/**
+ * Overrides the message rendered from the repository's commit template
+ */
+commit_message?: string, };
 
 export type PushTaskAttemptRequest = { repo_id: string, };
 
@@ -234,13 +489,41 @@ export type OpenEditorResponse = { url: string | null, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
+export type TaskDependencies = { 
+/**
+ * Tasks that must be done before this one can start
+ */
+blocked_by: Array<Task>, 
+/**
+ * Tasks waiting for this one
+ */
+blocking: Array<Task>, auto_start: TaskAutoStart | null, };
+
+export type AddTaskDependency = { blocked_by_task_id: string, };
+
+export type SetTaskAutoStart = { executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Base the branch on the parent workspace's branch, so the PR targets it
+ */
+stack_on_parent?: boolean, };
+
+export type CreateParallelTaskAttemptsBody = { task_id: string, 
+/**
+ * One attempt is started per profile, all on the same repos and branches
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Base the branches on the parent workspace's branch, so the PRs target it
+ */
+stack_on_parent?: boolean, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -254,6 +537,16 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
+export type RevertChangesRequest = { repo_id: string, files: Array<RevertSelection>, };
+
+export type RevertChangesResponse = { 
+/**
+ * The "user revert" commit, absent when nothing needed reverting
+ */
+commit_sha: string | null, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
@@ -274,7 +567,27 @@ export type GetPrCommentsQuery = { repo_id: string, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "gitea" | "unknown";
+
+/**
+ * Hostnames of self-hosted git servers whose provider can't be told from the
+ * remote URL alone
+ */
+export type SelfHostedGitHosts = { gitlab: Array<string>, 
+/**
+ * Gitea and Forgejo instances, talked to over their REST API
+ */
+gitea: Array<GiteaHost>, };
+
+export type GiteaHost = { 
+/**
+ * Web URL of the instance, e.g. `https://git.company.com`
+ */
+url: string, 
+/**
+ * Access token sent with every API request
+ */
+token: string | null, };
 
 export type OpenPrInfo = { number: bigint, url: string, title: string, head_branch: string, base_branch: string, };
 
@@ -356,13 +669,84 @@ export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
 export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
 
+/**
+ * How one attempt of a task did, for side-by-side comparison
+ */
+export type TaskAttemptComparison = { workspace: Workspace, 
+/**
+ * Profile of the latest coding agent run
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Changes against the target branches, `None` without a worktree
+ */
+diff_stats: DiffStats | null, 
+/**
+ * Status of the latest verification script run
+ */
+verification_status: ExecutionProcessStatus | null, 
+/**
+ * Seconds from the first process start to the last completion, `None`
+ * while a process is still running
+ */
+duration_seconds: bigint | null, 
+/**
+ * `None` when no turn recorded token usage
+ */
+usage: UsageSummary | null, };
+
+export type PickWinnerResponse = { archived_workspace_ids: Array<string>, };
+
+export type ForkTaskAttemptRequest = { 
+/**
+ * Coding agent turn whose resulting commits the fork starts from
+ */
+process_id: string, };
+
+export type ForkTaskAttemptResponse = { workspace: Workspace, session: Session, 
+/**
+ * Whether the session continues the agent's conversation up to the turn
+ */
+agent_session_forked: boolean, };
+
+export type SendReviewResponse = { 
+/**
+ * Number of comments included in the prompt
+ */
+sent: number, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, 
+/**
+ * Model prices keyed by model name. A key also matches models it is a
+ * prefix of, the longest matching key wins.
+ */
+model_prices: { [key in string]?: ModelPrice }, 
+/**
+ * How many times a failing verification script is handed back to the
+ * coding agent before the workspace is left for review
+ */
+verification_max_iterations: number, 
+/**
+ * Coding agents allowed to run at once across all projects; further
+ * starts wait in a queue. `None` for no limit
+ */
+max_concurrent_coding_agents: number | null, 
+/**
+ * Hostnames of self-hosted git servers, e.g. a company GitLab instance
+ */
+self_hosted_git_hosts: SelfHostedGitHosts, 
+/**
+ * Days the raw logs of finished processes are kept. Normalized
+ * conversations and turn summaries are kept regardless. `None` keeps
+ * raw logs forever
+ */
+log_retention_days: number | null, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -384,6 +768,8 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
+export type ModelPrice = { input_per_mtok: number, output_per_mtok: number, cache_read_per_mtok: number, cache_write_per_mtok: number, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type QueuedMessage = { 
@@ -402,15 +788,75 @@ queued_at: string, };
 
 export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
 
+/**
+ * How a requested execution went ahead
+ */
+export type ExecutionStart = { "type": "started", execution_process: ExecutionProcess, } | { "type": "queued" };
+
+export type UsageGroupBy = "task" | "workspace" | "project" | "executor_profile";
+
+export type UsageQuery = { group_by: UsageGroupBy, project_id: string | null, task_id: string | null, workspace_id: string | null, 
+/**
+ * Inclusive lower bound on the turn creation time
+ */
+from: string | null, 
+/**
+ * Exclusive upper bound on the turn creation time
+ */
+to: string | null, };
+
+export type UsageSummary = { 
+/**
+ * Task, workspace or project ID, or `EXECUTOR` / `EXECUTOR:VARIANT` when
+ * grouping by executor profile
+ */
+key: string, turns: bigint, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, 
+/**
+ * Estimated cost in USD of the turns whose model has a configured price
+ */
+cost_usd: number, 
+/**
+ * Turns that are not included in `cost_usd` because no price is configured
+ * for their model
+ */
+unpriced_turns: bigint, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+/**
+ * A file of the workspace diff to revert, optionally narrowed to some hunks.
+ * A rename is reverted by selecting both its old and new path.
+ */
+export type RevertSelection = { 
+/**
+ * Path relative to the repository root
+ */
+path: string, 
+/**
+ * Indices into the `hunks` of the file's diff; the whole file when absent
+ */
+hunks?: Array<number>, };
+
+export type TranscriptFormat = "markdown" | "json" | "html";
+
+export type Transcript = { schema_version: number, exported_at: string, task_id: string, task_title: string, workspace_id: string, branch: string, sessions: Array<TranscriptSession>, };
+
+export type TranscriptSession = { id: string, executor: string | null, created_at: string, turns: Array<TranscriptTurn>, };
+
+/**
+ * One coding agent turn with its conversation and the commits it produced
+ */
+export type TranscriptTurn = { execution_process_id: string, status: ExecutionProcessStatus, started_at: string, completed_at: string | null, prompt: string | null, summary: string | null, entries: Array<NormalizedEntry>, diffs: Array<TranscriptDiff>, };
+
+export type TranscriptDiff = { repo_name: string, from_commit: string, to_commit: string, unified_diff: string, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest | { "type": "ReviewRequest" } & ReviewRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "VerificationScript" | "DevServer" | "ToolInstallScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -421,9 +867,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_ACP = "CUSTOM_ACP" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_ACP": CustomAcp };
 
 export type SlashCommandDescription = { 
 /**
@@ -453,7 +899,7 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_ACP": CustomAcp } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
@@ -499,6 +945,8 @@ export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type CustomAcp = { append_prompt: AppendPrompt, command: string, args?: Array<string> | null, model?: string | null, mode?: string | null, auto_approve?: boolean | null, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -521,7 +969,17 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Why the follow-up was started without the user asking for it; `None`
+ * for prompts the user sent
+ */
+trigger?: FollowUpTrigger, };
+
+/**
+ * What started an automatic follow-up
+ */
+export type FollowUpTrigger = "verification_failure";
 
 export type ReviewRequest = { executor_profile_id: ExecutorProfileId, context: Array<RepoReviewContext> | null, prompt: string, 
 /**
@@ -543,7 +1001,13 @@ export type NormalizedEntry = { timestamp: string | null, entry_type: Normalized
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens consumed so far by the whole turn, for agents that report it
+ */
+turn_usage?: TurnTokenUsage, };
+
+export type TurnTokenUsage = { model: string | null, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**